@@ -0,0 +1,281 @@
+//! Compares running lint visitors in one shared traversal of the AST, as `Checker` does, with
+//! every lint walking the AST on its own. Run with `cargo bench -p selene-lib`.
+
+use std::{
+    hint::black_box,
+    time::{Duration, Instant},
+};
+
+use selene_lib::{
+    lints::{self, AstContext, Context, Diagnostic, DynLint, Lint},
+    standard_library::StandardLibrary,
+    Checker, CheckerConfig, LintInfo, LintRegistry,
+};
+
+const ITERATIONS: u32 = 20;
+
+// Only runs `pass`, so the lint walks the AST on its own like before visitors were shared
+struct PassOnly<L>(L);
+
+impl<L: Lint + Send + Sync> DynLint for PassOnly<L> {
+    fn pass(
+        &self,
+        ast: &full_moon::ast::Ast,
+        context: &Context,
+        ast_context: &AstContext,
+    ) -> Vec<Diagnostic> {
+        Lint::pass(&self.0, ast, context, ast_context)
+    }
+}
+
+fn register<L: Lint + Send + Sync + 'static>(
+    registry: &mut LintRegistry<serde_json::Value>,
+    name: &'static str,
+) where
+    L::Config: Default,
+    L::Error: 'static,
+{
+    registry.register_dyn(LintInfo::new(name, L::SEVERITY, L::LINT_TYPE), |_| {
+        Ok(Box::new(PassOnly(L::new(L::Config::default())?)))
+    });
+}
+
+fn separate_registry() -> LintRegistry<serde_json::Value> {
+    let mut registry = LintRegistry::empty();
+
+    register::<lints::almost_swapped::AlmostSwappedLint>(&mut registry, "almost_swapped");
+    register::<lints::bad_string_escape::BadStringEscapeLint>(&mut registry, "bad_string_escape");
+    register::<lints::compare_nan::CompareNanLint>(&mut registry, "compare_nan");
+    register::<lints::constant_table_comparison::ConstantTableComparisonLint>(
+        &mut registry,
+        "constant_table_comparison",
+    );
+    register::<lints::deprecated::DeprecatedLint>(&mut registry, "deprecated");
+    register::<lints::divide_by_zero::DivideByZeroLint>(&mut registry, "divide_by_zero");
+    register::<lints::duplicate_keys::DuplicateKeysLint>(&mut registry, "duplicate_keys");
+    register::<lints::empty_if::EmptyIfLint>(&mut registry, "empty_if");
+    register::<lints::empty_loop::EmptyLoopLint>(&mut registry, "empty_loop");
+    register::<lints::global_usage::GlobalLint>(&mut registry, "global_usage");
+    register::<lints::high_cyclomatic_complexity::HighCyclomaticComplexityLint>(
+        &mut registry,
+        "high_cyclomatic_complexity",
+    );
+    register::<lints::if_same_then_else::IfSameThenElseLint>(&mut registry, "if_same_then_else");
+    register::<lints::ifs_same_cond::IfsSameCondLint>(&mut registry, "ifs_same_cond");
+    register::<lints::standard_library::StandardLibraryLint>(
+        &mut registry,
+        "incorrect_standard_library_use",
+    );
+    register::<lints::invalid_lint_filter::InvalidLintFilterLint>(
+        &mut registry,
+        "invalid_lint_filter",
+    );
+    register::<lints::manual_table_clone::ManualTableCloneLint>(
+        &mut registry,
+        "manual_table_clone",
+    );
+    register::<lints::mismatched_arg_count::MismatchedArgCountLint>(
+        &mut registry,
+        "mismatched_arg_count",
+    );
+    register::<lints::multiple_statements::MultipleStatementsLint>(
+        &mut registry,
+        "multiple_statements",
+    );
+    register::<lints::must_use::MustUseLint>(&mut registry, "must_use");
+    register::<lints::parenthese_conditions::ParentheseConditionsLint>(
+        &mut registry,
+        "parenthese_conditions",
+    );
+    register::<lints::shadowing::ShadowingLint>(&mut registry, "shadowing");
+    register::<lints::suspicious_reverse_loop::SuspiciousReverseLoopLint>(
+        &mut registry,
+        "suspicious_reverse_loop",
+    );
+    register::<lints::type_check_inside_call::TypeCheckInsideCallLint>(
+        &mut registry,
+        "type_check_inside_call",
+    );
+    register::<lints::unbalanced_assignments::UnbalancedAssignmentsLint>(
+        &mut registry,
+        "unbalanced_assignments",
+    );
+    register::<lints::undefined_variable::UndefinedVariableLint>(
+        &mut registry,
+        "undefined_variable",
+    );
+    register::<lints::unscoped_variables::UnscopedVariablesLint>(
+        &mut registry,
+        "unscoped_variables",
+    );
+    register::<lints::unused_lint_filter::UnusedLintFilterLint>(
+        &mut registry,
+        "unused_lint_filter",
+    );
+    register::<lints::unused_variable::UnusedVariableLint>(&mut registry, "unused_variable");
+
+    #[cfg(feature = "roblox")]
+    {
+        register::<lints::roblox_incorrect_color3_new_bounds::Color3BoundsLint>(
+            &mut registry,
+            "roblox_incorrect_color3_new_bounds",
+        );
+        register::<lints::roblox_incorrect_roact_usage::IncorrectRoactUsageLint>(
+            &mut registry,
+            "roblox_incorrect_roact_usage",
+        );
+        register::<lints::roblox_suspicious_udim2_new::SuspiciousUDim2NewLint>(
+            &mut registry,
+            "roblox_suspicious_udim2_new",
+        );
+    }
+
+    registry
+}
+
+// Something like a large generated data module, with a function for every entry
+fn generated_source(entries: usize) -> String {
+    let mut source = String::from("local data = {}\n\n");
+
+    for index in 0..entries {
+        source.push_str(&format!(
+            r#"data[{index}] = {{
+	name = "entry_{index}",
+	values = {{ 1, 2, 3, {index} }},
+	nested = {{ enabled = true, weight = {index} / 10 }},
+}}
+
+data[{index}].update = function(self, delta)
+	if self.values[1] > delta then
+		self.values[1] = self.values[1] - delta
+	elseif delta == 0 then
+		return nil
+	end
+
+	for index, value in ipairs(self.values) do
+		print(index, value, string.format("%d", value))
+	end
+
+	return self
+end
+
+"#
+        ));
+    }
+
+    source.push_str("return data\n");
+    source
+}
+
+// Runs are interleaved, so that noise from the rest of the machine hits every checker alike
+fn time(
+    checkers: &[(&str, &Checker<serde_json::Value>)],
+    ast: &full_moon::ast::Ast,
+) -> Vec<Duration> {
+    // Warm up first, so that no checker pays for lazily initialized state
+    for (_, checker) in checkers {
+        black_box(checker.test_on(ast));
+    }
+
+    let mut times = vec![Vec::new(); checkers.len()];
+
+    for _ in 0..ITERATIONS {
+        for ((_, checker), times) in checkers.iter().zip(&mut times) {
+            let start = Instant::now();
+            black_box(checker.test_on(black_box(ast)));
+            times.push(start.elapsed());
+        }
+    }
+
+    checkers
+        .iter()
+        .zip(times)
+        .map(|((name, _), mut times)| {
+            times.sort();
+            let median = times[times.len() / 2];
+            println!("{name:<20} {median:>10.2?}");
+            median
+        })
+        .collect()
+}
+
+fn main() {
+    let source = generated_source(200);
+    let ast = full_moon::parse(&source).expect("generated source didn't parse");
+
+    println!(
+        "linting {} lines, median of {ITERATIONS} runs",
+        source.lines().count()
+    );
+
+    let standard_library = StandardLibrary::from_name("lua51").expect("no lua51 standard library");
+
+    let separate = Checker::with_registry(
+        CheckerConfig::default(),
+        standard_library.clone(),
+        separate_registry(),
+    )
+    .expect("couldn't create checker");
+
+    let shared =
+        Checker::new(CheckerConfig::default(), standard_library).expect("couldn't create checker");
+
+    assert_eq!(
+        separate.lints().count(),
+        shared.lints().count(),
+        "the benchmark doesn't run every built in lint"
+    );
+
+    let diagnostics = |checker: &Checker<_>| {
+        let mut diagnostics = checker
+            .test_on(&ast)
+            .into_iter()
+            .map(|diagnostic| {
+                (
+                    diagnostic.diagnostic.code,
+                    diagnostic.diagnostic.primary_label.range,
+                    diagnostic.diagnostic.message,
+                )
+            })
+            .collect::<Vec<_>>();
+
+        diagnostics.sort();
+        diagnostics
+    };
+
+    assert_eq!(
+        diagnostics(&separate),
+        diagnostics(&shared),
+        "sharing a traversal changed the diagnostics"
+    );
+
+    // Scoping and filtering take the same time either way, so they're timed on their own to see
+    // how much time is spent in lints
+    let baseline = Checker::with_registry(
+        CheckerConfig::default(),
+        StandardLibrary::from_name("lua51").expect("no lua51 standard library"),
+        LintRegistry::empty(),
+    )
+    .expect("couldn't create checker");
+
+    let times = time(
+        &[
+            ("no lints", &baseline),
+            ("separate traversals", &separate),
+            ("shared traversal", &shared),
+        ],
+        &ast,
+    );
+    // Timing is noisy, so a lint pass can come out faster than no lints at all
+    let (baseline_time, separate_time, shared_time) = (
+        times[0].as_secs_f64(),
+        times[1].as_secs_f64(),
+        times[2].as_secs_f64(),
+    );
+
+    println!(
+        "shared traversal is {:.2}x as fast, or {:.2}x as fast for the time spent in lints",
+        separate_time / shared_time,
+        (separate_time - baseline_time) / (shared_time - baseline_time)
+    );
+}
@@ -0,0 +1,296 @@
+//! Lints declared in `[[custom-lints]]`, which ban globals and calls without having to write a
+//! lint or a plugin. Names are matched the same way as the standard library, so `debug.setmetatable`
+//! only matches when `debug` is a global.
+
+use full_moon::{ast, node::Node, tokenizer::Position, visitors::Visitor};
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    ast_util::{name_paths::*, scopes::ScopeManager},
+    lints::{
+        intern_lint_name,
+        standard_library::{get_argument_types, maybe_more_arguments},
+        AstContext, Context, Diagnostic, DynLint, Label, LintType, LintVisitor,
+    },
+    standard_library::ArgumentType,
+    LintInfo, LintVariation,
+};
+
+/// A lint declared in `[[custom-lints]]`. Each one has exactly one pattern: either `global`, or
+/// `call` along with either `arguments` or `required-arguments`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+pub struct CustomLintConfig {
+    /// The name the lint is configured and filtered by.
+    pub name: String,
+    /// The message of every diagnostic the lint reports.
+    pub message: String,
+    /// The severity of the lint when it is not configured in `[lints]`, `warn` if not given.
+    pub severity: Option<LintVariation>,
+    /// The category of the lint, `style` if not given.
+    pub category: Option<LintType>,
+
+    /// Bans any use of a global, such as `debug.setmetatable`, including its fields.
+    pub global: Option<String>,
+    /// Bans calls to a global function, such as `require`.
+    pub call: Option<String>,
+    /// Only bans calls where every argument matches its constraint.
+    #[serde(default)]
+    pub arguments: Vec<ArgumentConstraint>,
+    /// Instead of banning calls, requires them to pass at least this many arguments.
+    pub required_arguments: Option<usize>,
+}
+
+/// A constraint on an argument of a call in `[[custom-lints]]`.
+#[derive(Clone, Debug, Deserialize, Serialize)]
+#[serde(deny_unknown_fields)]
+pub struct ArgumentConstraint {
+    /// Which argument this is, starting from 1.
+    pub index: usize,
+    /// The type the argument has to be, written the same way as in standard libraries, such as
+    /// `"number"` or `["socket"]` for specific strings.
+    #[serde(rename = "type")]
+    pub argument_type: ArgumentType,
+}
+
+enum Pattern {
+    Global(Vec<String>),
+    Call {
+        path: Vec<String>,
+        arguments: Vec<ArgumentConstraint>,
+    },
+    RequiredArguments {
+        path: Vec<String>,
+        count: usize,
+    },
+}
+
+pub(crate) struct CustomLint {
+    name: &'static str,
+    message: String,
+    pattern: Pattern,
+}
+
+fn parse_path(path: &str) -> Result<Vec<String>, String> {
+    let names = path.split('.').map(ToOwned::to_owned).collect::<Vec<_>>();
+
+    if names.iter().any(|name| {
+        name.is_empty()
+            || !name
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '_')
+    }) {
+        return Err(format!(
+            "`{path}` is not a name path, such as `debug.setmetatable`"
+        ));
+    }
+
+    Ok(names)
+}
+
+impl CustomLint {
+    /// Creates the lint declared by a config, along with its information. `taken` is whether a
+    /// name is already used by another lint.
+    pub(crate) fn new(
+        config: &CustomLintConfig,
+        taken: impl Fn(&str) -> bool,
+    ) -> Result<(LintInfo, Self), String> {
+        if config.name.is_empty()
+            || !config
+                .name
+                .chars()
+                .all(|character| character.is_ascii_alphanumeric() || character == '_')
+        {
+            return Err(
+                "custom lints must be named with only letters, numbers, and underscores".to_owned(),
+            );
+        }
+
+        if taken(&config.name) {
+            return Err("a lint with the same name already exists".to_owned());
+        }
+
+        let pattern = match (&config.global, &config.call) {
+            (Some(global), None) => {
+                if !config.arguments.is_empty() || config.required_arguments.is_some() {
+                    return Err(
+                        "`arguments` and `required-arguments` can only be used with `call`"
+                            .to_owned(),
+                    );
+                }
+
+                Pattern::Global(parse_path(global)?)
+            }
+
+            (None, Some(call)) => {
+                let path = parse_path(call)?;
+
+                if let Some(count) = config.required_arguments {
+                    if !config.arguments.is_empty() {
+                        return Err(
+                            "`arguments` and `required-arguments` can't be used together"
+                                .to_owned(),
+                        );
+                    }
+
+                    Pattern::RequiredArguments { path, count }
+                } else {
+                    if config
+                        .arguments
+                        .iter()
+                        .any(|constraint| constraint.index == 0)
+                    {
+                        return Err("argument indexes start from 1".to_owned());
+                    }
+
+                    Pattern::Call {
+                        path,
+                        arguments: config.arguments.clone(),
+                    }
+                }
+            }
+
+            _ => return Err("custom lints need either `global` or `call`".to_owned()),
+        };
+
+        let name = intern_lint_name(&config.name);
+
+        Ok((
+            LintInfo::new(
+                name,
+                config.severity.unwrap_or(LintVariation::Warn).to_severity(),
+                config.category.unwrap_or(LintType::Style),
+            ),
+            CustomLint {
+                name,
+                message: config.message.clone(),
+                pattern,
+            },
+        ))
+    }
+}
+
+impl DynLint for CustomLint {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        ast_context: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(CustomLintVisitor {
+            lint: self,
+            scope_manager: &ast_context.scope_manager,
+            ranges: Vec::new(),
+        }))
+    }
+}
+
+struct CustomLintVisitor<'a> {
+    lint: &'a CustomLint,
+    scope_manager: &'a ScopeManager,
+    ranges: Vec<(Position, Position)>,
+}
+
+impl LintVisitor for CustomLintVisitor<'_> {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.ranges
+            .into_iter()
+            .map(|(start, end)| {
+                Diagnostic::new(
+                    self.lint.name,
+                    self.lint.message.clone(),
+                    Label::new((start.bytes() as u32, end.bytes() as u32)),
+                )
+            })
+            .collect()
+    }
+}
+
+impl CustomLintVisitor<'_> {
+    // Only globals are matched, so locals that share their name are left alone
+    fn is_global(&self, node: &impl Node) -> bool {
+        match self
+            .scope_manager
+            .reference_at_byte(node.start_position().unwrap().bytes())
+        {
+            Some(reference) => reference.resolved.is_none(),
+            None => true,
+        }
+    }
+}
+
+impl Visitor for CustomLintVisitor<'_> {
+    fn visit_expression(&mut self, expression: &ast::Expression) {
+        let Pattern::Global(banned) = &self.lint.pattern else {
+            return;
+        };
+
+        if !self.is_global(expression) {
+            return;
+        }
+
+        if let Some(name_path) = name_path(expression) {
+            if name_path.starts_with(banned) {
+                self.ranges.push(expression.range().unwrap());
+            }
+        }
+    }
+
+    fn visit_function_call(&mut self, call: &ast::FunctionCall) {
+        if !self.is_global(call) {
+            return;
+        }
+
+        let mut keep_going = true;
+        let mut suffixes: Vec<&ast::Suffix> = call
+            .suffixes()
+            .take_while(|suffix| take_while_keep_going(suffix, &mut keep_going))
+            .collect();
+
+        let name_path = match name_path_from_prefix_suffix(call.prefix(), suffixes.iter().copied())
+        {
+            Some(name_path) => name_path,
+            None => return,
+        };
+
+        let function_args = match suffixes.pop() {
+            Some(ast::Suffix::Call(ast::Call::AnonymousCall(args))) => args,
+            Some(ast::Suffix::Call(ast::Call::MethodCall(method_call))) => method_call.args(),
+            _ => return,
+        };
+
+        match &self.lint.pattern {
+            Pattern::Global(banned) => {
+                if name_path.starts_with(banned) {
+                    self.ranges.push(call.range().unwrap());
+                }
+            }
+
+            Pattern::Call { path, arguments } => {
+                if &name_path != path {
+                    return;
+                }
+
+                let argument_types = get_argument_types(function_args);
+
+                if arguments.iter().all(|constraint| {
+                    matches!(
+                        argument_types.get(constraint.index - 1),
+                        Some((_, Some(passed_type))) if passed_type.matches(&constraint.argument_type)
+                    )
+                }) {
+                    self.ranges.push(call.range().unwrap());
+                }
+            }
+
+            Pattern::RequiredArguments { path, count } => {
+                if &name_path == path
+                    && get_argument_types(function_args).len() < *count
+                    && !maybe_more_arguments(function_args)
+                {
+                    self.ranges.push(call.range().unwrap());
+                }
+            }
+        }
+    }
+}
@@ -3,17 +3,27 @@
     feature = "force_exhaustive_checks",
     feature(non_exhaustive_omitted_patterns_lint)
 )]
-use std::{collections::HashMap, error::Error, fmt};
+use std::{
+    collections::HashMap,
+    error::Error,
+    fmt,
+    sync::atomic::{AtomicUsize, Ordering},
+};
 
 use full_moon::ast::Ast;
 use serde::{
     de::{DeserializeOwned, Deserializer},
-    Deserialize,
+    Deserialize, Serialize,
 };
 
 mod ast_util;
+pub mod custom_lints;
 mod lint_filtering;
 pub mod lints;
+pub mod luacheck;
+mod multi_visitor;
+#[cfg(feature = "plugins")]
+pub mod plugins;
 mod possible_std;
 pub mod standard_library;
 mod text;
@@ -24,7 +34,8 @@ mod test_util;
 #[cfg(test)]
 mod test_full_runs;
 
-use lints::{AstContext, Context, Diagnostic, Lint, Severity};
+pub use lint_filtering::{lint_filters, LintFilter};
+use lints::{AstContext, Context, Diagnostic, DynLint, Lint, LintType, Severity};
 use standard_library::StandardLibrary;
 
 #[derive(Debug)]
@@ -57,16 +68,30 @@ impl fmt::Display for CheckerError {
 
 impl Error for CheckerError {}
 
-#[derive(Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(default)]
 #[serde(rename_all = "kebab-case")]
 #[serde(deny_unknown_fields)]
 pub struct CheckerConfig<V> {
+    /// Another config this one builds on, relative to this one.
+    /// It's merged in by whatever reads the config, so the checker never sees it.
+    pub extends: Option<String>,
     pub config: HashMap<String, V>,
     #[serde(alias = "rules")]
     pub lints: HashMap<String, LintVariation>,
     pub std: Option<String>,
     pub exclude: Vec<String>,
+    pub overrides: Vec<ConfigOverride<V>>,
+    pub cache: bool,
+    /// Whether `-- luacheck:` comments filter lints, for projects migrating from luacheck.
+    pub luacheck_directives: bool,
+    /// Whether `-- selene: allow` comments must explain themselves with `-- reason: ...`.
+    pub require_filter_reasons: bool,
+    /// Lints written in Lua, as paths relative to the config. Reading them is up to whatever
+    /// reads the config, since the checker only sees the lints it is given.
+    pub plugins: Vec<String>,
+    /// Lints declared in `[[custom-lints]]`, which run after every registered lint.
+    pub custom_lints: Vec<custom_lints::CustomLintConfig>,
 
     // Not locked behind Roblox feature so that selene.toml for Roblox will
     // run even without it.
@@ -77,22 +102,85 @@ impl<V> CheckerConfig<V> {
     pub fn std(&self) -> &str {
         self.std.as_deref().unwrap_or("lua51")
     }
+
+    /// How a lint is configured in `[lints]`, if at all.
+    /// Lints configured by name take priority over their category.
+    pub fn lint_variation(&self, name: &str, lint_type: LintType) -> Option<LintVariation> {
+        self.lints
+            .get(name)
+            .or_else(|| self.lints.get(lint_type.name()))
+            .copied()
+    }
+}
+
+impl<V: Clone> CheckerConfig<V> {
+    /// The config for files that the overrides apply to, in order.
+    pub fn with_overrides<'a>(
+        &self,
+        overrides: impl IntoIterator<Item = &'a ConfigOverride<V>>,
+    ) -> Self
+    where
+        V: 'a,
+    {
+        let mut config = CheckerConfig {
+            overrides: Vec::new(),
+            ..self.clone()
+        };
+
+        for config_override in overrides {
+            config.config.extend(config_override.config.clone());
+            config.lints.extend(config_override.lints.clone());
+
+            if let Some(std) = &config_override.std {
+                config.std = Some(format!("{}+{std}", config.std()));
+            }
+        }
+
+        config
+    }
 }
 
 impl<V> Default for CheckerConfig<V> {
     fn default() -> Self {
         CheckerConfig {
+            extends: None,
             config: HashMap::new(),
             lints: HashMap::new(),
             std: None,
             exclude: Vec::new(),
+            overrides: Vec::new(),
+            cache: false,
+            luacheck_directives: false,
+            require_filter_reasons: false,
+            plugins: Vec::new(),
+            custom_lints: Vec::new(),
 
             roblox_std_source: RobloxStdSource::default(),
         }
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+/// Changes to the config that only apply to some files, from `[[overrides]]`.
+#[derive(Clone, Deserialize, Serialize)]
+#[serde(rename_all = "kebab-case")]
+#[serde(deny_unknown_fields)]
+pub struct ConfigOverride<V> {
+    /// Globs of the files this applies to.
+    pub files: Vec<String>,
+
+    /// Replaces the whole configuration of each lint given.
+    #[serde(default = "HashMap::new")]
+    pub config: HashMap<String, V>,
+
+    #[serde(default, alias = "rules")]
+    pub lints: HashMap<String, LintVariation>,
+
+    /// Standard libraries to chain on top of the config's own.
+    #[serde(default)]
+    pub std: Option<String>,
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum LintVariation {
     Allow,
@@ -110,7 +198,7 @@ impl LintVariation {
     }
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum RobloxStdSource {
     Floating,
@@ -123,210 +211,408 @@ impl Default for RobloxStdSource {
     }
 }
 
-macro_rules! use_lints {
+/// The lints a [`Checker`] runs, along with how to create each of them from its configuration.
+/// The default registry has every lint built into selene. Lints can be removed from it, and lints
+/// of your own, such as ones specific to a project, can be registered alongside them.
+pub struct LintRegistry<V> {
+    lints: Vec<(LintInfo, LintConstructor<V>)>,
+}
+
+type LintConstructor<V> = Box<dyn Fn(Option<V>) -> Result<Box<dyn DynLint>, CheckerErrorProblem>>;
+
+impl<V> LintRegistry<V> {
+    /// A registry without any lints.
+    pub fn empty() -> Self {
+        Self { lints: Vec::new() }
+    }
+
+    /// Registers a lint under the name it is configured and filtered by. Lints run in the order
+    /// they are registered, unless they replace a lint that was registered with the same name.
+    pub fn register<L: Lint + Send + Sync + 'static>(&mut self, name: &'static str)
+    where
+        V: 'static + for<'de> Deserializer<'de>,
+        L::Config: Default,
     {
-        $(
-            $lint_name:ident: $lint_path:ty,
-        )+
-
-        $(
-            #[$meta:meta]
-            {
-                $($meta_lint_name:ident: $meta_lint_path:ty,)+
-            },
-        )+
-    } => {
-        lazy_static::lazy_static! {
-            static ref ALL_LINTS: Vec<&'static str> = vec![
-                $(
-                    stringify!($lint_name),
-                )+
-
-                $(
-                    $(
-                        #[$meta]
-                        stringify!($meta_lint_name),
-                    )+
-                )+
-            ];
+        let info = LintInfo {
+            name,
+            severity: L::SEVERITY,
+            lint_type: L::LINT_TYPE,
+            default_config: default_config::<L>,
+        };
+
+        self.insert(
+            info,
+            Box::new(|config: Option<V>| {
+                let config = match config {
+                    Some(config) => L::Config::deserialize(config).map_err(|error| {
+                        CheckerErrorProblem::ConfigDeserializeError(Box::new(error))
+                    })?,
+
+                    None => L::Config::default(),
+                };
+
+                let lint = L::new(config)
+                    .map_err(|error| CheckerErrorProblem::LintNewError(Box::new(error)))?;
+
+                Ok(Box::new(lint) as Box<dyn DynLint>)
+            }),
+        );
+    }
+
+    /// Registers a lint that doesn't implement [`Lint`], such as one that is only known at runtime.
+    /// The constructor is given the lint's entry in `[config]`, if there is one.
+    pub fn register_dyn(
+        &mut self,
+        info: LintInfo,
+        constructor: impl Fn(Option<V>) -> Result<Box<dyn DynLint>, Box<dyn Error>> + 'static,
+    ) {
+        self.insert(
+            info,
+            Box::new(move |config| constructor(config).map_err(CheckerErrorProblem::LintNewError)),
+        );
+    }
+
+    /// Removes the lint registered under a name, returning whether there was one.
+    pub fn remove(&mut self, name: &str) -> bool {
+        let length = self.lints.len();
+        self.lints.retain(|(info, _)| info.name != name);
+        self.lints.len() != length
+    }
+
+    /// Every registered lint, in the order they run.
+    pub fn lints(&self) -> impl Iterator<Item = &LintInfo> {
+        self.lints.iter().map(|(info, _)| info)
+    }
+
+    fn insert(&mut self, info: LintInfo, constructor: LintConstructor<V>) {
+        match self
+            .lints
+            .iter_mut()
+            .find(|(registered, _)| registered.name == info.name)
+        {
+            Some(registered) => *registered = (info, constructor),
+            None => self.lints.push((info, constructor)),
+        }
+    }
+}
+
+impl<V: 'static + for<'de> Deserializer<'de>> Default for LintRegistry<V> {
+    fn default() -> Self {
+        let mut registry = Self::empty();
+
+        registry.register::<lints::almost_swapped::AlmostSwappedLint>("almost_swapped");
+        registry.register::<lints::bad_string_escape::BadStringEscapeLint>("bad_string_escape");
+        registry.register::<lints::compare_nan::CompareNanLint>("compare_nan");
+        registry.register::<lints::constant_table_comparison::ConstantTableComparisonLint>(
+            "constant_table_comparison",
+        );
+        registry.register::<lints::deprecated::DeprecatedLint>("deprecated");
+        registry.register::<lints::divide_by_zero::DivideByZeroLint>("divide_by_zero");
+        registry.register::<lints::duplicate_keys::DuplicateKeysLint>("duplicate_keys");
+        registry.register::<lints::empty_if::EmptyIfLint>("empty_if");
+        registry.register::<lints::empty_loop::EmptyLoopLint>("empty_loop");
+        registry.register::<lints::global_usage::GlobalLint>("global_usage");
+        registry.register::<lints::high_cyclomatic_complexity::HighCyclomaticComplexityLint>(
+            "high_cyclomatic_complexity",
+        );
+        registry.register::<lints::if_same_then_else::IfSameThenElseLint>("if_same_then_else");
+        registry.register::<lints::ifs_same_cond::IfsSameCondLint>("ifs_same_cond");
+        registry.register::<lints::standard_library::StandardLibraryLint>(
+            "incorrect_standard_library_use",
+        );
+        registry
+            .register::<lints::invalid_lint_filter::InvalidLintFilterLint>("invalid_lint_filter");
+        registry.register::<lints::manual_table_clone::ManualTableCloneLint>("manual_table_clone");
+        registry.register::<lints::mismatched_arg_count::MismatchedArgCountLint>(
+            "mismatched_arg_count",
+        );
+        registry
+            .register::<lints::multiple_statements::MultipleStatementsLint>("multiple_statements");
+        registry.register::<lints::must_use::MustUseLint>("must_use");
+        registry.register::<lints::parenthese_conditions::ParentheseConditionsLint>(
+            "parenthese_conditions",
+        );
+        registry.register::<lints::shadowing::ShadowingLint>("shadowing");
+        registry.register::<lints::suspicious_reverse_loop::SuspiciousReverseLoopLint>(
+            "suspicious_reverse_loop",
+        );
+        registry.register::<lints::type_check_inside_call::TypeCheckInsideCallLint>(
+            "type_check_inside_call",
+        );
+        registry.register::<lints::unbalanced_assignments::UnbalancedAssignmentsLint>(
+            "unbalanced_assignments",
+        );
+        registry.register::<lints::undefined_variable::UndefinedVariableLint>("undefined_variable");
+        registry.register::<lints::unscoped_variables::UnscopedVariablesLint>("unscoped_variables");
+        registry.register::<lints::unused_lint_filter::UnusedLintFilterLint>("unused_lint_filter");
+        registry.register::<lints::unused_variable::UnusedVariableLint>("unused_variable");
+
+        #[cfg(feature = "roblox")]
+        {
+            registry.register::<lints::roblox_incorrect_color3_new_bounds::Color3BoundsLint>(
+                "roblox_incorrect_color3_new_bounds",
+            );
+            registry.register::<lints::roblox_incorrect_roact_usage::IncorrectRoactUsageLint>(
+                "roblox_incorrect_roact_usage",
+            );
+            registry.register::<lints::roblox_suspicious_udim2_new::SuspiciousUDim2NewLint>(
+                "roblox_suspicious_udim2_new",
+            );
+        }
+
+        registry
+    }
+}
+
+lazy_static::lazy_static! {
+    static ref ALL_LINTS: Vec<LintInfo> = LintRegistry::<serde_json::Value>::default()
+        .lints()
+        .copied()
+        .collect();
+}
+
+pub struct Checker<V: 'static + DeserializeOwned> {
+    config: CheckerConfig<V>,
+    context: Context,
+    lints: Vec<(LintInfo, Box<dyn DynLint>)>,
+}
+
+impl<V: 'static + DeserializeOwned> Checker<V> {
+    /// Creates a checker that runs every lint built into selene.
+    pub fn new(
+        config: CheckerConfig<V>,
+        standard_library: StandardLibrary,
+    ) -> Result<Self, CheckerError>
+    where
+        V: for<'de> Deserializer<'de>,
+    {
+        Self::with_registry(config, standard_library, LintRegistry::default())
+    }
+
+    /// Creates a checker that runs the lints in a registry.
+    // TODO: Be more strict about config? Make sure all keys exist
+    pub fn with_registry(
+        mut config: CheckerConfig<V>,
+        standard_library: StandardLibrary,
+        registry: LintRegistry<V>,
+    ) -> Result<Self, CheckerError> {
+        let mut lints = registry
+            .lints
+            .into_iter()
+            .map(|(info, constructor)| {
+                constructor(config.config.remove(info.name))
+                    .map(|lint| (info, lint))
+                    .map_err(|problem| CheckerError {
+                        name: info.name,
+                        problem,
+                    })
+            })
+            .collect::<Result<Vec<_>, _>>()?;
+
+        for custom_lint in &config.custom_lints {
+            let (info, lint) = custom_lints::CustomLint::new(custom_lint, |name| {
+                lints.iter().any(|(info, _)| info.name == name)
+            })
+            .map_err(|error| CheckerError {
+                name: lints::intern_lint_name(&custom_lint.name),
+                problem: CheckerErrorProblem::LintNewError(error.into()),
+            })?;
+
+            lints.push((info, Box::new(lint)));
         }
 
-        pub struct Checker<V: 'static + DeserializeOwned> {
-            config: CheckerConfig<V>,
-            context: Context,
+        Ok(Self {
+            lints,
 
-            $(
-                $lint_name: $lint_path,
-            )+
+            context: Context {
+                standard_library,
+                user_set_standard_library: config
+                    .std
+                    .as_ref()
+                    .map(|std_text| std_text.split('+').map(ToOwned::to_owned).collect()),
+            },
 
-            $(
-                $(
-                    #[$meta]
-                    $meta_lint_name: $meta_lint_path,
-                )+
-            )+
+            config,
+        })
+    }
+
+    pub fn test_on(&self, ast: &Ast) -> Vec<CheckerDiagnostic> {
+        self.test_on_with_threads(ast, 1)
+    }
+
+    /// Like [`Checker::test_on`], but runs lints on up to `threads` threads, which speeds up
+    /// very large files. The diagnostics are the same, and in the same order.
+    pub fn test_on_with_threads(&self, ast: &Ast, threads: usize) -> Vec<CheckerDiagnostic> {
+        let mut diagnostics = Vec::new();
+
+        let ast_context = AstContext::from_ast(ast);
+
+        for ((info, _), lint_pass) in
+            self.lints
+                .iter()
+                .zip(self.lint_passes(ast, &ast_context, threads))
+        {
+            let severity = info.severity_with(&self.config);
+
+            diagnostics.extend(lint_pass.into_iter().map(|diagnostic| CheckerDiagnostic {
+                diagnostic,
+                severity,
+            }));
         }
 
-        impl<V: 'static + DeserializeOwned> Checker<V> {
-            // TODO: Be more strict about config? Make sure all keys exist
-            pub fn new(
-                mut config: CheckerConfig<V>,
-                standard_library: StandardLibrary,
-            ) -> Result<Self, CheckerError> where V: for<'de> Deserializer<'de> {
-                macro_rules! lint_field {
-                    ($name:ident, $path:ty) => {{
-                        let lint_name = stringify!($name);
-
-                        let lint = <$path>::new({
-                            match config.config.remove(lint_name) {
-                                Some(entry_generic) => {
-                                    <$path as Lint>::Config::deserialize(entry_generic).map_err(|error| {
-                                        CheckerError {
-                                            name: lint_name,
-                                            problem: CheckerErrorProblem::ConfigDeserializeError(Box::new(error)),
-                                        }
-                                    })?
-                                }
-
-                                None => {
-                                    <$path as Lint>::Config::default()
-                                }
-                            }
-                        }).map_err(|error| {
-                            CheckerError {
-                                name: stringify!($name),
-                                problem: CheckerErrorProblem::LintNewError(Box::new(error)),
-                            }
-                        })?;
-
-                        lint
-                    }};
+        let lint_names = self.lints().map(|lint| lint.name).collect::<Vec<_>>();
+
+        diagnostics = lint_filtering::filter_diagnostics(
+            ast,
+            diagnostics,
+            &lint_names,
+            self.lint_severity("invalid_lint_filter"),
+            self.lint_severity("unused_lint_filter"),
+            self.config.luacheck_directives,
+            self.config.require_filter_reasons,
+        );
+
+        diagnostics
+    }
+
+    // Lints with visitors are run together in one traversal of the AST on this thread, while the
+    // lints that implement `pass` are shared between the rest. Their diagnostics are put back in
+    // the order the lints were registered, so the output never depends on the threads.
+    fn lint_passes(
+        &self,
+        ast: &Ast,
+        ast_context: &AstContext,
+        threads: usize,
+    ) -> Vec<Vec<Diagnostic>> {
+        let mut visitor_indexes = Vec::new();
+        let mut visitors = Vec::new();
+        let mut pass_indexes = Vec::new();
+
+        for (index, (_, lint)) in self.lints.iter().enumerate() {
+            match lint.visitor(&self.context, ast_context) {
+                Some(visitor) => {
+                    visitor_indexes.push(index);
+                    visitors.push(visitor);
                 }
 
-                Ok(Self {
-                    $(
-                        $lint_name: {
-                            lint_field!($lint_name, $lint_path)
-                        },
-                    )+
-                    $(
-                        $(
-                            #[$meta]
-                            $meta_lint_name: {
-                                lint_field!($meta_lint_name, $meta_lint_path)
-                            },
-                        )+
-                    )+
-
-                    context: Context {
-                        standard_library,
-                        user_set_standard_library: config.std.as_ref().map(|std_text| {
-                            std_text.split('+').map(ToOwned::to_owned).collect()
-                        }),
-                    },
-
-                    config,
-                })
+                None => pass_indexes.push(index),
             }
+        }
 
-            pub fn test_on(&self, ast: &Ast) -> Vec<CheckerDiagnostic> {
-                let mut diagnostics = Vec::new();
+        let next_pass = AtomicUsize::new(0);
+        // The config is left out, since it doesn't have to be shared between threads
+        let (lints, context) = (&self.lints, &self.context);
 
-                let ast_context = AstContext::from_ast(ast);
+        let run_passes = || {
+            let mut lint_passes = Vec::new();
 
-                macro_rules! check_lint {
-                    ($name:ident) => {
-                        let lint = &self.$name;
+            while let Some(&index) = pass_indexes.get(next_pass.fetch_add(1, Ordering::Relaxed)) {
+                profiling::scope!(&format!("lint: {}", lints[index].0.name));
+                lint_passes.push((index, lints[index].1.pass(ast, context, ast_context)));
+            }
 
-                        let lint_pass = {
-                            profiling::scope!(&format!("lint: {}", stringify!($name)));
-                            lint.pass(ast, &self.context, &ast_context)
-                        };
+            lint_passes
+        };
 
-                        diagnostics.extend(&mut lint_pass.into_iter().map(|diagnostic| {
-                            CheckerDiagnostic {
-                                diagnostic,
-                                severity: self.get_lint_severity(lint, stringify!($name)),
-                            }
-                        }));
-                    };
-                }
+        let mut lint_passes = self.lints.iter().map(|_| Vec::new()).collect::<Vec<_>>();
+
+        std::thread::scope(|scope| {
+            let workers = (1..threads.min(pass_indexes.len() + 1))
+                .map(|_| scope.spawn(run_passes))
+                .collect::<Vec<_>>();
 
-                $(
-                    check_lint!($lint_name);
-                )+
-
-                $(
-                    $(
-                        #[$meta]
-                        {
-                            check_lint!($meta_lint_name);
-                        }
-                    )+
-                )+
-
-                diagnostics = lint_filtering::filter_diagnostics(
-                    ast,
-                    diagnostics,
-                    self.get_lint_severity(&self.invalid_lint_filter, "invalid_lint_filter"),
-                );
-
-                diagnostics
+            let visitor_passes = {
+                profiling::scope!("lint visitors");
+                multi_visitor::visit_ast(ast, visitors)
+            };
+
+            for (index, lint_pass) in visitor_indexes.into_iter().zip(visitor_passes) {
+                lint_passes[index] = lint_pass;
             }
 
-            fn get_lint_severity<R: Lint>(&self, _lint: &R, name: &'static str) -> Severity {
-                match self.config.lints.get(name) {
-                    Some(variation) => variation.to_severity(),
-                    None => R::SEVERITY,
-                }
+            // Once the visitors are done, this thread helps with whatever passes are left
+            let mut finished = run_passes();
+
+            for worker in workers {
+                finished.extend(worker.join().expect("lint panicked"));
             }
-        }
-    };
+
+            for (index, lint_pass) in finished {
+                lint_passes[index] = lint_pass;
+            }
+        });
+
+        lint_passes
+    }
+
+    /// Every lint the checker runs, in order.
+    pub fn lints(&self) -> impl Iterator<Item = &LintInfo> {
+        self.lints.iter().map(|(info, _)| info)
+    }
+
+    // Lints that were removed from the registry are never reported
+    fn lint_severity(&self, name: &str) -> Severity {
+        self.lints()
+            .find(|lint| lint.name == name)
+            .map_or(Severity::Allow, |lint| lint.severity_with(&self.config))
+    }
 }
 
-#[derive(Debug)]
+#[derive(Debug, Deserialize, Serialize)]
 pub struct CheckerDiagnostic {
     pub diagnostic: Diagnostic,
     pub severity: Severity,
 }
 
-pub fn lint_exists(name: &str) -> bool {
-    ALL_LINTS.contains(&name)
+/// Information about a lint that doesn't depend on its configuration.
+#[derive(Clone, Copy, Debug)]
+pub struct LintInfo {
+    pub name: &'static str,
+    /// The severity of the lint when it is not configured by the user
+    pub severity: Severity,
+    pub lint_type: LintType,
+    default_config: fn() -> serde_json::Value,
 }
 
-use_lints! {
-    almost_swapped: lints::almost_swapped::AlmostSwappedLint,
-    bad_string_escape: lints::bad_string_escape::BadStringEscapeLint,
-    compare_nan: lints::compare_nan::CompareNanLint,
-    constant_table_comparison: lints::constant_table_comparison::ConstantTableComparisonLint,
-    deprecated: lints::deprecated::DeprecatedLint,
-    divide_by_zero: lints::divide_by_zero::DivideByZeroLint,
-    duplicate_keys: lints::duplicate_keys::DuplicateKeysLint,
-    empty_if: lints::empty_if::EmptyIfLint,
-    empty_loop: lints::empty_loop::EmptyLoopLint,
-    global_usage: lints::global_usage::GlobalLint,
-    high_cyclomatic_complexity: lints::high_cyclomatic_complexity::HighCyclomaticComplexityLint,
-    if_same_then_else: lints::if_same_then_else::IfSameThenElseLint,
-    ifs_same_cond: lints::ifs_same_cond::IfsSameCondLint,
-    incorrect_standard_library_use: lints::standard_library::StandardLibraryLint,
-    invalid_lint_filter: lints::invalid_lint_filter::InvalidLintFilterLint,
-    manual_table_clone: lints::manual_table_clone::ManualTableCloneLint,
-    mismatched_arg_count: lints::mismatched_arg_count::MismatchedArgCountLint,
-    multiple_statements: lints::multiple_statements::MultipleStatementsLint,
-    must_use: lints::must_use::MustUseLint,
-    parenthese_conditions: lints::parenthese_conditions::ParentheseConditionsLint,
-    shadowing: lints::shadowing::ShadowingLint,
-    suspicious_reverse_loop: lints::suspicious_reverse_loop::SuspiciousReverseLoopLint,
-    type_check_inside_call: lints::type_check_inside_call::TypeCheckInsideCallLint,
-    unbalanced_assignments: lints::unbalanced_assignments::UnbalancedAssignmentsLint,
-    undefined_variable: lints::undefined_variable::UndefinedVariableLint,
-    unscoped_variables: lints::unscoped_variables::UnscopedVariablesLint,
-    unused_variable: lints::unused_variable::UnusedVariableLint,
-
-    #[cfg(feature = "roblox")]
-    {
-        roblox_incorrect_color3_new_bounds: lints::roblox_incorrect_color3_new_bounds::Color3BoundsLint,
-        roblox_incorrect_roact_usage: lints::roblox_incorrect_roact_usage::IncorrectRoactUsageLint,
-        roblox_suspicious_udim2_new: lints::roblox_suspicious_udim2_new::SuspiciousUDim2NewLint,
-    },
+impl LintInfo {
+    /// Information for a lint registered with [`LintRegistry::register_dyn`], whose default
+    /// configuration is `null`.
+    pub fn new(name: &'static str, severity: Severity, lint_type: LintType) -> Self {
+        LintInfo {
+            name,
+            severity,
+            lint_type,
+            default_config: || serde_json::Value::Null,
+        }
+    }
+
+    /// The configuration the lint uses when it is not configured by the user,
+    /// `null` if it can't be configured.
+    pub fn default_config(&self) -> serde_json::Value {
+        (self.default_config)()
+    }
+
+    /// The severity of the lint with a config, after applying the severity of its category.
+    pub fn severity_with<V>(&self, config: &CheckerConfig<V>) -> Severity {
+        match config.lint_variation(self.name, self.lint_type) {
+            Some(variation) => variation.to_severity(),
+            None => self.severity,
+        }
+    }
+}
+
+fn default_config<R: Lint>() -> serde_json::Value
+where
+    R::Config: Default,
+{
+    serde_json::to_value(R::Config::default()).expect("couldn't serialize lint config")
+}
+
+/// Every lint built into selene.
+pub fn all_lints() -> &'static [LintInfo] {
+    &ALL_LINTS
+}
+
+pub fn lint_exists(name: &str) -> bool {
+    ALL_LINTS.iter().any(|lint| lint.name == name)
 }
@@ -3,14 +3,27 @@ use crate::{
         first_code,
         visit_nodes::{NodeVisitor, VisitorType},
     },
-    lint_exists,
     lints::{Diagnostic, Label, Severity},
-    CheckerDiagnostic, LintVariation,
+    luacheck, CheckerDiagnostic, LintVariation,
 };
-use full_moon::{ast::Ast, node::Node, tokenizer::TokenType};
-use std::collections::HashSet;
+use full_moon::{
+    ast::{Ast, FunctionBody},
+    node::Node,
+    tokenizer::{Token, TokenType},
+    visitors::Visitor,
+};
+use std::collections::{HashMap, HashSet};
 
 const GLOBAL_LINT_PREFIX: &str = "#";
+const LUACHECK_PREFIX: &str = "luacheck:";
+
+// The lints luacheck would report for a variable, for directives like `ignore foo`
+const VARIABLE_LINTS: &[&str] = &[
+    "unscoped_variables",
+    "undefined_variable",
+    "unused_variable",
+    "shadowing",
+];
 
 lazy_static::lazy_static! {
     static ref NODES_TO_IGNORE: HashSet<VisitorType> = {
@@ -25,6 +38,31 @@ pub struct FilterConfiguration {
     global: bool,
     pub lint: String,
     variation: LintVariation,
+    // luacheck directives can apply to only some names, such as `ignore 212/self`
+    names: Option<Vec<String>>,
+    // `allow-next-line` and friends apply to the line after the comment, rather than the next node
+    next_line: bool,
+    reason: Option<String>,
+}
+
+impl FilterConfiguration {
+    fn applies_to(&self, diagnostic: &Diagnostic, identifiers: &HashMap<usize, String>) -> bool {
+        if self.lint != diagnostic.code {
+            return false;
+        }
+
+        let Some(names) = &self.names else {
+            return true;
+        };
+
+        identifiers
+            .get(&(diagnostic.primary_label.range.0 as usize))
+            .is_some_and(|identifier| {
+                names
+                    .iter()
+                    .any(|name| luacheck::name_matches(name, identifier))
+            })
+    }
 }
 
 #[derive(Clone, Debug)]
@@ -32,12 +70,36 @@ struct Filter {
     configuration: FilterConfiguration,
     comment_range: (usize, usize),
     range: (usize, usize),
+    luacheck: bool,
+}
+
+/// A `-- luacheck:` comment, such as `-- luacheck: push ignore 211, globals foo`.
+#[derive(Debug, Default)]
+pub struct LuacheckDirective {
+    push: bool,
+    pop: bool,
+    configurations: Vec<FilterConfiguration>,
 }
 
 #[derive(Default)]
-struct FilterVisitor {
+struct FilterVisitor<'a> {
     comments_checked: HashSet<(usize, usize)>,
     ranges: Vec<Result<Filter, Diagnostic>>,
+    line_starts: Vec<(usize, usize)>,
+    // The lints the checker runs, which are the only ones that can be filtered.
+    // `None` keeps filters for any lint, so that they can be listed.
+    lint_names: Option<&'a [&'a str]>,
+}
+
+impl FilterVisitor<'_> {
+    // The first byte of code after a line, so that filters can end there
+    fn end_of_line(&self, line: usize) -> usize {
+        self.line_starts
+            .iter()
+            .find(|(line_start, _)| *line_start > line)
+            .map(|(_, bytes)| *bytes)
+            .unwrap_or(usize::MAX)
+    }
 }
 
 pub fn parse_comment(comment_original: &str) -> Option<Vec<FilterConfiguration>> {
@@ -55,6 +117,15 @@ pub fn parse_comment(comment_original: &str) -> Option<Vec<FilterConfiguration>>
     let mut check_lint = false;
     let mut finished = false;
 
+    // Reasons are written after the filter, such as `-- selene: allow(lint) -- reason: why`
+    let reason = comment_original
+        .split_once(')')
+        .and_then(|(_, rest)| rest.trim().strip_prefix("--"))
+        .and_then(|rest| rest.trim_start().strip_prefix("reason:"))
+        .map(str::trim)
+        .filter(|reason| !reason.is_empty())
+        .map(ToOwned::to_owned);
+
     for character in config.chars() {
         if character == '(' {
             check_lint = true;
@@ -72,7 +143,12 @@ pub fn parse_comment(comment_original: &str) -> Option<Vec<FilterConfiguration>>
         return None;
     }
 
-    let variation = match variation.as_str() {
+    let (variation, next_line) = match variation.strip_suffix("-next-line") {
+        Some(variation) => (variation, true),
+        None => (variation.as_str(), false),
+    };
+
+    let variation = match variation {
         "allow" => LintVariation::Allow,
         "deny" => LintVariation::Deny,
         "warn" => LintVariation::Warn,
@@ -85,12 +161,133 @@ pub fn parse_comment(comment_original: &str) -> Option<Vec<FilterConfiguration>>
                 global,
                 lint: lint.to_owned(),
                 variation,
+                names: None,
+                next_line,
+                reason: reason.clone(),
             })
             .collect(),
     )
 }
 
-impl NodeVisitor for FilterVisitor {
+/// Parses the luacheck options that selene has an equivalent for, which are `ignore`, `globals`,
+/// `read globals`, and `push`/`pop`. Warnings are translated into the lints that cover them.
+pub fn parse_luacheck_comment(comment: &str) -> Option<LuacheckDirective> {
+    let options = comment.trim().strip_prefix(LUACHECK_PREFIX)?;
+
+    let mut directive = LuacheckDirective::default();
+    // Names are `None` when every name is allowed
+    let mut allowed: Vec<(&str, Option<Vec<String>>)> = Vec::new();
+
+    let mut allow = |lint: &'static str, name: Option<&str>| match allowed
+        .iter_mut()
+        .find(|(allowed_lint, _)| *allowed_lint == lint)
+    {
+        Some((_, names)) => match name {
+            Some(name) => {
+                if let Some(names) = names {
+                    names.push(name.to_owned());
+                }
+            }
+
+            None => *names = None,
+        },
+
+        None => allowed.push((lint, name.map(|name| vec![name.to_owned()]))),
+    };
+
+    for (index, option) in options.split(',').enumerate() {
+        let mut words = option.split_whitespace().collect::<Vec<_>>();
+
+        if index == 0 {
+            match words.first() {
+                Some(&"push") => directive.push = true,
+                Some(&"pop") => directive.pop = true,
+                _ => {}
+            }
+
+            if directive.push || directive.pop {
+                words.remove(0);
+            }
+        }
+
+        match words.as_slice() {
+            // Like the rest of the directive, this only covers what luacheck would have warned about
+            ["ignore"] => {
+                for (lint, _) in luacheck::LINT_WARNINGS {
+                    allow(lint, None);
+                }
+            }
+
+            ["ignore", arguments @ ..] => {
+                for argument in arguments {
+                    match argument.split_once('/') {
+                        Some((code, name)) => {
+                            for lint in luacheck::lints_matching(code) {
+                                allow(lint, Some(name));
+                            }
+                        }
+
+                        None if argument
+                            .starts_with(|character: char| character.is_ascii_digit()) =>
+                        {
+                            for lint in luacheck::lints_matching(argument) {
+                                allow(lint, None);
+                            }
+                        }
+
+                        None => {
+                            for lint in VARIABLE_LINTS {
+                                allow(lint, Some(argument));
+                            }
+                        }
+                    }
+                }
+            }
+
+            ["globals" | "new_globals", arguments @ ..] | ["new", "globals", arguments @ ..] => {
+                for argument in arguments {
+                    // Only the global itself is checked, not its fields
+                    let global = argument.split('.').next().unwrap_or(argument);
+                    allow("undefined_variable", Some(global));
+                    allow("unscoped_variables", Some(global));
+                    allow("unused_variable", Some(global));
+                }
+            }
+
+            ["read_globals" | "new_read_globals", arguments @ ..]
+            | ["read", "globals", arguments @ ..]
+            | ["new", "read", "globals", arguments @ ..] => {
+                for argument in arguments {
+                    let global = argument.split('.').next().unwrap_or(argument);
+                    allow("undefined_variable", Some(global));
+                }
+            }
+
+            // Anything else only matters to luacheck
+            _ => {}
+        }
+    }
+
+    directive.configurations = allowed
+        .into_iter()
+        .map(|(lint, names)| FilterConfiguration {
+            global: false,
+            lint: lint.to_owned(),
+            variation: LintVariation::Allow,
+            names,
+            next_line: false,
+            reason: None,
+        })
+        .collect();
+
+    if directive.push || directive.pop || !directive.configurations.is_empty() {
+        Some(directive)
+    } else {
+        None
+    }
+}
+
+impl NodeVisitor for FilterVisitor<'_> {
     fn visit_node(&mut self, node: &dyn Node, visitor_type: VisitorType) {
         if NODES_TO_IGNORE.contains(&visitor_type) {
             return;
@@ -130,47 +327,280 @@ impl NodeVisitor for FilterVisitor {
                     )
                 });
 
-                self.ranges
-                    .extend(configurations.into_iter().map(|configuration| {
-                        if lint_exists(&configuration.lint) {
-                            Ok(Filter {
-                                configuration,
-                                comment_range: (
-                                    trivia.start_position().bytes(),
-                                    trivia.end_position().bytes(),
-                                ),
-                                range: (range.0.bytes(), range.1.bytes()),
-                            })
-                        } else {
+                let next_line_range = (
+                    trivia_end_position.bytes(),
+                    self.end_of_line(trivia_end_position.line() + 1),
+                );
+
+                for configuration in configurations {
+                    let comment_range =
+                        (trivia_start_position.bytes(), trivia_end_position.bytes());
+
+                    self.ranges.push(
+                        if self.lint_names.is_some_and(|lint_names| {
+                            !lint_names.contains(&configuration.lint.as_str())
+                        }) {
                             Err(Diagnostic::new(
                                 "invalid_lint_filter",
                                 format!("no lint named `{}` exists", configuration.lint),
-                                Label::new((
-                                    trivia_start_position.bytes(),
-                                    trivia_end_position.bytes(),
-                                )),
+                                Label::new(comment_range),
                             ))
-                        }
-                    }));
+                        } else if configuration.global && configuration.next_line {
+                            Err(Diagnostic::new(
+                                "invalid_lint_filter",
+                                "next line filters can't be global".to_owned(),
+                                Label::new(comment_range),
+                            ))
+                        } else {
+                            Ok(Filter {
+                                range: if configuration.next_line {
+                                    next_line_range
+                                } else {
+                                    (range.0.bytes(), range.1.bytes())
+                                },
+                                configuration,
+                                comment_range,
+                                luacheck: false,
+                            })
+                        },
+                    );
+                }
             }
         }
     }
 }
 
-fn get_filter_ranges(ast: &Ast) -> Vec<Result<Filter, Diagnostic>> {
-    let mut filter_visitor = FilterVisitor::default();
+#[derive(Default)]
+struct FunctionBodyVisitor {
+    ranges: Vec<(usize, usize)>,
+}
+
+impl Visitor for FunctionBodyVisitor {
+    fn visit_function_body(&mut self, node: &FunctionBody) {
+        if let Some((start, end)) = node.range() {
+            self.ranges.push((start.bytes(), end.bytes()));
+        }
+    }
+}
+
+fn comment_text(trivia: &Token) -> Option<&str> {
+    match trivia.token_type() {
+        TokenType::SingleLineComment { comment } => Some(comment),
+        _ => None,
+    }
+}
+
+// Like luacheck, a directive after code only applies to that line. Otherwise, it applies until the
+// end of the function it's in, or the matching `pop` if it came after a `push`.
+fn get_luacheck_filters(
+    ast: &Ast,
+    line_starts: &[(usize, usize)],
+) -> Vec<Result<Filter, Diagnostic>> {
+    let mut function_body_visitor = FunctionBodyVisitor::default();
+    function_body_visitor.visit_ast(ast);
+
+    let function_end = |position: usize| {
+        function_body_visitor
+            .ranges
+            .iter()
+            .filter(|(start, end)| *start <= position && position < *end)
+            .map(|(_, end)| *end)
+            .min()
+            .unwrap_or(usize::MAX)
+    };
+
+    let mut filters: Vec<Filter> = Vec::new();
+    let mut failures = Vec::new();
+    // The number of filters when each `push` was seen
+    let mut pushes = Vec::new();
+    // Tokens aren't in order, so neither are their comments
+    let mut comments = ast
+        .nodes()
+        .tokens()
+        .chain(std::iter::once(ast.eof()))
+        .flat_map(|token| {
+            token
+                .leading_trivia()
+                .map(|trivia| (trivia, false))
+                .chain(token.trailing_trivia().map(|trivia| (trivia, true)))
+        })
+        .filter_map(|(trivia, after_code)| Some((trivia, comment_text(trivia)?, after_code)))
+        .collect::<Vec<_>>();
+
+    comments.sort_by_key(|(trivia, ..)| trivia.start_position().bytes());
+
+    for (trivia, comment, after_code) in comments {
+        let Some(directive) = parse_luacheck_comment(comment) else {
+            continue;
+        };
+
+        let comment_range = (
+            trivia.start_position().bytes(),
+            trivia.end_position().bytes(),
+        );
+
+        if directive.pop {
+            match pushes.pop() {
+                Some(first_pushed) => {
+                    for filter in &mut filters[first_pushed..] {
+                        filter.range.1 = filter.range.1.min(comment_range.0);
+                    }
+                }
+
+                None => failures.push(Diagnostic::new(
+                    "invalid_lint_filter",
+                    "`luacheck: pop` without a matching `luacheck: push`".to_owned(),
+                    Label::new(comment_range),
+                )),
+            }
+        }
+
+        if directive.push {
+            pushes.push(filters.len());
+        }
+
+        let range = if after_code {
+            let line = trivia.start_position().line();
+            let line_start = line_starts
+                .iter()
+                .find(|(line_start, _)| *line_start == line)
+                .map_or(comment_range.0, |(_, bytes)| *bytes);
+
+            (line_start, comment_range.0)
+        } else {
+            (comment_range.0, function_end(comment_range.0))
+        };
+
+        filters.extend(
+            directive
+                .configurations
+                .into_iter()
+                .map(|configuration| Filter {
+                    configuration,
+                    comment_range,
+                    range,
+                    luacheck: true,
+                }),
+        );
+    }
+
+    filters
+        .into_iter()
+        .map(Ok)
+        .chain(failures.into_iter().map(Err))
+        .collect()
+}
+
+// The first byte of code on every line that has any, in order
+fn line_starts(ast: &Ast) -> Vec<(usize, usize)> {
+    // Tokens aren't in order, such as parentheses coming before what's inside them
+    let mut line_starts = ast
+        .nodes()
+        .tokens()
+        .map(|token| {
+            let start = token.token().start_position();
+            (start.line(), start.bytes())
+        })
+        .collect::<Vec<_>>();
+
+    line_starts.sort_unstable();
+    line_starts.dedup_by_key(|(line, _)| *line);
+    line_starts
+}
+
+fn get_filter_ranges(
+    ast: &Ast,
+    lint_names: Option<&[&str]>,
+    luacheck_directives: bool,
+) -> Vec<Result<Filter, Diagnostic>> {
+    let mut filter_visitor = FilterVisitor {
+        line_starts: line_starts(ast),
+        lint_names,
+        ..FilterVisitor::default()
+    };
+
     filter_visitor.visit_nodes(ast);
+
+    if luacheck_directives {
+        filter_visitor
+            .ranges
+            .extend(get_luacheck_filters(ast, &filter_visitor.line_starts));
+    }
+
     filter_visitor.ranges
 }
 
+/// A `-- selene:` comment that filters a lint, for reporting what is filtered.
+#[derive(Clone, Debug)]
+pub struct LintFilter {
+    pub lint: String,
+    pub variation: LintVariation,
+    /// Whether this is a `--# selene:` filter for the entire file
+    pub global: bool,
+    /// Whether this is a filter for the line after it, such as `allow-next-line`
+    pub next_line: bool,
+    pub reason: Option<String>,
+    pub comment_range: (usize, usize),
+    /// The bytes the filter applies to, which is the entire file for global filters
+    pub range: (usize, usize),
+    /// Whether the lint is one of the given lints. Filters for any other lint do nothing,
+    /// and are reported by `invalid_lint_filter`.
+    pub known: bool,
+}
+
+/// Finds every `-- selene:` filter, in the order they appear. Filters for lints other than
+/// the ones the checker runs are kept, so that they can be found and removed.
+pub fn lint_filters(ast: &Ast, lint_names: &[&str]) -> Vec<LintFilter> {
+    let end_of_file = ast.eof().token().end_position().bytes();
+
+    // The only other errors are for filters that can't apply to anything
+    let mut filters = get_filter_ranges(ast, None, false)
+        .into_iter()
+        .flatten()
+        .map(|filter| LintFilter {
+            known: lint_names.contains(&filter.configuration.lint.as_str()),
+            range: if filter.configuration.global {
+                (0, end_of_file)
+            } else {
+                (filter.range.0, filter.range.1.min(end_of_file))
+            },
+            lint: filter.configuration.lint,
+            variation: filter.configuration.variation,
+            global: filter.configuration.global,
+            next_line: filter.configuration.next_line,
+            reason: filter.configuration.reason,
+            comment_range: filter.comment_range,
+        })
+        .collect::<Vec<_>>();
+
+    filters.sort_by_key(|filter| filter.comment_range);
+    filters
+}
+
+// Names are only needed for luacheck directives such as `ignore foo`
+fn identifiers_by_position(ast: &Ast) -> HashMap<usize, String> {
+    ast.nodes()
+        .tokens()
+        .filter_map(|token| match token.token_type() {
+            TokenType::Identifier { identifier } => Some((
+                token.token().start_position().bytes(),
+                identifier.to_string(),
+            )),
+            _ => None,
+        })
+        .collect()
+}
+
 #[derive(Debug)]
 enum FilterInstruction {
     Push {
+        id: usize,
         configuration: FilterConfiguration,
         bytes: usize,
     },
 
     Pop {
+        id: usize,
         bytes: usize,
     },
 }
@@ -179,18 +609,26 @@ impl FilterInstruction {
     fn bytes(&self) -> usize {
         match self {
             FilterInstruction::Push { bytes, .. } => *bytes,
-            FilterInstruction::Pop { bytes } => *bytes,
+            FilterInstruction::Pop { bytes, .. } => *bytes,
         }
     }
 }
 
+// Filters for the same range, along with their ids, so that conflicts between them can be found
+type SameRangeFilters = ((usize, usize), Vec<(usize, Filter)>);
+
 pub fn filter_diagnostics(
     ast: &Ast,
     mut diagnostics: Vec<CheckerDiagnostic>,
+    lint_names: &[&str],
     invalid_lint_filter_severity: Severity,
+    unused_lint_filter_severity: Severity,
+    luacheck_directives: bool,
+    require_filter_reasons: bool,
 ) -> Vec<CheckerDiagnostic> {
-    let filter_ranges = get_filter_ranges(ast);
+    let filter_ranges = get_filter_ranges(ast, Some(lint_names), luacheck_directives);
     let (mut filters, mut failures) = (Vec::new(), Vec::new());
+    let mut unused_filters = Vec::new();
     let mut new_diagnostics;
 
     for thing in filter_ranges {
@@ -200,16 +638,53 @@ pub fn filter_diagnostics(
         }
     }
 
+    if require_filter_reasons {
+        let mut comments_checked = HashSet::new();
+
+        for filter in &filters {
+            if filter.luacheck
+                || filter.configuration.variation != LintVariation::Allow
+                || filter.configuration.reason.is_some()
+                || !comments_checked.insert(filter.comment_range)
+            {
+                continue;
+            }
+
+            failures.push(Diagnostic::new_complete(
+                "invalid_lint_filter",
+                "filter has no reason".to_owned(),
+                Label::new(filter.comment_range),
+                vec!["try: `-- selene: allow(lint) -- reason: ...`".to_owned()],
+                Vec::new(),
+            ));
+        }
+    }
+
     if filters.is_empty() {
         new_diagnostics = diagnostics;
     } else {
         // Filter ranges are translated into instructions for a stack
-        let mut global_filters: Vec<Filter> = Vec::new();
+        let mut global_filters: Vec<(usize, Filter)> = Vec::new();
         let mut instructions: Vec<FilterInstruction> = Vec::new();
-        let mut conflicting: Option<((usize, usize), Vec<Filter>)> = None;
+        let mut conflicting: Option<SameRangeFilters> = None;
         let first_code = first_code(ast);
 
-        for filter in filters {
+        // Filters that may be unused, and the ranges `unused_lint_filter` is allowed in
+        let mut used_candidates = Vec::new();
+        let mut used = HashSet::new();
+        let mut conflicted = HashSet::new();
+        let mut unused_lint_filter_allowed = Vec::new();
+
+        let identifiers = if filters
+            .iter()
+            .any(|filter| filter.configuration.names.is_some())
+        {
+            identifiers_by_position(ast)
+        } else {
+            HashMap::new()
+        };
+
+        for (id, filter) in filters.into_iter().enumerate() {
             // Check for global filters
             if filter.configuration.global {
                 if let Some(first_code) = first_code {
@@ -230,34 +705,60 @@ pub fn filter_diagnostics(
                 }
             }
 
-            // Check for conflicting filters
-            if let Some((range, ref mut filters)) = conflicting.as_mut() {
-                if *range == filter.range {
-                    for possibly_conflicting in filters.iter() {
-                        if possibly_conflicting.configuration.lint == filter.configuration.lint {
-                            failures.push(Diagnostic::new_complete(
-                                "invalid_lint_filter",
-                                "filter conflicts with a previous one for the same code".to_owned(),
-                                Label::new(filter.comment_range),
-                                Vec::new(),
-                                vec![Label::new_with_message(
-                                    possibly_conflicting.comment_range,
-                                    "conflicts with this".to_owned(),
-                                )],
-                            ));
+            // Check for conflicting filters. luacheck directives only ever allow lints, so they can't conflict
+            if !filter.luacheck {
+                if let Some((range, ref mut filters)) = conflicting.as_mut() {
+                    if *range == filter.range {
+                        for (conflicting_id, possibly_conflicting) in filters.iter() {
+                            if possibly_conflicting.configuration.lint == filter.configuration.lint
+                            {
+                                conflicted.extend([id, *conflicting_id]);
+
+                                failures.push(Diagnostic::new_complete(
+                                    "invalid_lint_filter",
+                                    "filter conflicts with a previous one for the same code"
+                                        .to_owned(),
+                                    Label::new(filter.comment_range),
+                                    Vec::new(),
+                                    vec![Label::new_with_message(
+                                        possibly_conflicting.comment_range,
+                                        "conflicts with this".to_owned(),
+                                    )],
+                                ));
+                            }
                         }
+
+                        filters.push((id, filter.clone()));
+                    } else {
+                        conflicting = Some((filter.range, vec![(id, filter.clone())]));
                     }
+                } else {
+                    conflicting = Some((filter.range, vec![(id, filter.clone())]));
+                }
+            }
 
-                    filters.push(filter.clone());
+            if !filter.luacheck && filter.configuration.variation == LintVariation::Allow {
+                let range = if filter.configuration.global {
+                    (0, usize::MAX)
                 } else {
-                    conflicting = Some((filter.range, vec![filter.clone()]));
+                    filter.range
+                };
+
+                match filter.configuration.lint.as_str() {
+                    // These are reported after filtering, so they never filter anything
+                    "invalid_lint_filter" => {}
+                    "unused_lint_filter" => unused_lint_filter_allowed.push(range),
+                    _ => used_candidates.push((
+                        id,
+                        filter.configuration.lint.clone(),
+                        filter.comment_range,
+                        range,
+                    )),
                 }
-            } else {
-                conflicting = Some((filter.range, vec![filter.clone()]));
             }
 
             if filter.configuration.global {
-                global_filters.push(filter);
+                global_filters.push((id, filter));
             } else {
                 instructions.insert(
                     instructions
@@ -265,6 +766,7 @@ pub fn filter_diagnostics(
                         .position(|instruction| instruction.bytes() < filter.range.1)
                         .unwrap_or(instructions.len()),
                     FilterInstruction::Pop {
+                        id,
                         bytes: filter.range.1,
                     },
                 );
@@ -275,6 +777,7 @@ pub fn filter_diagnostics(
                         .position(|instruction| instruction.bytes() < filter.range.0)
                         .unwrap_or(instructions.len()),
                     FilterInstruction::Push {
+                        id,
                         configuration: filter.configuration,
                         bytes: filter.range.0,
                     },
@@ -282,8 +785,9 @@ pub fn filter_diagnostics(
             }
         }
 
-        for global_filter in global_filters {
+        for (id, global_filter) in global_filters {
             instructions.push(FilterInstruction::Push {
+                id,
                 configuration: global_filter.configuration,
                 bytes: 0,
             })
@@ -301,14 +805,19 @@ pub fn filter_diagnostics(
             while let Some(instruction) = instructions.pop() {
                 if instruction.bytes() <= start_byte {
                     match instruction {
-                        FilterInstruction::Push { configuration, .. } => {
-                            stack.push(configuration);
+                        FilterInstruction::Push {
+                            id, configuration, ..
+                        } => {
+                            stack.push((id, configuration));
                         }
 
-                        FilterInstruction::Pop { .. } => {
-                            stack
-                                .pop()
-                                .expect("FilterInstruction::Pop instructed, but stack is empty");
+                        // luacheck directives don't nest, so filters aren't always popped in order
+                        FilterInstruction::Pop { id, .. } => {
+                            let position = stack
+                                .iter()
+                                .rposition(|(pushed_id, _)| *pushed_id == id)
+                                .expect("FilterInstruction::Pop instructed, but filter was never pushed");
+                            stack.remove(position);
                         }
                     }
                 } else {
@@ -318,8 +827,10 @@ pub fn filter_diagnostics(
             }
 
             // Find the most recent configuration for this lint, and respect it
-            for configuration in stack.iter().rev() {
-                if configuration.lint == diagnostic.diagnostic.code {
+            for (id, configuration) in stack.iter().rev() {
+                if configuration.applies_to(&diagnostic.diagnostic, &identifiers) {
+                    used.insert(*id);
+
                     let severity = configuration.variation.to_severity();
                     if severity != Severity::Allow {
                         new_diagnostics.push(CheckerDiagnostic {
@@ -335,6 +846,24 @@ pub fn filter_diagnostics(
             // If no configuration touched this lint, pass it through identically
             new_diagnostics.push(diagnostic);
         }
+
+        for (id, lint, comment_range, range) in used_candidates {
+            if used.contains(&id)
+                || conflicted.contains(&id)
+                || unused_lint_filter_allowed.iter().any(|allowed| {
+                    *allowed == range
+                        || (allowed.0 <= comment_range.0 && comment_range.0 < allowed.1)
+                })
+            {
+                continue;
+            }
+
+            unused_filters.push(Diagnostic::new(
+                "unused_lint_filter",
+                format!("`{lint}` is never reported here, so this filter isn't needed"),
+                Label::new(comment_range),
+            ));
+        }
     }
 
     new_diagnostics.extend(&mut failures.into_iter().map(|failure| CheckerDiagnostic {
@@ -342,6 +871,11 @@ pub fn filter_diagnostics(
         diagnostic: failure,
     }));
 
+    new_diagnostics.extend(unused_filters.into_iter().map(|unused| CheckerDiagnostic {
+        severity: unused_lint_filter_severity,
+        diagnostic: unused,
+    }));
+
     new_diagnostics
 }
 
@@ -363,6 +897,76 @@ mod tests {
         test_full_run("lint_filtering", "just_comments");
     }
 
+    #[test]
+    fn test_luacheck_directives() {
+        test_full_run_config(
+            "lint_filtering",
+            "luacheck_directives",
+            CheckerConfig {
+                luacheck_directives: true,
+                ..CheckerConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_next_line() {
+        test_full_run("lint_filtering", "next_line");
+    }
+
+    #[test]
+    fn test_require_reasons() {
+        test_full_run_config(
+            "lint_filtering",
+            "require_reasons",
+            CheckerConfig {
+                require_filter_reasons: true,
+                ..CheckerConfig::default()
+            },
+        );
+    }
+
+    #[test]
+    fn test_unused_lint_filter() {
+        test_full_run("lint_filtering", "unused_lint_filter");
+    }
+
+    #[test]
+    fn test_lint_filters() {
+        let ast = full_moon::parse(
+            "--# selene: allow(shadowing)\n\
+             -- selene: allow(unused_variable) -- reason: testing\n\
+             local x = 1\n\
+             -- selene: deny-next-line(undefined_variable, lint_thatll_never_be_created)\n\
+             print(y)\n",
+        )
+        .unwrap();
+
+        let filters = super::lint_filters(
+            &ast,
+            &["shadowing", "unused_variable", "undefined_variable"],
+        );
+        assert_eq!(filters.len(), 4);
+
+        assert_eq!(filters[0].lint, "shadowing");
+        assert!(filters[0].global);
+        assert_eq!(
+            filters[0].range,
+            (0, ast.eof().token().end_position().bytes())
+        );
+
+        assert_eq!(filters[1].lint, "unused_variable");
+        assert_eq!(filters[1].reason.as_deref(), Some("testing"));
+
+        assert_eq!(filters[2].lint, "undefined_variable");
+        assert_eq!(filters[2].variation, LintVariation::Deny);
+        assert!(filters[2].next_line);
+        assert!(filters[2].known);
+
+        assert_eq!(filters[3].lint, "lint_thatll_never_be_created");
+        assert!(!filters[3].known);
+    }
+
     #[test]
     fn test_manual_table_clone() {
         test_full_run("lint_filtering", "manual_table_clone");
@@ -1,11 +1,14 @@
 use crate::{ast_util::scopes::ScopeManager, standard_library::StandardLibrary};
-use std::convert::TryInto;
+use std::{collections::HashSet, convert::TryInto, sync::Mutex};
 
 use codespan_reporting::diagnostic::{
     Diagnostic as CodespanDiagnostic, Label as CodespanLabel, Severity as CodespanSeverity,
 };
-use full_moon::{ast::Ast, node::Node};
-use serde::de::DeserializeOwned;
+use full_moon::{ast::Ast, node::Node, visitors::Visitor};
+use serde::{
+    de::{self, DeserializeOwned, Deserializer},
+    Deserialize, Serialize,
+};
 
 pub mod almost_swapped;
 pub mod bad_string_escape;
@@ -33,6 +36,7 @@ pub mod type_check_inside_call;
 pub mod unbalanced_assignments;
 pub mod undefined_variable;
 pub mod unscoped_variables;
+pub mod unused_lint_filter;
 pub mod unused_variable;
 
 #[cfg(feature = "roblox")]
@@ -48,7 +52,8 @@ pub mod roblox_suspicious_udim2_new;
 mod test_util;
 
 pub trait Lint {
-    type Config: DeserializeOwned;
+    // Serialize is used to show the default configuration to users
+    type Config: DeserializeOwned + Serialize;
     type Error: std::error::Error;
 
     const SEVERITY: Severity;
@@ -58,14 +63,90 @@ pub trait Lint {
     where
         Self: Sized;
 
+    /// Finds the lint's diagnostics in a file. Lints that only look at nodes as they're visited
+    /// should implement [`Lint::visitor`] instead, which this runs on its own by default.
+    fn pass(
+        &self,
+        ast: &full_moon::ast::Ast,
+        context: &Context,
+        ast_context: &AstContext,
+    ) -> Vec<Diagnostic> {
+        run_visitor(self.visitor(context, ast_context), ast)
+    }
+
+    /// Node callbacks for the lint, which a checker runs in one traversal of the AST shared with
+    /// every other lint that has them. Lints that need the whole AST at once implement
+    /// [`Lint::pass`] and keep this as `None`. Otherwise, `None` means there is nothing to report.
+    fn visitor<'a>(
+        &'a self,
+        _context: &'a Context,
+        _ast_context: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        None
+    }
+}
+
+/// The visitor of a lint, returned by [`Lint::visitor`].
+pub trait LintVisitor: Visitor {
+    /// The diagnostics found, once every node has been visited.
+    fn finish(self: Box<Self>) -> Vec<Diagnostic>;
+}
+
+fn run_visitor(visitor: Option<Box<dyn LintVisitor + '_>>, ast: &Ast) -> Vec<Diagnostic> {
+    match visitor {
+        Some(visitor) => crate::multi_visitor::visit_ast(ast, vec![visitor])
+            .pop()
+            .unwrap_or_default(),
+        None => Vec::new(),
+    }
+}
+
+/// An object safe version of [`Lint`], so that the lints a checker runs can be chosen at runtime
+/// with a [`LintRegistry`](crate::LintRegistry). Every [`Lint`] implements it.
+pub trait DynLint: Send + Sync {
+    /// See [`Lint::pass`].
+    fn pass(
+        &self,
+        ast: &full_moon::ast::Ast,
+        context: &Context,
+        ast_context: &AstContext,
+    ) -> Vec<Diagnostic> {
+        run_visitor(self.visitor(context, ast_context), ast)
+    }
+
+    /// See [`Lint::visitor`].
+    fn visitor<'a>(
+        &'a self,
+        _context: &'a Context,
+        _ast_context: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        None
+    }
+}
+
+impl<L: Lint + Send + Sync> DynLint for L {
     fn pass(
         &self,
         ast: &full_moon::ast::Ast,
         context: &Context,
         ast_context: &AstContext,
-    ) -> Vec<Diagnostic>;
+    ) -> Vec<Diagnostic> {
+        Lint::pass(self, ast, context, ast_context)
+    }
+
+    fn visitor<'a>(
+        &'a self,
+        context: &'a Context,
+        ast_context: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Lint::visitor(self, context, ast_context)
+    }
 }
 
+/// The category of a lint. Categories can be configured in `[lints]` like lints can,
+/// such as `style = "allow"`, in which case lints configured by name take priority.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
+#[serde(rename_all = "snake_case")]
 pub enum LintType {
     /// Code that does something simple but in a complex way
     Complexity,
@@ -81,20 +162,40 @@ pub enum LintType {
     Style,
 }
 
-#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+impl LintType {
+    pub const ALL: [LintType; 4] = [
+        LintType::Complexity,
+        LintType::Correctness,
+        LintType::Performance,
+        LintType::Style,
+    ];
+
+    /// The name of the category, as written in `[lints]`.
+    pub fn name(self) -> &'static str {
+        match self {
+            LintType::Complexity => "complexity",
+            LintType::Correctness => "correctness",
+            LintType::Performance => "performance",
+            LintType::Style => "style",
+        }
+    }
+}
+
+#[derive(Clone, Copy, Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub enum Severity {
     Allow,
     Error,
     Warning,
 }
 
-#[derive(Debug)]
+#[derive(Debug, Serialize)]
 pub struct Diagnostic {
     pub code: &'static str,
     pub message: String,
     pub notes: Vec<String>,
     pub primary_label: Label,
     pub secondary_labels: Vec<Label>,
+    pub suggestions: Vec<Suggestion>,
 }
 
 impl Diagnostic {
@@ -106,6 +207,7 @@ impl Diagnostic {
 
             notes: Vec::new(),
             secondary_labels: Vec::new(),
+            suggestions: Vec::new(),
         }
     }
 
@@ -122,9 +224,15 @@ impl Diagnostic {
             notes,
             primary_label,
             secondary_labels,
+            suggestions: Vec::new(),
         }
     }
 
+    pub fn with_suggestion(mut self, suggestion: Suggestion) -> Self {
+        self.suggestions.push(suggestion);
+        self
+    }
+
     pub fn into_codespan_diagnostic(
         self,
         file_id: codespan::FileId,
@@ -151,7 +259,65 @@ impl Diagnostic {
     }
 }
 
-#[derive(Debug, PartialEq, Eq)]
+#[derive(Deserialize)]
+struct DeserializedDiagnostic {
+    code: String,
+    message: String,
+    notes: Vec<String>,
+    primary_label: Label,
+    secondary_labels: Vec<Label>,
+    suggestions: Vec<Suggestion>,
+}
+
+lazy_static::lazy_static! {
+    static ref INTERNED_LINT_NAMES: Mutex<HashSet<&'static str>> = Mutex::new(HashSet::new());
+}
+
+/// Gives the name of a lint that's only known at runtime, such as a plugin, the `&'static str`
+/// that diagnostics use as their code. Every name is only leaked once.
+pub fn intern_lint_name(name: &str) -> &'static str {
+    let mut interned_lint_names = INTERNED_LINT_NAMES.lock().unwrap();
+
+    match interned_lint_names.get(name) {
+        Some(interned) => interned,
+        None => {
+            let interned = Box::leak(name.to_owned().into_boxed_str());
+            interned_lint_names.insert(interned);
+            interned
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Diagnostic {
+    // Codes are always the names of lints, which is what lets them stay `&'static str`
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let diagnostic = DeserializedDiagnostic::deserialize(deserializer)?;
+
+        let code = crate::all_lints()
+            .iter()
+            .map(|lint| lint.name)
+            .find(|name| *name == diagnostic.code)
+            .or_else(|| {
+                INTERNED_LINT_NAMES
+                    .lock()
+                    .unwrap()
+                    .get(diagnostic.code.as_str())
+                    .copied()
+            })
+            .ok_or_else(|| de::Error::custom(format!("unknown lint `{}`", diagnostic.code)))?;
+
+        Ok(Diagnostic {
+            code,
+            message: diagnostic.message,
+            notes: diagnostic.notes,
+            primary_label: diagnostic.primary_label,
+            secondary_labels: diagnostic.secondary_labels,
+            suggestions: diagnostic.suggestions,
+        })
+    }
+}
+
+#[derive(Debug, PartialEq, Eq, Deserialize, Serialize)]
 pub struct Label {
     pub message: Option<String>,
     pub range: (u32, u32),
@@ -212,6 +378,116 @@ impl Label {
     }
 }
 
+/// How confident a lint is that applying a [`Suggestion`] is correct.
+#[derive(Clone, Copy, Debug, PartialEq, Eq, PartialOrd, Ord, Deserialize, Serialize)]
+pub enum Applicability {
+    /// The suggestion is definitely what the user intended, and can be applied automatically.
+    MachineApplicable,
+
+    /// The suggestion may be what the user intended, but it is uncertain.
+    /// It should still compile, but may change the behavior of the code.
+    MaybeIncorrect,
+
+    /// The suggestion contains placeholders that the user must fill in.
+    HasPlaceholders,
+
+    /// The applicability of the suggestion is unknown.
+    Unspecified,
+}
+
+/// A single replacement of a byte range of the source code.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Edit {
+    pub range: (u32, u32),
+    pub replacement: String,
+}
+
+impl Edit {
+    pub fn new<P: TryInto<u32>>(range: (P, P), replacement: String) -> Edit {
+        Edit {
+            range: Label::new(range).range,
+            replacement,
+        }
+    }
+}
+
+/// A fix for a diagnostic, made up of edits that must all be applied together.
+#[derive(Clone, Debug, PartialEq, Eq, Deserialize, Serialize)]
+pub struct Suggestion {
+    pub message: String,
+    pub edits: Vec<Edit>,
+    pub applicability: Applicability,
+}
+
+impl Suggestion {
+    pub fn new(message: String, edits: Vec<Edit>, applicability: Applicability) -> Self {
+        Self {
+            message,
+            edits,
+            applicability,
+        }
+    }
+
+    /// Shorthand for a suggestion that replaces a single range.
+    pub fn replace<P: TryInto<u32>>(
+        message: String,
+        range: (P, P),
+        replacement: String,
+        applicability: Applicability,
+    ) -> Self {
+        Self::new(message, vec![Edit::new(range, replacement)], applicability)
+    }
+}
+
+/// Applies every suggestion to the source, skipping suggestions whose edits overlap
+/// with an edit that was already applied.
+/// Returns the new source and the number of suggestions that were applied.
+pub fn apply_suggestions<'a>(
+    source: &str,
+    suggestions: impl IntoIterator<Item = &'a Suggestion>,
+) -> (String, usize) {
+    let mut edits: Vec<&Edit> = Vec::new();
+    let mut applied = 0;
+
+    'next_suggestion: for suggestion in suggestions {
+        for edit in &suggestion.edits {
+            if edit.range.0 > edit.range.1 || edit.range.1 as usize > source.len() {
+                continue 'next_suggestion;
+            }
+
+            for other in suggestion.edits.iter().chain(edits.iter().copied()) {
+                if std::ptr::eq(edit, other) {
+                    continue;
+                }
+
+                if edit.range.0 < other.range.1 && other.range.0 < edit.range.1
+                    || edit.range == other.range
+                {
+                    continue 'next_suggestion;
+                }
+            }
+        }
+
+        edits.extend(&suggestion.edits);
+        applied += 1;
+    }
+
+    edits.sort_by_key(|edit| edit.range);
+
+    let mut output = String::with_capacity(source.len());
+    let mut position = 0;
+
+    for edit in edits {
+        output.push_str(&source[position..edit.range.0 as usize]);
+        output.push_str(&edit.replacement);
+        position = edit.range.1 as usize;
+    }
+
+    output.push_str(&source[position..]);
+
+    (output, applied)
+}
+
 #[derive(Clone, Debug)]
 pub struct Context {
     pub standard_library: StandardLibrary,
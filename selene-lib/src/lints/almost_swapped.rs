@@ -2,10 +2,7 @@ use super::*;
 use crate::ast_util::{purge_trivia, range, HasSideEffects};
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    visitors::Visitor,
-};
+use full_moon::{ast, node::Node, visitors::Visitor};
 
 pub struct AlmostSwappedLint;
 
@@ -20,17 +17,32 @@ impl Lint for AlmostSwappedLint {
         Ok(AlmostSwappedLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = AlmostSwappedVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(AlmostSwappedVisitor {
             almost_swaps: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct AlmostSwappedVisitor {
+    almost_swaps: Vec<AlmostSwap>,
+}
 
-        visitor
-            .almost_swaps
+impl LintVisitor for AlmostSwappedVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.almost_swaps
             .iter()
             .map(|almost_swap| {
+                let swap = format!(
+                    "{name1}, {name2} = {name2}, {name1}",
+                    name1 = almost_swap.names.0,
+                    name2 = almost_swap.names.1,
+                );
+
                 Diagnostic::new_complete(
                     "almost_swapped",
                     format!(
@@ -39,25 +51,33 @@ impl Lint for AlmostSwappedLint {
                         (almost_swap.names.1),
                     ),
                     Label::new(almost_swap.range),
-                    vec![format!(
-                        "try: `{name1}, {name2} = {name2}, {name1}`",
-                        name1 = almost_swap.names.0,
-                        name2 = almost_swap.names.1,
-                    )],
+                    vec![format!("try: `{swap}`")],
                     Vec::new(),
                 )
+                .with_suggestion(Suggestion::replace(
+                    format!("replace with `{swap}`"),
+                    almost_swap.statements_range,
+                    swap,
+                    // The original code is wrong, so we can't be sure this is what was intended
+                    Applicability::MaybeIncorrect,
+                ))
             })
             .collect()
     }
 }
 
-struct AlmostSwappedVisitor {
-    almost_swaps: Vec<AlmostSwap>,
-}
-
 struct AlmostSwap {
     names: (String, String),
     range: (usize, usize),
+    statements_range: (usize, usize),
+}
+
+// Node ranges can stop short of the end of the statement, such as with `t[1]`
+fn tokens_end(stmt: &ast::Stmt) -> usize {
+    stmt.tokens()
+        .map(|token| token.token().end_position().bytes())
+        .max()
+        .unwrap_or_else(|| range(stmt).1)
 }
 
 impl Visitor for AlmostSwappedVisitor {
@@ -84,12 +104,14 @@ impl Visitor for AlmostSwappedVisitor {
                                 self.almost_swaps.push(AlmostSwap {
                                     names: last_swap.names.to_owned(),
                                     range: (last_swap.range.0, expr_end),
+                                    statements_range: (last_swap.range.0, tokens_end(stmt)),
                                 });
                             }
                         } else {
                             last_swap = Some(AlmostSwap {
                                 names: (var_text, expr_text),
                                 range: range(stmt),
+                                statements_range: (range(stmt).0, tokens_end(stmt)),
                             });
                         }
 
@@ -1,11 +1,7 @@
 use super::*;
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    tokenizer,
-    visitors::Visitor,
-};
+use full_moon::{ast, tokenizer, visitors::Visitor};
 use regex::Regex;
 
 lazy_static::lazy_static! {
@@ -34,16 +30,26 @@ impl Lint for BadStringEscapeLint {
         Ok(BadStringEscapeLint)
     }
 
-    fn pass(&self, ast: &Ast, context: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = BadStringEscapeVisitor {
+    fn visitor<'a>(
+        &'a self,
+        context: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(BadStringEscapeVisitor {
             sequences: Vec::new(),
             roblox: context.is_roblox(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct BadStringEscapeVisitor {
+    sequences: Vec<StringEscapeSequence>,
+    roblox: bool,
+}
 
-        visitor
-            .sequences
+impl LintVisitor for BadStringEscapeVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.sequences
             .iter()
             .map(|sequence| match sequence.issue {
                 ReasonWhy::Invalid => Diagnostic::new(
@@ -93,11 +99,6 @@ impl Lint for BadStringEscapeLint {
     }
 }
 
-struct BadStringEscapeVisitor {
-    sequences: Vec<StringEscapeSequence>,
-    roblox: bool,
-}
-
 struct StringEscapeSequence {
     range: (usize, usize),
     issue: ReasonWhy,
@@ -1,10 +1,7 @@
 use super::*;
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    visitors::Visitor,
-};
+use full_moon::{ast, visitors::Visitor};
 
 pub struct CompareNanLint;
 
@@ -19,15 +16,24 @@ impl Lint for CompareNanLint {
         Ok(CompareNanLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = CompareNanVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(CompareNanVisitor {
             comparisons: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct CompareNanVisitor {
+    comparisons: Vec<Comparison>,
+}
 
-        visitor
-            .comparisons
+impl LintVisitor for CompareNanVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.comparisons
             .iter()
             .map(|comparisons| {
                 Diagnostic::new_complete(
@@ -46,10 +52,6 @@ impl Lint for CompareNanLint {
     }
 }
 
-struct CompareNanVisitor {
-    comparisons: Vec<Comparison>,
-}
-
 struct Comparison {
     variable: String,
     operator: String,
@@ -4,7 +4,7 @@ use super::*;
 use std::convert::Infallible;
 
 use full_moon::{
-    ast::{self, Ast, BinOp},
+    ast::{self, BinOp},
     visitors::Visitor,
 };
 
@@ -21,15 +21,24 @@ impl Lint for ConstantTableComparisonLint {
         Ok(ConstantTableComparisonLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = ConstantTableComparisonVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(ConstantTableComparisonVisitor {
             comparisons: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct ConstantTableComparisonVisitor {
+    comparisons: Vec<Comparison>,
+}
 
-        visitor
-            .comparisons
+impl LintVisitor for ConstantTableComparisonVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.comparisons
             .iter()
             .map(|comparison| {
                 Diagnostic::new_complete(
@@ -65,10 +74,6 @@ impl Lint for ConstantTableComparisonLint {
     }
 }
 
-struct ConstantTableComparisonVisitor {
-    comparisons: Vec<Comparison>,
-}
-
 #[derive(Clone, Copy)]
 enum EmptyComparisonSide {
     Left,
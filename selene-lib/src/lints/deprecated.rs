@@ -1,13 +1,13 @@
-use std::convert::Infallible;
+use std::{convert::Infallible, fmt};
 
-use full_moon::{ast, visitors::Visitor};
-use serde::Deserialize;
+use full_moon::{ast, tokenizer::Token, visitors::Visitor};
+use serde::{Deserialize, Serialize};
 
-use crate::ast_util::{name_paths::*, scopes::ScopeManager};
+use crate::ast_util::{name_paths::*, range, scopes::ScopeManager};
 
 use super::{super::standard_library::*, *};
 
-#[derive(Clone, Default, Deserialize)]
+#[derive(Clone, Default, Deserialize, Serialize)]
 #[serde(default)]
 pub struct DeprecatedLintConfig {
     pub allow: Vec<String>,
@@ -28,19 +28,78 @@ impl Lint for DeprecatedLint {
         Ok(DeprecatedLint { config })
     }
 
-    fn pass(&self, ast: &Ast, context: &Context, ast_context: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = DeprecatedVisitor::new(
+    fn visitor<'a>(
+        &'a self,
+        context: &'a Context,
+        ast_context: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(DeprecatedVisitor::new(
             &self.config,
             &ast_context.scope_manager,
             &context.standard_library,
-        );
+        )))
+    }
+}
 
-        visitor.visit_ast(ast);
+// An argument of a deprecated call, as it is put into the replacement
+struct Argument {
+    display: String,
+    // Whether the argument has to be wrapped in parentheses when it is part of a bigger expression,
+    // such as `a + b` in `#%1`
+    needs_parentheses: bool,
+}
 
-        visitor.diagnostics
+impl Argument {
+    fn from_expression(expression: &ast::Expression) -> Self {
+        let needs_parentheses = match expression {
+            ast::Expression::Parentheses { .. } => false,
+
+            #[cfg(feature = "roblox")]
+            ast::Expression::Value {
+                type_assertion: Some(_),
+                ..
+            } => true,
+
+            ast::Expression::Value { value, .. } => !matches!(
+                **value,
+                ast::Value::Var(_)
+                    | ast::Value::FunctionCall(_)
+                    | ast::Value::ParenthesesExpression(_)
+            ),
+
+            _ => true,
+        };
+
+        Argument {
+            display: display_without_trivia(expression),
+            needs_parentheses,
+        }
     }
 }
 
+// The node as it is written, without the whitespace and comments around it
+fn display_without_trivia(node: &(impl Node + fmt::Display)) -> String {
+    let display = node.to_string();
+
+    // Tokens aren't always given in the order they are written in, such as with parentheses
+    let first = node
+        .tokens()
+        .min_by_key(|token| token.token().start_position().bytes())
+        .unwrap();
+    let last = node
+        .tokens()
+        .max_by_key(|token| token.token().end_position().bytes())
+        .unwrap();
+
+    let trivia_len = |trivia: &mut dyn Iterator<Item = &Token>| {
+        trivia.map(|trivia| trivia.to_string().len()).sum::<usize>()
+    };
+
+    display[trivia_len(&mut first.leading_trivia())
+        ..display.len() - trivia_len(&mut last.trailing_trivia())]
+        .to_owned()
+}
+
 struct DeprecatedVisitor<'a> {
     allow: Vec<Vec<String>>,
     diagnostics: Vec<Diagnostic>,
@@ -48,6 +107,12 @@ struct DeprecatedVisitor<'a> {
     standard_library: &'a StandardLibrary,
 }
 
+impl LintVisitor for DeprecatedVisitor<'_> {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
+
 impl<'a> DeprecatedVisitor<'a> {
     fn new(
         config: &DeprecatedLintConfig,
@@ -94,7 +159,9 @@ impl<'a> DeprecatedVisitor<'a> {
         node: &N,
         what: &str,
         name_path: &[String],
-        parameters: &[String],
+        // None if the name path is not being called
+        arguments: Option<&[Argument]>,
+        replace_range: (usize, usize),
     ) {
         assert!(!name_path.is_empty());
 
@@ -114,12 +181,41 @@ impl<'a> DeprecatedVisitor<'a> {
             };
 
             let mut notes = vec![deprecated.message.to_owned()];
-
-            if let Some(replace_with) = deprecated.try_instead(parameters) {
+            let mut suggestion = None;
+
+            if let Some(replace_with) = deprecated.try_instead_parenthesized(
+                &arguments
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|argument| argument.display.clone())
+                    .collect::<Vec<_>>(),
+                &arguments
+                    .unwrap_or_default()
+                    .iter()
+                    .map(|argument| argument.needs_parentheses)
+                    .collect::<Vec<_>>(),
+            ) {
                 notes.push(format!("try: {replace_with}"));
+
+                // If only part of the name path is deprecated, the replacement isn't for the whole node.
+                // Replacements that use parameters are only valid when this is being called.
+                if bound == name_path.len()
+                    && (arguments.is_some()
+                        || !deprecated
+                            .replace
+                            .iter()
+                            .any(|replace_format| replace_format.contains('%')))
+                {
+                    suggestion = Some(Suggestion::replace(
+                        format!("replace with `{replace_with}`"),
+                        replace_range,
+                        replace_with,
+                        Applicability::MachineApplicable,
+                    ));
+                }
             }
 
-            self.diagnostics.push(Diagnostic::new_complete(
+            let diagnostic = Diagnostic::new_complete(
                 "deprecated",
                 format!(
                     "standard library {what} `{}` is deprecated",
@@ -128,7 +224,12 @@ impl<'a> DeprecatedVisitor<'a> {
                 Label::from_node(node, None),
                 notes,
                 Vec::new(),
-            ));
+            );
+
+            self.diagnostics.push(match suggestion {
+                Some(suggestion) => diagnostic.with_suggestion(suggestion),
+                None => diagnostic,
+            });
         }
     }
 }
@@ -149,7 +250,13 @@ impl Visitor for DeprecatedVisitor<'_> {
             None => return,
         };
 
-        self.check_name_path(expression, "expression", &name_path, &[]);
+        self.check_name_path(
+            expression,
+            "expression",
+            &name_path,
+            None,
+            range::<_, usize>(expression),
+        );
     }
 
     fn visit_function_call(&mut self, call: &ast::FunctionCall) {
@@ -194,21 +301,45 @@ impl Visitor for DeprecatedVisitor<'_> {
             feature = "force_exhaustive_checks",
             deny(non_exhaustive_omitted_patterns)
         )]
-        let argument_displays = match function_args {
-            ast::FunctionArgs::Parentheses { arguments, .. } => arguments
-                .iter()
-                .map(|argument| argument.to_string())
-                .collect(),
-
-            ast::FunctionArgs::String(token) => vec![token.to_string()],
-            ast::FunctionArgs::TableConstructor(table_constructor) => {
-                vec![table_constructor.to_string()]
+        let arguments = match function_args {
+            ast::FunctionArgs::Parentheses { arguments, .. } => {
+                arguments.iter().map(Argument::from_expression).collect()
             }
 
+            ast::FunctionArgs::String(token) => vec![Argument {
+                display: display_without_trivia(token),
+                needs_parentheses: true,
+            }],
+
+            ast::FunctionArgs::TableConstructor(table_constructor) => vec![Argument {
+                display: display_without_trivia(table_constructor),
+                needs_parentheses: true,
+            }],
+
             _ => Vec::new(),
         };
 
-        self.check_name_path(call, "function", &name_path, &argument_displays);
+        // Replacements of methods are only the method, so `game:connect(f)` becomes `game:Connect(f)`.
+        // Suffixes after the deprecated call (such as `wait(1):andThen()`) are not part of the replacement.
+        let replace_start = match call_suffix {
+            ast::Suffix::Call(ast::Call::MethodCall(method_call)) => {
+                method_call.name().start_position()
+            }
+            _ => call.start_position(),
+        };
+
+        let replace_range = (
+            replace_start.unwrap().bytes(),
+            call_suffix.end_position().unwrap().bytes(),
+        );
+
+        self.check_name_path(
+            call,
+            "function",
+            &name_path,
+            Some(&arguments),
+            replace_range,
+        );
     }
 }
 
@@ -234,6 +365,15 @@ mod tests {
         );
     }
 
+    #[test]
+    fn test_deprecated_suggestions() {
+        test_lint(
+            DeprecatedLint::new(DeprecatedLintConfig::default()).unwrap(),
+            "deprecated",
+            "deprecated_suggestions",
+        );
+    }
+
     #[test]
     fn test_specific_allow() {
         test_lint(
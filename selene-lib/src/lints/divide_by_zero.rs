@@ -1,11 +1,7 @@
 use super::*;
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    node::Node,
-    visitors::Visitor,
-};
+use full_moon::{ast, node::Node, visitors::Visitor};
 
 pub struct DivideByZeroLint;
 
@@ -20,15 +16,24 @@ impl Lint for DivideByZeroLint {
         Ok(DivideByZeroLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = DivideByZeroVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(DivideByZeroVisitor {
             positions: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct DivideByZeroVisitor {
+    positions: Vec<(usize, usize)>,
+}
 
-        visitor
-            .positions
+impl LintVisitor for DivideByZeroVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.positions
             .iter()
             .map(|position| {
                 Diagnostic::new(
@@ -41,10 +46,6 @@ impl Lint for DivideByZeroLint {
     }
 }
 
-struct DivideByZeroVisitor {
-    positions: Vec<(usize, usize)>,
-}
-
 fn value_is_zero(value: &ast::Value) -> bool {
     if let ast::Value::Number(token) = value {
         token.token().to_string() == "0"
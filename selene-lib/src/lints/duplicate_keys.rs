@@ -3,11 +3,7 @@ use crate::ast_util::range;
 use super::*;
 use std::{collections::HashMap, convert::Infallible};
 
-use full_moon::{
-    ast::{self, Ast},
-    tokenizer,
-    visitors::Visitor,
-};
+use full_moon::{ast, tokenizer, visitors::Visitor};
 
 pub struct DuplicateKeysLint;
 
@@ -22,29 +18,14 @@ impl Lint for DuplicateKeysLint {
         Ok(DuplicateKeysLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = DuplicateKeysVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(DuplicateKeysVisitor {
             duplicates: Vec::new(),
-        };
-
-        visitor.visit_ast(ast);
-
-        visitor
-            .duplicates
-            .iter()
-            .map(|duplicate| {
-                Diagnostic::new_complete(
-                    "duplicate_keys",
-                    format!("key `{}` is already declared", duplicate.name),
-                    Label::new(duplicate.position),
-                    Vec::new(),
-                    vec![Label::new_with_message(
-                        duplicate.original_declaration,
-                        format!("`{}` originally declared here", duplicate.name),
-                    )],
-                )
-            })
-            .collect()
+        }))
     }
 }
 
@@ -72,6 +53,26 @@ struct DuplicateKeysVisitor {
     duplicates: Vec<DuplicateKey>,
 }
 
+impl LintVisitor for DuplicateKeysVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.duplicates
+            .iter()
+            .map(|duplicate| {
+                Diagnostic::new_complete(
+                    "duplicate_keys",
+                    format!("key `{}` is already declared", duplicate.name),
+                    Label::new(duplicate.position),
+                    Vec::new(),
+                    vec![Label::new_with_message(
+                        duplicate.original_declaration,
+                        format!("`{}` originally declared here", duplicate.name),
+                    )],
+                )
+            })
+            .collect()
+    }
+}
+
 /// Attempts to evaluate an expression key such as `"foobar"` in `["foobar"] = true` to a named identifier, `foobar`.
 /// Also extracts `5` from `[5] = true`.
 /// Only works for string literal expression keys, or constant number keys.
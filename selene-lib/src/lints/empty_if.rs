@@ -2,14 +2,14 @@ use super::*;
 use std::convert::Infallible;
 
 use full_moon::{
-    ast::{self, Ast},
+    ast,
     node::Node,
     tokenizer::{Token, TokenKind},
     visitors::Visitor,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Default, Deserialize)]
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
 #[serde(default)]
 pub struct EmptyIfLintConfig {
     comments_count: bool,
@@ -30,22 +30,39 @@ impl Lint for EmptyIfLint {
         Ok(EmptyIfLint { config })
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = EmptyIfVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(EmptyIfVisitor {
             comment_positions: Vec::new(),
+            comments_count: self.config.comments_count,
             positions: Vec::new(),
-        };
+        }))
+    }
+}
+
+fn block_is_empty(block: &ast::Block) -> bool {
+    block.last_stmt().is_none() && block.stmts().next().is_none()
+}
 
-        visitor.visit_ast(ast);
+struct EmptyIfVisitor {
+    comment_positions: Vec<u32>,
+    comments_count: bool,
+    positions: Vec<((u32, u32), EmptyIfKind)>,
+}
 
-        let comment_positions = visitor.comment_positions.clone();
+impl LintVisitor for EmptyIfVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        let comment_positions = self.comment_positions;
+        let comments_count = self.comments_count;
 
-        visitor
-            .positions
+        self.positions
             .into_iter()
             .filter(|(position, _)| {
                 // OPTIMIZE: This is O(n^2), can we optimize this?
-                if self.config.comments_count {
+                if comments_count {
                     !comment_positions.iter().any(|comment_position| {
                         position.0 <= *comment_position && position.1 >= *comment_position
                     })
@@ -69,15 +86,6 @@ impl Lint for EmptyIfLint {
     }
 }
 
-fn block_is_empty(block: &ast::Block) -> bool {
-    block.last_stmt().is_none() && block.stmts().next().is_none()
-}
-
-struct EmptyIfVisitor {
-    comment_positions: Vec<u32>,
-    positions: Vec<((u32, u32), EmptyIfKind)>,
-}
-
 impl Visitor for EmptyIfVisitor {
     fn visit_if(&mut self, if_block: &ast::If) {
         if block_is_empty(if_block.block()) {
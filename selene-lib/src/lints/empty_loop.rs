@@ -3,13 +3,13 @@ use crate::ast_util::range;
 use std::convert::Infallible;
 
 use full_moon::{
-    ast::{self, Ast},
+    ast,
     tokenizer::{Token, TokenKind},
     visitors::Visitor,
 };
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Default, Deserialize)]
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
 #[serde(default)]
 pub struct EmptyLoopLintConfig {
     comments_count: bool,
@@ -30,22 +30,35 @@ impl Lint for EmptyLoopLint {
         Ok(EmptyLoopLint { config })
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = EmptyLoopVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(EmptyLoopVisitor {
             comment_positions: Vec::new(),
+            comments_count: self.config.comments_count,
             positions: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct EmptyLoopVisitor {
+    comment_positions: Vec<u32>,
+    comments_count: bool,
+    positions: Vec<(u32, u32)>,
+}
 
-        let comment_positions = visitor.comment_positions.clone();
+impl LintVisitor for EmptyLoopVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        let comment_positions = self.comment_positions;
+        let comments_count = self.comments_count;
 
-        visitor
-            .positions
+        self.positions
             .into_iter()
             .filter(|position| {
                 // OPTIMIZE: This is O(n^2), can we optimize this?
-                if self.config.comments_count {
+                if comments_count {
                     !comment_positions.iter().any(|comment_position| {
                         position.0 <= *comment_position && position.1 >= *comment_position
                     })
@@ -64,11 +77,6 @@ impl Lint for EmptyLoopLint {
     }
 }
 
-struct EmptyLoopVisitor {
-    comment_positions: Vec<u32>,
-    positions: Vec<(u32, u32)>,
-}
-
 fn block_is_empty(block: &ast::Block) -> bool {
     block.last_stmt().is_none() && block.stmts().next().is_none()
 }
@@ -3,13 +3,13 @@ use crate::ast_util::range;
 use std::convert::Infallible;
 
 use full_moon::{
-    ast::{self, Ast, TableConstructor},
+    ast::{self, TableConstructor},
     visitors::Visitor,
 };
 
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Copy, Deserialize)]
+#[derive(Clone, Copy, Deserialize, Serialize)]
 pub struct HighCyclomaticComplexityConfig {
     maximum_complexity: u16,
 }
@@ -39,16 +39,26 @@ impl Lint for HighCyclomaticComplexityLint {
         Ok(HighCyclomaticComplexityLint { config })
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = HighCyclomaticComplexityVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(HighCyclomaticComplexityVisitor {
             positions: Vec::new(),
             config: self.config,
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct HighCyclomaticComplexityVisitor {
+    positions: Vec<((u32, u32), u16)>,
+    config: HighCyclomaticComplexityConfig,
+}
 
-        visitor
-            .positions
+impl LintVisitor for HighCyclomaticComplexityVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.positions
             .into_iter()
             .map(|(position, complexity)| {
                 Diagnostic::new(
@@ -64,11 +74,6 @@ impl Lint for HighCyclomaticComplexityLint {
     }
 }
 
-struct HighCyclomaticComplexityVisitor {
-    positions: Vec<((u32, u32), u16)>,
-    config: HighCyclomaticComplexityConfig,
-}
-
 fn count_table_complexity(table: &TableConstructor, starting_complexity: u16) -> u16 {
     let mut complexity = starting_complexity;
 
@@ -2,11 +2,7 @@ use super::*;
 use crate::ast_util::range;
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    node::Node,
-    visitors::Visitor,
-};
+use full_moon::{ast, node::Node, visitors::Visitor};
 
 pub struct IfSameThenElseLint;
 
@@ -21,16 +17,25 @@ impl Lint for IfSameThenElseLint {
         Ok(IfSameThenElseLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = IfSameThenElseVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(IfSameThenElseVisitor {
             positions: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct IfSameThenElseVisitor {
+    positions: Vec<((u32, u32), (u32, u32))>,
+}
 
-        visitor
-            .positions
-            .drain(..)
+impl LintVisitor for IfSameThenElseVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.positions
+            .into_iter()
             .map(|position| {
                 Diagnostic::new_complete(
                     "if_same_then_else",
@@ -47,10 +52,6 @@ impl Lint for IfSameThenElseLint {
     }
 }
 
-struct IfSameThenElseVisitor {
-    positions: Vec<((u32, u32), (u32, u32))>,
-}
-
 impl Visitor for IfSameThenElseVisitor {
     fn visit_if(&mut self, if_block: &ast::If) {
         let else_ifs = if_block
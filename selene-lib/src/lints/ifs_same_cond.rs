@@ -2,11 +2,7 @@ use super::*;
 use crate::ast_util::{range, HasSideEffects};
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    node::Node,
-    visitors::Visitor,
-};
+use full_moon::{ast, node::Node, visitors::Visitor};
 
 pub struct IfsSameCondLint;
 
@@ -21,16 +17,25 @@ impl Lint for IfsSameCondLint {
         Ok(IfsSameCondLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = IfsSameCondVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(IfsSameCondVisitor {
             positions: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct IfsSameCondVisitor {
+    positions: Vec<((u32, u32), (u32, u32))>,
+}
 
-        visitor
-            .positions
-            .drain(..)
+impl LintVisitor for IfsSameCondVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.positions
+            .into_iter()
             .map(|position| {
                 Diagnostic::new_complete(
                     "ifs_same_cond",
@@ -47,10 +52,6 @@ impl Lint for IfsSameCondLint {
     }
 }
 
-struct IfsSameCondVisitor {
-    positions: Vec<((u32, u32), (u32, u32))>,
-}
-
 impl Visitor for IfsSameCondVisitor {
     fn visit_if(&mut self, if_block: &ast::If) {
         if let Some(else_ifs) = if_block.else_if() {
@@ -8,7 +8,7 @@ use crate::ast_util::{
 };
 
 use super::*;
-use std::convert::Infallible;
+use std::{collections::HashSet, convert::Infallible};
 
 pub struct ManualTableCloneLint;
 
@@ -36,6 +36,7 @@ impl Lint for ManualTableCloneLint {
             matches: Vec::new(),
             loop_tracker: LoopTracker::new(ast),
             scope_manager: &ast_context.scope_manager,
+            single_local_assignments: HashSet::new(),
             stmt_begins: Vec::new(),
         };
 
@@ -56,20 +57,39 @@ struct ManualTableCloneMatch {
     looping_over: String,
     loop_type: LoopType,
     replaces_definition_range: Option<(usize, usize)>,
+    single_definition: bool,
 }
 
 impl ManualTableCloneMatch {
     fn into_diagnostic(self) -> Diagnostic {
-        Diagnostic::new_complete(
+        let replacement = format!(
+            "local {} = table.clone({})",
+            self.assigning_into.trim(),
+            self.looping_over.trim()
+        );
+
+        // If the definition can't be replaced along with the loop, we can't offer a fix
+        // without possibly breaking code between the two.
+        let suggestion = if self.replaces_definition_range.is_none() && self.single_definition {
+            Some(Suggestion::replace(
+                format!("replace with `{replacement}`"),
+                self.range,
+                replacement.clone(),
+                match self.loop_type {
+                    LoopType::Ipairs => Applicability::MaybeIncorrect,
+                    LoopType::Other => Applicability::MachineApplicable,
+                },
+            ))
+        } else {
+            None
+        };
+
+        let diagnostic = Diagnostic::new_complete(
             "manual_table_clone",
             "manual implementation of table.clone".to_owned(),
             Label::new(self.range),
             {
-                let mut notes = vec![format!(
-                    "try `local {} = table.clone({})`",
-                    self.assigning_into.trim(),
-                    self.looping_over.trim()
-                )];
+                let mut notes = vec![format!("try `{replacement}`")];
 
                 if matches!(self.loop_type, LoopType::Ipairs) {
                     notes.push("if this is a mixed table, then table.clone is not equivalent, as ipairs only goes over the array portion.\n\
@@ -86,7 +106,12 @@ impl ManualTableCloneMatch {
             } else {
                 Vec::new()
             },
-        )
+        );
+
+        match suggestion {
+            Some(suggestion) => diagnostic.with_suggestion(suggestion),
+            None => diagnostic,
+        }
     }
 }
 
@@ -94,6 +119,7 @@ struct ManualTableCloneVisitor<'ast> {
     matches: Vec<ManualTableCloneMatch>,
     loop_tracker: LoopTracker,
     scope_manager: &'ast ScopeManager,
+    single_local_assignments: HashSet<(usize, usize)>,
     stmt_begins: Vec<usize>,
 }
 
@@ -397,10 +423,19 @@ impl Visitor for ManualTableCloneVisitor<'_> {
             } else {
                 None
             },
+            single_definition: self
+                .single_local_assignments
+                .contains(&(*definition_start, *definition_end)),
             loop_type,
         });
     }
 
+    fn visit_local_assignment(&mut self, node: &ast::LocalAssignment) {
+        if node.names().len() == 1 {
+            self.single_local_assignments.insert(range(node));
+        }
+    }
+
     fn visit_stmt_end(&mut self, stmt: &ast::Stmt) {
         self.stmt_begins.push(range(stmt).0);
     }
@@ -1,14 +1,10 @@
 use super::*;
 use std::{collections::HashSet, convert::Infallible};
 
-use full_moon::{
-    ast::{self, Ast},
-    node::Node,
-    visitors::Visitor,
-};
-use serde::Deserialize;
-
-#[derive(Clone, Copy, Default, Deserialize)]
+use full_moon::{ast, node::Node, visitors::Visitor};
+use serde::{Deserialize, Serialize};
+
+#[derive(Clone, Copy, Default, Deserialize, Serialize)]
 pub struct MultipleStatementsConfig {
     one_line_if: OneLineIf,
 }
@@ -17,7 +13,7 @@ pub struct MultipleStatementsLint {
     config: MultipleStatementsConfig,
 }
 
-#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize)]
+#[derive(Clone, Copy, Default, PartialEq, Eq, Deserialize, Serialize)]
 #[serde(rename_all = "kebab-case")]
 pub enum OneLineIf {
     Allow,
@@ -37,16 +33,29 @@ impl Lint for MultipleStatementsLint {
         Ok(MultipleStatementsLint { config })
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = MultipleStatementsVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(MultipleStatementsVisitor {
             config: self.config,
             ..MultipleStatementsVisitor::default()
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+#[derive(Default)]
+struct MultipleStatementsVisitor {
+    config: MultipleStatementsConfig,
+    if_lines: HashSet<usize>,
+    lines_with_stmt: HashSet<usize>,
+    positions: Vec<(usize, usize)>,
+}
 
-        visitor
-            .positions
+impl LintVisitor for MultipleStatementsVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.positions
             .iter()
             .map(|position| {
                 Diagnostic::new(
@@ -59,14 +68,6 @@ impl Lint for MultipleStatementsLint {
     }
 }
 
-#[derive(Default)]
-struct MultipleStatementsVisitor {
-    config: MultipleStatementsConfig,
-    if_lines: HashSet<usize>,
-    lines_with_stmt: HashSet<usize>,
-    positions: Vec<(usize, usize)>,
-}
-
 impl MultipleStatementsVisitor {
     fn prepare_if(&mut self, if_block: &ast::If) {
         let line = if_block.then_token().end_position().unwrap().line();
@@ -2,10 +2,7 @@ use super::*;
 use crate::ast_util::range;
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    visitors::Visitor,
-};
+use full_moon::{ast, visitors::Visitor};
 
 pub struct ParentheseConditionsLint;
 
@@ -20,15 +17,24 @@ impl Lint for ParentheseConditionsLint {
         Ok(ParentheseConditionsLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = ParentheseConditionsVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(ParentheseConditionsVisitor {
             positions: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct ParentheseConditionsVisitor {
+    positions: Vec<(usize, usize)>,
+}
 
-        visitor
-            .positions
+impl LintVisitor for ParentheseConditionsVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.positions
             .iter()
             .map(|position| {
                 Diagnostic::new(
@@ -41,10 +47,6 @@ impl Lint for ParentheseConditionsLint {
     }
 }
 
-struct ParentheseConditionsVisitor {
-    positions: Vec<(usize, usize)>,
-}
-
 impl ParentheseConditionsVisitor {
     fn lint_condition(&mut self, condition: &ast::Expression) {
         let is_parentheses = match condition {
@@ -2,10 +2,7 @@ use super::*;
 use crate::ast_util::range;
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    visitors::Visitor,
-};
+use full_moon::{ast, visitors::Visitor};
 
 pub struct Color3BoundsLint;
 
@@ -20,17 +17,27 @@ impl Lint for Color3BoundsLint {
         Ok(Color3BoundsLint)
     }
 
-    fn pass(&self, ast: &Ast, context: &Context, _: &AstContext) -> Vec<Diagnostic> {
+    fn visitor<'a>(
+        &'a self,
+        context: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
         if !context.is_roblox() {
-            return Vec::new();
+            return None;
         }
 
-        let mut visitor = Color3BoundsVisitor::default();
+        Some(Box::new(Color3BoundsVisitor::default()))
+    }
+}
 
-        visitor.visit_ast(ast);
+#[derive(Default)]
+struct Color3BoundsVisitor {
+    positions: Vec<(usize, usize)>,
+}
 
-        visitor
-            .positions
+impl LintVisitor for Color3BoundsVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.positions
             .iter()
             .map(|position| {
                 Diagnostic::new_complete(
@@ -45,11 +52,6 @@ impl Lint for Color3BoundsLint {
     }
 }
 
-#[derive(Default)]
-struct Color3BoundsVisitor {
-    positions: Vec<(usize, usize)>,
-}
-
 impl Visitor for Color3BoundsVisitor {
     fn visit_function_call(&mut self, call: &ast::FunctionCall) {
         if_chain::if_chain! {
@@ -9,7 +9,7 @@ use std::{
 };
 
 use full_moon::{
-    ast::{self, Ast},
+    ast,
     tokenizer::{TokenReference, TokenType},
     visitors::Visitor,
 };
@@ -60,32 +60,38 @@ impl Lint for IncorrectRoactUsageLint {
         Ok(IncorrectRoactUsageLint)
     }
 
-    fn pass(&self, ast: &Ast, context: &Context, _: &AstContext) -> Vec<Diagnostic> {
+    fn visitor<'a>(
+        &'a self,
+        context: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
         if !context.is_roblox() {
-            return Vec::new();
+            return None;
         }
 
         let roblox_classes = &context.standard_library.roblox_classes;
 
         // Old roblox standard library
         if roblox_classes.is_empty() {
-            return Vec::new();
+            return None;
         }
 
-        let mut visitor = IncorrectRoactUsageVisitor {
+        Some(Box::new(IncorrectRoactUsageVisitor {
             definitions_of_create_element: HashMap::new(),
             invalid_events: Vec::new(),
             invalid_properties: Vec::new(),
             unknown_class: Vec::new(),
 
             roblox_classes,
-        };
-
-        visitor.visit_ast(ast);
+        }))
+    }
+}
 
+impl LintVisitor for IncorrectRoactUsageVisitor<'_> {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
         let mut diagnostics = Vec::new();
 
-        for invalid_event in visitor.invalid_events {
+        for invalid_event in self.invalid_events {
             diagnostics.push(Diagnostic::new(
                 "roblox_incorrect_roact_usage",
                 format!(
@@ -96,7 +102,7 @@ impl Lint for IncorrectRoactUsageLint {
             ));
         }
 
-        for invalid_property in visitor.invalid_properties {
+        for invalid_property in self.invalid_properties {
             match invalid_property.property_name.as_str() {
                 "Name" => {
                     diagnostics.push(Diagnostic::new_complete(
@@ -127,7 +133,7 @@ impl Lint for IncorrectRoactUsageLint {
             }
         }
 
-        for unknown_class in visitor.unknown_class {
+        for unknown_class in self.unknown_class {
             diagnostics.push(Diagnostic::new(
                 "roblox_incorrect_roact_usage",
                 format!("`{}` is not a valid class", unknown_class.name),
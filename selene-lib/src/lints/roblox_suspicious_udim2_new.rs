@@ -2,10 +2,7 @@ use super::*;
 use crate::ast_util::range;
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    visitors::Visitor,
-};
+use full_moon::{ast, visitors::Visitor};
 
 pub struct SuspiciousUDim2NewLint;
 
@@ -51,16 +48,16 @@ impl Lint for SuspiciousUDim2NewLint {
         Ok(SuspiciousUDim2NewLint)
     }
 
-    fn pass(&self, ast: &Ast, context: &Context, _: &AstContext) -> Vec<Diagnostic> {
+    fn visitor<'a>(
+        &'a self,
+        context: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
         if !context.is_roblox() {
-            return Vec::new();
+            return None;
         }
 
-        let mut visitor = UDim2CountVisitor::default();
-
-        visitor.visit_ast(ast);
-
-        visitor.args.iter().map(create_diagnostic).collect()
+        Some(Box::new(UDim2CountVisitor::default()))
     }
 }
 
@@ -69,6 +66,12 @@ struct UDim2CountVisitor {
     args: Vec<MismatchedArgCount>,
 }
 
+impl LintVisitor for UDim2CountVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.args.iter().map(create_diagnostic).collect()
+    }
+}
+
 struct MismatchedArgCount {
     args_provided: usize,
     call_range: (usize, usize),
@@ -2,9 +2,9 @@ use super::*;
 
 use full_moon::ast::Ast;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct ShadowingConfig {
     ignore_pattern: String,
@@ -6,7 +6,7 @@ use crate::{
 use std::convert::Infallible;
 
 use full_moon::{
-    ast::{self, Ast},
+    ast,
     node::Node,
     tokenizer::{Position, Symbol, TokenType},
     visitors::Visitor,
@@ -25,17 +25,17 @@ impl Lint for StandardLibraryLint {
         Ok(StandardLibraryLint)
     }
 
-    fn pass(&self, ast: &Ast, context: &Context, ast_context: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = StandardLibraryVisitor {
+    fn visitor<'a>(
+        &'a self,
+        context: &'a Context,
+        ast_context: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(StandardLibraryVisitor {
             diagnostics: Vec::new(),
             scope_manager: &ast_context.scope_manager,
             standard_library: &context.standard_library,
             user_set_standard_library: &context.user_set_standard_library,
-        };
-
-        visitor.visit_ast(ast);
-
-        visitor.diagnostics
+        }))
     }
 }
 
@@ -192,6 +192,63 @@ fn get_argument_type(expression: &ast::Expression) -> Option<PassedArgumentType>
     }
 }
 
+// Returns the range and constant type, if there is one, of every argument passed to a function
+pub(crate) fn get_argument_types(
+    function_args: &ast::FunctionArgs,
+) -> Vec<((Position, Position), Option<PassedArgumentType>)> {
+    let mut argument_types = Vec::new();
+
+    #[cfg_attr(
+        feature = "force_exhaustive_checks",
+        deny(non_exhaustive_omitted_patterns)
+    )]
+    match function_args {
+        ast::FunctionArgs::Parentheses { arguments, .. } => {
+            for argument in arguments {
+                argument_types.push((argument.range().unwrap(), get_argument_type(argument)));
+            }
+        }
+
+        ast::FunctionArgs::String(token) => {
+            argument_types.push((
+                token.range().unwrap(),
+                Some(PassedArgumentType::from_string(token.token().to_string())),
+            ));
+        }
+
+        ast::FunctionArgs::TableConstructor(table) => {
+            argument_types.push((table.range().unwrap(), Some(ArgumentType::Table.into())));
+        }
+
+        _ => {}
+    }
+
+    argument_types
+}
+
+// Whether the last argument is a function call or vararg, which can pass any number of arguments
+pub(crate) fn maybe_more_arguments(function_args: &ast::FunctionArgs) -> bool {
+    if let ast::FunctionArgs::Parentheses { arguments, .. } = function_args {
+        if let Some(ast::punctuated::Pair::End(ast::Expression::Value { value, .. })) =
+            arguments.last()
+        {
+            match &**value {
+                ast::Value::FunctionCall(_) => return true,
+
+                ast::Value::Symbol(token_ref) => {
+                    if let TokenType::Symbol { symbol } = token_ref.token().token_type() {
+                        return symbol == &full_moon::tokenizer::Symbol::Ellipse;
+                    }
+                }
+
+                _ => {}
+            }
+        }
+    }
+
+    false
+}
+
 pub struct StandardLibraryVisitor<'std> {
     diagnostics: Vec<Diagnostic>,
     scope_manager: &'std ScopeManager,
@@ -199,6 +256,12 @@ pub struct StandardLibraryVisitor<'std> {
     user_set_standard_library: &'std Option<Vec<String>>,
 }
 
+impl LintVisitor for StandardLibraryVisitor<'_> {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.diagnostics
+    }
+}
+
 impl StandardLibraryVisitor<'_> {
     fn lint_invalid_field_access(
         &mut self,
@@ -477,32 +540,7 @@ impl Visitor for StandardLibraryVisitor<'_> {
             return;
         }
 
-        let mut argument_types = Vec::new();
-
-        #[cfg_attr(
-            feature = "force_exhaustive_checks",
-            deny(non_exhaustive_omitted_patterns)
-        )]
-        match function_args {
-            ast::FunctionArgs::Parentheses { arguments, .. } => {
-                for argument in arguments {
-                    argument_types.push((argument.range().unwrap(), get_argument_type(argument)));
-                }
-            }
-
-            ast::FunctionArgs::String(token) => {
-                argument_types.push((
-                    token.range().unwrap(),
-                    Some(PassedArgumentType::from_string(token.token().to_string())),
-                ));
-            }
-
-            ast::FunctionArgs::TableConstructor(table) => {
-                argument_types.push((table.range().unwrap(), Some(ArgumentType::Table.into())));
-            }
-
-            _ => {}
-        }
+        let argument_types = get_argument_types(function_args);
 
         let mut expected_args = function
             .arguments
@@ -513,29 +551,7 @@ impl Visitor for StandardLibraryVisitor<'_> {
         let mut vararg = false;
         let mut max_args = function.arguments.len();
 
-        let mut maybe_more_arguments = false;
-
-        if let ast::FunctionArgs::Parentheses { arguments, .. } = function_args {
-            if let Some(ast::punctuated::Pair::End(ast::Expression::Value { value, .. })) =
-                arguments.last()
-            {
-                match &**value {
-                    ast::Value::FunctionCall(_) => {
-                        maybe_more_arguments = true;
-                    }
-
-                    ast::Value::Symbol(token_ref) => {
-                        if let TokenType::Symbol { symbol } = token_ref.token().token_type() {
-                            if symbol == &full_moon::tokenizer::Symbol::Ellipse {
-                                maybe_more_arguments = true;
-                            }
-                        }
-                    }
-
-                    _ => {}
-                }
-            }
-        };
+        let maybe_more_arguments = maybe_more_arguments(function_args);
 
         if let Some(last) = function.arguments.last() {
             if last.argument_type == ArgumentType::Vararg {
@@ -631,7 +647,7 @@ impl Visitor for StandardLibraryVisitor<'_> {
 }
 
 #[derive(Debug, PartialEq, Eq)]
-enum PassedArgumentType {
+pub(crate) enum PassedArgumentType {
     Primitive(ArgumentType),
     String(String),
 }
@@ -642,7 +658,7 @@ impl PassedArgumentType {
         PassedArgumentType::String(string.chars().skip(1).collect())
     }
 
-    fn matches(&self, argument_type: &ArgumentType) -> bool {
+    pub(crate) fn matches(&self, argument_type: &ArgumentType) -> bool {
         if argument_type == &ArgumentType::Any {
             return true;
         }
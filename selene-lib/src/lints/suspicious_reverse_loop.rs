@@ -1,11 +1,7 @@
 use super::*;
 use std::{convert::Infallible, str};
 
-use full_moon::{
-    ast::{self, Ast},
-    node::Node,
-    visitors::Visitor,
-};
+use full_moon::{ast, node::Node, visitors::Visitor};
 
 pub struct SuspiciousReverseLoopLint;
 
@@ -20,15 +16,24 @@ impl Lint for SuspiciousReverseLoopLint {
         Ok(SuspiciousReverseLoopLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = SuspiciousReverseLoopVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(SuspiciousReverseLoopVisitor {
             positions: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct SuspiciousReverseLoopVisitor {
+    positions: Vec<(usize, usize)>,
+}
 
-        visitor
-            .positions
+impl LintVisitor for SuspiciousReverseLoopVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.positions
             .iter()
             .map(|position| {
                 Diagnostic::new_complete(
@@ -43,10 +48,6 @@ impl Lint for SuspiciousReverseLoopLint {
     }
 }
 
-struct SuspiciousReverseLoopVisitor {
-    positions: Vec<(usize, usize)>,
-}
-
 impl Visitor for SuspiciousReverseLoopVisitor {
     fn visit_numeric_for(&mut self, node: &ast::NumericFor) {
         if_chain::if_chain! {
@@ -1,4 +1,4 @@
-use super::{AstContext, Context, Lint};
+use super::{apply_suggestions, AstContext, Context, Lint};
 use crate::{
     test_util::{get_standard_library, PrettyString},
     StandardLibrary,
@@ -73,10 +73,32 @@ pub fn test_lint_config_with_output<
     );
 
     let mut files = codespan::Files::new();
-    let source_id = files.add(format!("{test_name}.lua"), lua_source);
+    let source_id = files.add(format!("{test_name}.lua"), lua_source.as_str());
 
     diagnostics.sort_by_key(|diagnostic| diagnostic.primary_label.range);
 
+    let suggestions = diagnostics
+        .iter()
+        .flat_map(|diagnostic| &diagnostic.suggestions)
+        .collect::<Vec<_>>();
+
+    if !suggestions.is_empty() {
+        let (fixed_source, _) = apply_suggestions(&lua_source, suggestions);
+        assert!(
+            full_moon::parse(&fixed_source).is_ok(),
+            "applying suggestions did not produce valid code:\n{fixed_source}"
+        );
+
+        let fixed_path =
+            path_base.with_extension(output_extension.replacen("stderr", "fixed.lua", 1));
+
+        if let Ok(expected) = fs::read_to_string(&fixed_path) {
+            pretty_assertions::assert_eq!(PrettyString(&expected), PrettyString(&fixed_source));
+        } else {
+            fs::write(fixed_path, fixed_source).expect("couldn't write to fixed file");
+        }
+    }
+
     let mut output = termcolor::NoColor::new(Vec::new());
 
     for diagnostic in diagnostics
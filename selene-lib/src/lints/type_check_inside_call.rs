@@ -2,10 +2,7 @@ use super::*;
 use crate::ast_util::{is_type_function, range};
 use std::convert::Infallible;
 
-use full_moon::{
-    ast::{self, Ast},
-    visitors::Visitor,
-};
+use full_moon::{ast, visitors::Visitor};
 
 pub struct TypeCheckInsideCallLint;
 
@@ -20,16 +17,26 @@ impl Lint for TypeCheckInsideCallLint {
         Ok(TypeCheckInsideCallLint)
     }
 
-    fn pass(&self, ast: &Ast, context: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = TypeCheckInsideCallVisitor {
+    fn visitor<'a>(
+        &'a self,
+        context: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(TypeCheckInsideCallVisitor {
             positions: Vec::new(),
             roblox: context.is_roblox(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct TypeCheckInsideCallVisitor {
+    positions: Vec<(usize, usize)>,
+    roblox: bool,
+}
 
-        visitor
-            .positions
+impl LintVisitor for TypeCheckInsideCallVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.positions
             .iter()
             .map(|position| {
                 Diagnostic::new_complete(
@@ -44,11 +51,6 @@ impl Lint for TypeCheckInsideCallLint {
     }
 }
 
-struct TypeCheckInsideCallVisitor {
-    positions: Vec<(usize, usize)>,
-    roblox: bool,
-}
-
 impl Visitor for TypeCheckInsideCallVisitor {
     fn visit_function_call(&mut self, call: &ast::FunctionCall) {
         if_chain::if_chain! {
@@ -2,7 +2,7 @@ use super::*;
 use std::convert::Infallible;
 
 use full_moon::{
-    ast::{self, punctuated::Punctuated, Ast},
+    ast::{self, punctuated::Punctuated},
     node::Node,
     tokenizer::{Symbol, TokenType},
     visitors::Visitor,
@@ -21,16 +21,25 @@ impl Lint for UnbalancedAssignmentsLint {
         Ok(UnbalancedAssignmentsLint)
     }
 
-    fn pass(&self, ast: &Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
-        let mut visitor = UnbalancedAssignmentsVisitor {
+    fn visitor<'a>(
+        &'a self,
+        _: &'a Context,
+        _: &'a AstContext,
+    ) -> Option<Box<dyn LintVisitor + 'a>> {
+        Some(Box::new(UnbalancedAssignmentsVisitor {
             assignments: Vec::new(),
-        };
+        }))
+    }
+}
 
-        visitor.visit_ast(ast);
+struct UnbalancedAssignmentsVisitor {
+    assignments: Vec<UnbalancedAssignment>,
+}
 
-        visitor
-            .assignments
-            .drain(..)
+impl LintVisitor for UnbalancedAssignmentsVisitor {
+    fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+        self.assignments
+            .into_iter()
             .map(|assignment| {
                 if assignment.more {
                     Diagnostic::new(
@@ -63,10 +72,6 @@ impl Lint for UnbalancedAssignmentsLint {
     }
 }
 
-struct UnbalancedAssignmentsVisitor {
-    assignments: Vec<UnbalancedAssignment>,
-}
-
 fn expression_is_call(expression: &ast::Expression) -> bool {
     match expression {
         ast::Expression::Parentheses { expression, .. } => expression_is_call(expression),
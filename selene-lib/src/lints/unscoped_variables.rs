@@ -5,9 +5,9 @@ use std::collections::HashSet;
 
 use full_moon::ast::Ast;
 use regex::Regex;
-use serde::Deserialize;
+use serde::{Deserialize, Serialize};
 
-#[derive(Clone, Deserialize)]
+#[derive(Clone, Deserialize, Serialize)]
 #[serde(default)]
 pub struct UnscopedVariablesConfig {
     ignore_pattern: String,
@@ -0,0 +1,22 @@
+use super::*;
+use std::convert::Infallible;
+
+// This is a shell lint, like invalid_lint_filter. Filters are only known to be unused once they
+// have been applied, so this is reported by lint_filtering.rs
+pub struct UnusedLintFilterLint;
+
+impl Lint for UnusedLintFilterLint {
+    type Config = ();
+    type Error = Infallible;
+
+    const SEVERITY: Severity = Severity::Warning;
+    const LINT_TYPE: LintType = LintType::Style;
+
+    fn new(_: Self::Config) -> Result<Self, Self::Error> {
+        Ok(UnusedLintFilterLint)
+    }
+
+    fn pass(&self, _: &full_moon::ast::Ast, _: &Context, _: &AstContext) -> Vec<Diagnostic> {
+        Vec::new()
+    }
+}
@@ -0,0 +1,143 @@
+//! What selene knows about luacheck, for projects that are migrating from it.
+
+/// The luacheck warnings that each lint is equivalent to.
+pub const LINT_WARNINGS: &[(&str, &[&str])] = &[
+    ("unscoped_variables", &["111"]),
+    ("undefined_variable", &["112", "113"]),
+    (
+        "unused_variable",
+        &["211", "212", "213", "221", "231", "232", "233", "241"],
+    ),
+    (
+        "shadowing",
+        &[
+            "411", "412", "413", "421", "422", "423", "431", "432", "433",
+        ],
+    ),
+    ("unbalanced_assignments", &["531", "532"]),
+    ("empty_if", &["542"]),
+    ("high_cyclomatic_complexity", &["561"]),
+    ("suspicious_reverse_loop", &["571"]),
+];
+
+/// Whether a luacheck warning pattern, such as `21` or `4..`, matches a warning.
+/// Patterns only need to match the start of the warning.
+pub fn code_matches(pattern: &str, code: &str) -> bool {
+    pattern.len() <= code.len()
+        && pattern
+            .chars()
+            .zip(code.chars())
+            .all(|(pattern, code)| pattern == '.' || pattern == code)
+}
+
+/// The lints equivalent to any warning a luacheck warning pattern matches.
+pub fn lints_matching(pattern: &str) -> impl Iterator<Item = &'static str> + '_ {
+    LINT_WARNINGS
+        .iter()
+        .filter(move |(_, codes)| codes.iter().any(|code| code_matches(pattern, code)))
+        .map(|(lint, _)| *lint)
+}
+
+fn class_matches(class: char, character: char) -> bool {
+    let matches = match class.to_ascii_lowercase() {
+        'a' => character.is_ascii_alphabetic(),
+        'd' => character.is_ascii_digit(),
+        'l' => character.is_ascii_lowercase(),
+        'p' => character.is_ascii_punctuation(),
+        's' => character.is_ascii_whitespace(),
+        'u' => character.is_ascii_uppercase(),
+        'w' => character.is_ascii_alphanumeric(),
+        _ => return class == character,
+    };
+
+    // Uppercase classes, such as `%A`, are the complement
+    matches != class.is_ascii_uppercase()
+}
+
+fn pattern_matches_from(pattern: &[char], name: &[char]) -> bool {
+    let Some(&first) = pattern.first() else {
+        return name.is_empty();
+    };
+
+    let (item_length, item_matches): (usize, &dyn Fn(char) -> bool) = match first {
+        '.' => (1, &|_| true),
+        '%' if pattern.len() > 1 => (2, &|character| class_matches(pattern[1], character)),
+        literal => (1, &move |character| character == literal),
+    };
+
+    let rest = &pattern[item_length..];
+
+    match rest.first() {
+        Some('*') | Some('-') => {
+            let rest = &rest[1..];
+            let matching = name
+                .iter()
+                .take_while(|&&character| item_matches(character));
+            (0..=matching.count()).any(|count| pattern_matches_from(rest, &name[count..]))
+        }
+
+        Some('+') => {
+            let rest = &rest[1..];
+            let matching = name
+                .iter()
+                .take_while(|&&character| item_matches(character));
+            (1..=matching.count()).any(|count| pattern_matches_from(rest, &name[count..]))
+        }
+
+        Some('?') => {
+            let rest = &rest[1..];
+            (matches!(name.first(), Some(&character) if item_matches(character))
+                && pattern_matches_from(rest, &name[1..]))
+                || pattern_matches_from(rest, name)
+        }
+
+        _ => {
+            matches!(name.first(), Some(&character) if item_matches(character))
+                && pattern_matches_from(rest, &name[1..])
+        }
+    }
+}
+
+/// Whether a name matches a luacheck name pattern, such as `_.*`. These are Lua patterns that
+/// have to match the whole name.
+pub fn name_matches(pattern: &str, name: &str) -> bool {
+    let pattern = pattern.chars().collect::<Vec<_>>();
+    let name = name.chars().collect::<Vec<_>>();
+    pattern_matches_from(&pattern, &name)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_code_matches() {
+        assert!(code_matches("212", "212"));
+        assert!(code_matches("21", "212"));
+        assert!(code_matches("4..", "421"));
+        assert!(!code_matches("213", "212"));
+        assert!(!code_matches("2122", "212"));
+
+        assert_eq!(
+            lints_matching("211").collect::<Vec<_>>(),
+            ["unused_variable"]
+        );
+        assert_eq!(
+            lints_matching("1").collect::<Vec<_>>(),
+            ["unscoped_variables", "undefined_variable"]
+        );
+    }
+
+    #[test]
+    fn test_name_matches() {
+        assert!(name_matches("foo", "foo"));
+        assert!(!name_matches("foo", "food"));
+        assert!(name_matches("_.*", "_unused"));
+        assert!(name_matches("_.*", "_"));
+        assert!(!name_matches("_.*", "used"));
+        assert!(name_matches("self%d+", "self12"));
+        assert!(!name_matches("self%d+", "self"));
+        assert!(name_matches("colou?r", "color"));
+        assert!(name_matches("%a%w*", "x1"));
+    }
+}
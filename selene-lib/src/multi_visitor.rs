@@ -0,0 +1,207 @@
+//! Runs the visitors of many lints in a single traversal of the AST, rather than having every lint
+//! walk the whole AST on its own.
+
+use full_moon::{
+    ast::{span::ContainedSpan, *},
+    tokenizer::{Token, TokenReference},
+    visitors::Visitor,
+};
+
+#[cfg(feature = "roblox")]
+use full_moon::ast::types::*;
+
+use crate::lints::{Diagnostic, LintVisitor};
+
+struct MultiVisitor<'a> {
+    visitors: Vec<Box<dyn LintVisitor + 'a>>,
+}
+
+macro_rules! forward_nodes {
+    ($($visit_name:ident => $ast_type:ty,)+) => {
+        paste::item! {
+            $(
+                fn $visit_name(&mut self, node: &$ast_type) {
+                    for visitor in &mut self.visitors {
+                        visitor.$visit_name(node);
+                    }
+                }
+
+                fn [<$visit_name _end>](&mut self, node: &$ast_type) {
+                    for visitor in &mut self.visitors {
+                        visitor.[<$visit_name _end>](node);
+                    }
+                }
+            )+
+        }
+    };
+}
+
+macro_rules! forward_tokens {
+    ($($visit_name:ident,)+) => {
+        $(
+            fn $visit_name(&mut self, token: &Token) {
+                for visitor in &mut self.visitors {
+                    visitor.$visit_name(token);
+                }
+            }
+        )+
+    };
+}
+
+// Every method of `Visitor` has to be forwarded, otherwise lints would silently miss nodes
+impl Visitor for MultiVisitor<'_> {
+    forward_nodes! {
+        visit_anonymous_call => FunctionArgs,
+        visit_assignment => Assignment,
+        visit_block => Block,
+        visit_call => Call,
+        visit_contained_span => ContainedSpan,
+        visit_do => Do,
+        visit_else_if => ElseIf,
+        visit_eof => TokenReference,
+        visit_expression => Expression,
+        visit_field => Field,
+        visit_function_args => FunctionArgs,
+        visit_function_body => FunctionBody,
+        visit_function_call => FunctionCall,
+        visit_function_declaration => FunctionDeclaration,
+        visit_function_name => FunctionName,
+        visit_generic_for => GenericFor,
+        visit_if => If,
+        visit_index => Index,
+        visit_local_assignment => LocalAssignment,
+        visit_local_function => LocalFunction,
+        visit_last_stmt => LastStmt,
+        visit_method_call => MethodCall,
+        visit_numeric_for => NumericFor,
+        visit_parameter => Parameter,
+        visit_prefix => Prefix,
+        visit_return => Return,
+        visit_repeat => Repeat,
+        visit_stmt => Stmt,
+        visit_suffix => Suffix,
+        visit_table_constructor => TableConstructor,
+        visit_token_reference => TokenReference,
+        visit_un_op => UnOp,
+        visit_value => Value,
+        visit_var => Var,
+        visit_var_expression => VarExpression,
+        visit_while => While,
+    }
+
+    #[cfg(feature = "roblox")]
+    forward_nodes! {
+        visit_compound_assignment => CompoundAssignment,
+        visit_compound_op => CompoundOp,
+        visit_else_if_expression => ElseIfExpression,
+        visit_exported_type_declaration => ExportedTypeDeclaration,
+        visit_generic_declaration => GenericDeclaration,
+        visit_generic_declaration_parameter => GenericDeclarationParameter,
+        visit_generic_parameter_info => GenericParameterInfo,
+        visit_if_expression => IfExpression,
+        visit_indexed_type_info => IndexedTypeInfo,
+        visit_interpolated_string => InterpolatedString,
+        visit_type_argument => TypeArgument,
+        visit_type_assertion => TypeAssertion,
+        visit_type_declaration => TypeDeclaration,
+        visit_type_field => TypeField,
+        visit_type_field_key => TypeFieldKey,
+        visit_type_info => TypeInfo,
+        visit_type_specifier => TypeSpecifier,
+    }
+
+    forward_tokens! {
+        visit_identifier,
+        visit_multi_line_comment,
+        visit_number,
+        visit_single_line_comment,
+        visit_string_literal,
+        visit_symbol,
+        visit_token,
+        visit_whitespace,
+    }
+
+    #[cfg(feature = "roblox")]
+    forward_tokens! {
+        visit_interpolated_string_segment,
+    }
+}
+
+/// Visits the AST once with every visitor, returning the diagnostics of each in the same order.
+pub(crate) fn visit_ast<'a>(
+    ast: &Ast,
+    visitors: Vec<Box<dyn LintVisitor + 'a>>,
+) -> Vec<Vec<Diagnostic>> {
+    let mut multi_visitor = MultiVisitor { visitors };
+    multi_visitor.visit_ast(ast);
+
+    multi_visitor
+        .visitors
+        .into_iter()
+        .map(LintVisitor::finish)
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    struct RecordingVisitor {
+        name: &'static str,
+        visits: Vec<String>,
+    }
+
+    impl Visitor for RecordingVisitor {
+        fn visit_local_assignment(&mut self, _: &LocalAssignment) {
+            self.visits.push("local_assignment".to_owned());
+        }
+
+        fn visit_function_call_end(&mut self, call: &FunctionCall) {
+            self.visits.push(format!("function_call_end {call}"));
+        }
+
+        fn visit_identifier(&mut self, token: &Token) {
+            self.visits.push(format!("identifier {token}"));
+        }
+    }
+
+    impl LintVisitor for RecordingVisitor {
+        fn finish(self: Box<Self>) -> Vec<Diagnostic> {
+            self.visits
+                .into_iter()
+                .map(|visit| Diagnostic::new(self.name, visit, crate::lints::Label::new((0, 0))))
+                .collect()
+        }
+    }
+
+    fn recording_visitor(name: &'static str) -> Box<dyn LintVisitor> {
+        Box::new(RecordingVisitor {
+            name,
+            visits: Vec::new(),
+        })
+    }
+
+    #[test]
+    fn test_visitors_see_every_node() {
+        let ast = full_moon::parse("local x = f(g(1))\nprint(x)").unwrap();
+
+        let alone = visit_ast(&ast, vec![recording_visitor("alone")]);
+        let together = visit_ast(
+            &ast,
+            vec![recording_visitor("first"), recording_visitor("second")],
+        );
+
+        let messages = |diagnostics: &[Diagnostic]| {
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.message.clone())
+                .collect::<Vec<_>>()
+        };
+
+        assert_eq!(together.len(), 2);
+        assert!(messages(&alone[0]).contains(&"function_call_end g(1)".to_owned()));
+        assert_eq!(messages(&alone[0]), messages(&together[0]));
+        assert_eq!(messages(&alone[0]), messages(&together[1]));
+        assert_eq!(together[1][0].code, "second");
+    }
+}
@@ -0,0 +1,651 @@
+//! Lints written in Lua, for rules that are specific to a project. Every file gets a new Lua state
+//! for each plugin, in which the plugin's `check` function is given a copy of the AST and its
+//! scopes, and returns diagnostics that are treated like those of any other lint.
+
+use std::{cell::Cell, error::Error, fmt};
+
+use full_moon::{ast::Ast, tokenizer::TokenType};
+use mlua::{Function, HookTriggers, Lua, LuaOptions, StdLib, Table, Value};
+use serde::{de::Deserializer, Deserialize};
+
+use crate::{
+    ast_util::scopes::{ReferenceWrite, ScopeManager},
+    lints::{self, AstContext, Context, Diagnostic, DynLint, Label, LintType, Severity},
+    LintInfo, LintRegistry, LintVariation,
+};
+
+const PRELUDE: &str = include_str!("plugins/prelude.lua");
+
+/// How much a plugin can do for a single file. A plugin that loops forever or keeps allocating
+/// fails for that file instead of stopping selene, and no reasonable plugin gets close to these.
+#[derive(Clone, Copy, Debug)]
+struct Limits {
+    instructions: u64,
+    memory: usize,
+}
+
+const LIMITS: Limits = Limits {
+    instructions: 1_000_000_000,
+    memory: 256 * 1024 * 1024,
+};
+
+// Counting every instruction would be slow, so the hook is only called this often
+const INSTRUCTIONS_PER_HOOK: u32 = 10_000;
+
+#[derive(Debug)]
+pub struct PluginError {
+    pub name: String,
+    pub problem: PluginErrorProblem,
+}
+
+#[derive(Debug)]
+pub enum PluginErrorProblem {
+    InvalidName,
+    AlreadyRegistered,
+    InvalidPlugin(String),
+    LuaError(mlua::Error),
+}
+
+impl fmt::Display for PluginError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        use PluginErrorProblem::*;
+
+        write!(formatter, "[{}] ", self.name)?;
+
+        match &self.problem {
+            InvalidName => write!(
+                formatter,
+                "plugins must be named with only letters, numbers, and underscores"
+            ),
+            AlreadyRegistered => write!(formatter, "a lint with the same name already exists"),
+            InvalidPlugin(problem) => write!(formatter, "{problem}"),
+            LuaError(error) => write!(formatter, "{error}"),
+        }
+    }
+}
+
+impl Error for PluginError {}
+
+/// Registers a plugin, which is named after its file, such as `no_dynamic_require` for
+/// `no_dynamic_require.lua`. Plugins are configured in `[config]` under that name, which is given
+/// to them as `file.config`.
+pub fn register_plugin<V>(
+    registry: &mut LintRegistry<V>,
+    name: &str,
+    source: &str,
+) -> Result<(), PluginError>
+where
+    V: 'static + for<'de> Deserializer<'de>,
+{
+    let error = |problem| PluginError {
+        name: name.to_owned(),
+        problem,
+    };
+
+    if name.is_empty()
+        || !name
+            .chars()
+            .all(|character| character.is_ascii_alphanumeric() || character == '_')
+    {
+        return Err(error(PluginErrorProblem::InvalidName));
+    }
+
+    if registry.lints().any(|lint| lint.name == name) {
+        return Err(error(PluginErrorProblem::AlreadyRegistered));
+    }
+
+    let (severity, lint_type) = plugin_info(name, source).map_err(error)?;
+
+    let name = lints::intern_lint_name(name);
+    let source = source.to_owned();
+
+    registry.register_dyn(
+        LintInfo::new(name, severity, lint_type),
+        move |config: Option<V>| {
+            let config = match config {
+                Some(config) => serde_json::Value::deserialize(config)?,
+                None => serde_json::Value::Null,
+            };
+
+            Ok(Box::new(PluginLint {
+                name,
+                source: source.clone(),
+                config,
+                limits: LIMITS,
+            }))
+        },
+    );
+
+    Ok(())
+}
+
+// The severity and category a plugin asks for, checking that it can be run at all
+fn plugin_info(name: &str, source: &str) -> Result<(Severity, LintType), PluginErrorProblem> {
+    let lua = create_lua(LIMITS).map_err(PluginErrorProblem::LuaError)?;
+
+    let plugin = match lua
+        .load(source)
+        .set_name(format!("{name}.lua"))
+        .eval::<Value>()
+        .map_err(PluginErrorProblem::LuaError)?
+    {
+        Value::Table(plugin) => plugin,
+        _ => {
+            return Err(PluginErrorProblem::InvalidPlugin(
+                "plugins must return a table".to_owned(),
+            ))
+        }
+    };
+
+    if !matches!(plugin.get("check"), Ok(Value::Function(_))) {
+        return Err(PluginErrorProblem::InvalidPlugin(
+            "plugins must have a `check` function".to_owned(),
+        ));
+    }
+
+    // The same as `severity` in `[[custom-lints]]`, and in `[lints]`
+    let severity = match plugin
+        .get::<_, Option<String>>("severity")
+        .map_err(PluginErrorProblem::LuaError)?
+        .as_deref()
+    {
+        None | Some("warn") => LintVariation::Warn,
+        Some("deny") => LintVariation::Deny,
+        Some("allow") => LintVariation::Allow,
+        Some(other) => {
+            return Err(PluginErrorProblem::InvalidPlugin(format!(
+                "unknown severity `{other}`, expected `deny`, `warn`, or `allow`"
+            )))
+        }
+    }
+    .to_severity();
+
+    let lint_type = match plugin
+        .get::<_, Option<String>>("category")
+        .map_err(PluginErrorProblem::LuaError)?
+    {
+        None => LintType::Style,
+        Some(category) => LintType::ALL
+            .into_iter()
+            .find(|lint_type| lint_type.name() == category)
+            .ok_or_else(|| {
+                PluginErrorProblem::InvalidPlugin(format!(
+                    "unknown category `{category}`, expected `complexity`, `correctness`, `performance`, or `style`"
+                ))
+            })?,
+    };
+
+    Ok((severity, lint_type))
+}
+
+// Plugins can't touch the file system, or anything else outside of the file they're given
+fn create_lua(limits: Limits) -> mlua::Result<Lua> {
+    let lua = Lua::new_with(
+        StdLib::MATH | StdLib::STRING | StdLib::TABLE | StdLib::UTF8,
+        LuaOptions::default(),
+    )?;
+
+    lua.set_memory_limit(limits.memory)?;
+
+    let instructions = Cell::new(0);
+    lua.set_hook(
+        HookTriggers::new().every_nth_instruction(INSTRUCTIONS_PER_HOOK),
+        move |lua, _| {
+            instructions.set(instructions.get() + u64::from(INSTRUCTIONS_PER_HOOK));
+            if instructions.get() <= limits.instructions {
+                return Ok(());
+            }
+
+            // From then on every instruction fails, so that `pcall` can't be used to carry on
+            let error = mlua::Error::RuntimeError(format!(
+                "ran for more than {} instructions",
+                limits.instructions
+            ));
+
+            lua.set_hook(HookTriggers::new().every_nth_instruction(1), {
+                let error = error.clone();
+                move |_, _| Err(error.clone())
+            });
+
+            Err(error)
+        },
+    );
+
+    lua.load(PRELUDE).set_name("selene").exec()?;
+    Ok(lua)
+}
+
+// Plugins see values the way serde gives them, with `null` as `nil`
+fn json_to_lua<'lua>(lua: &'lua Lua, value: &serde_json::Value) -> mlua::Result<Value<'lua>> {
+    Ok(match value {
+        serde_json::Value::Null => Value::Nil,
+        serde_json::Value::Bool(boolean) => Value::Boolean(*boolean),
+        serde_json::Value::Number(number) => match number.as_i64() {
+            Some(integer) => Value::Integer(integer),
+            None => Value::Number(number.as_f64().unwrap_or_default()),
+        },
+        serde_json::Value::String(string) => Value::String(lua.create_string(string)?),
+        serde_json::Value::Array(array) => Value::Table(
+            lua.create_sequence_from(
+                array
+                    .iter()
+                    .map(|value| json_to_lua(lua, value))
+                    .collect::<mlua::Result<Vec<_>>>()?,
+            )?,
+        ),
+        serde_json::Value::Object(object) => {
+            let table = lua.create_table_with_capacity(0, object.len())?;
+            for (key, value) in object {
+                table.raw_set(key.as_str(), json_to_lua(lua, value)?)?;
+            }
+
+            Value::Table(table)
+        }
+    })
+}
+
+struct PluginLint {
+    name: &'static str,
+    source: String,
+    config: serde_json::Value,
+    limits: Limits,
+}
+
+impl PluginLint {
+    fn check(&self, ast: &Ast, scope_manager: &ScopeManager) -> mlua::Result<Vec<Diagnostic>> {
+        let lua = create_lua(self.limits)?;
+
+        let plugin: Table = lua
+            .load(&self.source)
+            .set_name(format!("{}.lua", self.name))
+            .eval()?;
+
+        let check: Function = plugin.get("check")?;
+        let source = full_moon::print(ast);
+
+        let file = lua.create_table()?;
+        file.set(
+            "ast",
+            json_to_lua(
+                &lua,
+                &serde_json::to_value(ast).expect("couldn't serialize ast"),
+            )?,
+        )?;
+        file.set("scopes", scopes_table(&lua, scope_manager)?)?;
+        file.set("source", source.as_str())?;
+        file.set("config", json_to_lua(&lua, &self.config)?)?;
+
+        let diagnostics: Option<Vec<Table>> = check.call(file)?;
+
+        diagnostics
+            .unwrap_or_default()
+            .into_iter()
+            .map(|diagnostic| {
+                let message: String = diagnostic.get("message")?;
+                let notes: Option<Vec<String>> = diagnostic.get("notes")?;
+
+                let range = match diagnostic.get::<_, Vec<usize>>("range")?.as_slice() {
+                    // Ranges past the end of the file would have nothing to point at
+                    &[start, end] => {
+                        let start = start.min(source.len());
+                        (start, end.min(source.len()).max(start))
+                    }
+                    _ => {
+                        return Err(mlua::Error::RuntimeError(
+                            "the range of a diagnostic must be `{ start, finish }`".to_owned(),
+                        ))
+                    }
+                };
+
+                Ok(Diagnostic::new_complete(
+                    self.name,
+                    message,
+                    Label::new(range),
+                    notes.unwrap_or_default(),
+                    Vec::new(),
+                ))
+            })
+            .collect()
+    }
+}
+
+impl DynLint for PluginLint {
+    fn pass(&self, ast: &Ast, _: &Context, ast_context: &AstContext) -> Vec<Diagnostic> {
+        match self.check(ast, &ast_context.scope_manager) {
+            Ok(mut diagnostics) => {
+                diagnostics.sort_by_key(|diagnostic| diagnostic.primary_label.range);
+                diagnostics
+            }
+
+            // Reported rather than ignored, so that a broken plugin doesn't look like a passing one
+            Err(error) => vec![Diagnostic::new(
+                self.name,
+                format!("plugin `{}` failed: {error}", self.name),
+                Label::new((0, 0)),
+            )],
+        }
+    }
+}
+
+// Ids become indexes into `variables` and `references`, which start from 1 like any Lua array
+fn scopes_table<'lua>(lua: &'lua Lua, scope_manager: &ScopeManager) -> mlua::Result<Table<'lua>> {
+    let range = |(start, end): (usize, usize)| lua.create_sequence_from([start, end]);
+    let ranges = |ranges: &[(usize, usize)]| {
+        lua.create_sequence_from(
+            ranges
+                .iter()
+                .map(|&ranged| range(ranged))
+                .collect::<mlua::Result<Vec<_>>>()?,
+        )
+    };
+
+    let variables = lua.create_table()?;
+    for (_, variable) in scope_manager.variables.iter() {
+        let table = lua.create_table()?;
+        table.set("name", variable.name.as_str())?;
+        table.set("definitions", ranges(&variable.definitions)?)?;
+        table.set("identifiers", ranges(&variable.identifiers)?)?;
+        table.set(
+            "references",
+            lua.create_sequence_from(variable.references.iter().map(|id| id.index() + 1))?,
+        )?;
+        table.set("shadowed", variable.shadowed.map(|id| id.index() + 1))?;
+        table.set("is_self", variable.is_self)?;
+        variables.push(table)?;
+    }
+
+    let references = lua.create_table()?;
+    for (_, reference) in scope_manager.references.iter() {
+        let table = lua.create_table()?;
+        table.set("name", reference.name.as_str())?;
+        table.set("identifier", range(reference.identifier)?)?;
+        table.set("resolved", reference.resolved.map(|id| id.index() + 1))?;
+        table.set("read", reference.read)?;
+        table.set(
+            "write",
+            reference.write.map(|write| match write {
+                ReferenceWrite::Assign => "assign",
+                ReferenceWrite::Extend => "extend",
+            }),
+        )?;
+
+        if let Some(indexing) = &reference.indexing {
+            let entries = lua.create_table()?;
+
+            for index_entry in indexing {
+                let entry = lua.create_table()?;
+                entry.set("range", range(index_entry.index)?)?;
+                entry.set(
+                    "name",
+                    index_entry
+                        .static_name
+                        .as_ref()
+                        .and_then(|token| match token.token_type() {
+                            TokenType::Identifier { identifier } => Some(identifier.to_string()),
+                            TokenType::StringLiteral { literal, .. } => Some(literal.to_string()),
+                            _ => None,
+                        }),
+                )?;
+                entries.push(entry)?;
+            }
+
+            table.set("indexing", entries)?;
+        }
+
+        references.push(table)?;
+    }
+
+    let scopes = lua.create_table()?;
+    scopes.set("variables", variables)?;
+    scopes.set("references", references)?;
+    Ok(scopes)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{standard_library::StandardLibrary, Checker, CheckerConfig};
+
+    const NO_DYNAMIC_REQUIRE: &str = r#"
+        return {
+            severity = "deny",
+            category = "correctness",
+
+            check = function(file)
+                local diagnostics = {}
+
+                selene.visit(file.ast, "FunctionCall", function(call)
+                    if call.prefix.Name == nil or selene.text(call.prefix.Name) ~= "require" then
+                        return
+                    end
+
+                    local arguments = call.suffixes[1].Call.AnonymousCall
+                    if arguments.String ~= nil then
+                        return
+                    end
+
+                    local first = arguments.Parentheses and arguments.Parentheses.arguments.pairs[1]
+                    local expression = first and (first.End or first.Punctuated[1])
+                    if expression and expression.value and expression.value.String then
+                        return
+                    end
+
+                    table.insert(diagnostics, {
+                        message = "require paths must be strings",
+                        range = selene.range(call),
+                        notes = { "computed paths can't be followed by tools" },
+                    })
+                end)
+
+                return diagnostics
+            end,
+        }
+    "#;
+
+    fn diagnostics_with(
+        plugins: &[(&str, &str)],
+        config: CheckerConfig<serde_json::Value>,
+        code: &str,
+    ) -> Vec<crate::CheckerDiagnostic> {
+        let mut registry = LintRegistry::empty();
+        registry
+            .register::<lints::invalid_lint_filter::InvalidLintFilterLint>("invalid_lint_filter");
+
+        for (name, source) in plugins {
+            register_plugin(&mut registry, name, source).unwrap();
+        }
+
+        let checker = Checker::with_registry(config, StandardLibrary::default(), registry)
+            .expect("couldn't create checker");
+
+        let mut diagnostics = checker.test_on(&full_moon::parse(code).unwrap());
+        diagnostics.sort_by_key(|diagnostic| diagnostic.diagnostic.primary_label.range);
+        diagnostics
+    }
+
+    #[test]
+    fn test_plugin_diagnostics() {
+        let code = "local a = require(\"a\")\nlocal b = require \"b\"\nlocal c = require(name)\n-- selene: allow(no_dynamic_require)\nlocal d = require(name .. \"d\")\n";
+
+        let diagnostics = diagnostics_with(
+            &[("no_dynamic_require", NO_DYNAMIC_REQUIRE)],
+            CheckerConfig::default(),
+            code,
+        );
+
+        assert_eq!(diagnostics.len(), 1, "{diagnostics:#?}");
+
+        let diagnostic = &diagnostics[0];
+        assert_eq!(diagnostic.diagnostic.code, "no_dynamic_require");
+        assert_eq!(diagnostic.severity, Severity::Error);
+        assert_eq!(
+            diagnostic.diagnostic.message,
+            "require paths must be strings"
+        );
+        assert_eq!(
+            diagnostic.diagnostic.notes,
+            ["computed paths can't be followed by tools"]
+        );
+
+        let (start, end) = diagnostic.diagnostic.primary_label.range;
+        assert_eq!(&code[start as usize..end as usize], "require(name)");
+    }
+
+    #[test]
+    fn test_plugin_scopes_and_config() {
+        let plugin = r#"
+            return {
+                check = function(file)
+                    local diagnostics = {}
+
+                    for _, reference in ipairs(file.scopes.references) do
+                        if reference.resolved == nil and reference.name == file.config.banned then
+                            table.insert(diagnostics, {
+                                message = "don't use " .. reference.name,
+                                range = reference.identifier,
+                            })
+                        end
+                    end
+
+                    for _, variable in ipairs(file.scopes.variables) do
+                        if variable.name == file.config.banned then
+                            table.insert(diagnostics, {
+                                message = variable.name .. " has " .. #variable.references .. " references",
+                                range = variable.identifiers[1],
+                            })
+                        end
+                    end
+
+                    return diagnostics
+                end,
+            }
+        "#;
+
+        let diagnostics = diagnostics_with(
+            &[("banned_global", plugin)],
+            CheckerConfig {
+                config: [(
+                    "banned_global".to_owned(),
+                    serde_json::json!({ "banned": "bad" }),
+                )]
+                .into_iter()
+                .collect(),
+                ..CheckerConfig::default()
+            },
+            "bad()\ndo\n\tlocal bad = 1\n\tprint(bad)\nend\n",
+        );
+
+        assert_eq!(
+            diagnostics
+                .iter()
+                .map(|diagnostic| diagnostic.diagnostic.message.as_str())
+                .collect::<Vec<_>>(),
+            ["don't use bad", "bad has 2 references"]
+        );
+
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+    }
+
+    #[test]
+    fn test_plugin_failing() {
+        let diagnostics = diagnostics_with(
+            &[(
+                "broken",
+                "return { check = function() error(\"oh no\") end }",
+            )],
+            CheckerConfig::default(),
+            "local x = 1",
+        );
+
+        assert_eq!(diagnostics.len(), 1);
+        assert!(diagnostics[0]
+            .diagnostic
+            .message
+            .starts_with("plugin `broken` failed: "));
+        assert!(diagnostics[0].diagnostic.message.contains("oh no"));
+    }
+
+    #[test]
+    fn test_invalid_plugins() {
+        let problem = |name: &str, source: &str| {
+            register_plugin(
+                &mut LintRegistry::<serde_json::Value>::default(),
+                name,
+                source,
+            )
+            .unwrap_err()
+            .problem
+        };
+
+        let check = "return { check = function() end }";
+
+        assert!(matches!(
+            problem("no-dashes", check),
+            PluginErrorProblem::InvalidName
+        ));
+        assert!(matches!(
+            problem("empty_if", check),
+            PluginErrorProblem::AlreadyRegistered
+        ));
+        assert!(matches!(
+            problem("no_table", "return 1"),
+            PluginErrorProblem::InvalidPlugin(_)
+        ));
+        assert!(matches!(
+            problem("no_check", "return {}"),
+            PluginErrorProblem::InvalidPlugin(_)
+        ));
+        assert!(matches!(
+            problem(
+                "bad_severity",
+                "return { severity = \"error\", check = function() end }"
+            ),
+            PluginErrorProblem::InvalidPlugin(_)
+        ));
+        assert!(matches!(
+            problem("syntax_error", "return {"),
+            PluginErrorProblem::LuaError(_)
+        ));
+        assert!(matches!(
+            problem(
+                "reads_files",
+                "return { check = function() end, file = io.open(\"selene.toml\") }"
+            ),
+            PluginErrorProblem::LuaError(_)
+        ));
+    }
+    #[test]
+    fn test_plugin_limits() {
+        let limits = Limits {
+            instructions: 1_000_000,
+            memory: 4 * 1024 * 1024,
+        };
+
+        let error = |source: &str| {
+            let ast = full_moon::parse("local x = 1").unwrap();
+
+            PluginLint {
+                name: "limited",
+                source: source.to_owned(),
+                config: serde_json::Value::Null,
+                limits,
+            }
+            .check(&ast, &ScopeManager::new(&ast))
+            .unwrap_err()
+            .to_string()
+        };
+
+        assert!(error(
+            "return { check = function() while true do pcall(function() while true do end end) end end }"
+        )
+        .contains("ran for more than 1000000 instructions"));
+
+        assert!(matches!(
+            error(
+                "return { check = function() local t = {} for i = 1, math.huge do t[i] = string.rep(\"x\", 1000) .. i end end }"
+            )
+            .as_str(),
+            message if message.contains("memory")
+        ));
+    }
+}
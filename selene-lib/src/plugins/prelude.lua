@@ -0,0 +1,107 @@
+-- Run before every plugin, which can use these helpers through the global `selene`.
+-- Plugins can only see the file they are given, so anything that can read other files is removed.
+dofile = nil
+loadfile = nil
+
+selene = {}
+
+local function is_trivia(key)
+	return key == "leading_trivia" or key == "trailing_trivia"
+end
+
+local function is_token(value)
+	return value.start_position ~= nil and value.end_position ~= nil and value.token_type ~= nil
+end
+
+-- Keys are visited in the same order every time, so that plugins are deterministic
+local function sorted_keys(value)
+	local keys = {}
+
+	for key in pairs(value) do
+		table.insert(keys, key)
+	end
+
+	table.sort(keys, function(a, b)
+		if type(a) == type(b) then
+			return a < b
+		end
+
+		return type(a) == "number"
+	end)
+
+	return keys
+end
+
+-- The bytes a node covers, as `{ start, finish }`, without the whitespace and comments around it.
+-- This is what diagnostics take as their `range`.
+function selene.range(node)
+	local start, finish
+
+	local function walk(value)
+		if type(value) ~= "table" then
+			return
+		end
+
+		if is_token(value) then
+			if start == nil or value.start_position.bytes < start then
+				start = value.start_position.bytes
+			end
+
+			if finish == nil or value.end_position.bytes > finish then
+				finish = value.end_position.bytes
+			end
+
+			return
+		end
+
+		for key, child in pairs(value) do
+			if not is_trivia(key) then
+				walk(child)
+			end
+		end
+	end
+
+	walk(node)
+
+	if start == nil then
+		return nil
+	end
+
+	return { start, finish }
+end
+
+-- Calls `callback` with every node of a kind inside `node`, such as "FunctionCall" or "LocalAssignment".
+-- Kinds are the names of the variants full_moon gives to nodes, such as those of `Stmt` and `Value`.
+function selene.visit(node, kind, callback)
+	local function walk(value)
+		if type(value) ~= "table" or is_token(value) then
+			return
+		end
+
+		for _, key in ipairs(sorted_keys(value)) do
+			if not is_trivia(key) then
+				local child = value[key]
+
+				if key == kind then
+					callback(child)
+				end
+
+				walk(child)
+			end
+		end
+	end
+
+	walk(node)
+end
+
+-- The text of an identifier, symbol, number, or string token, such as `require`. Strings are
+-- given without their quotes.
+function selene.text(token)
+	local token_type = (token.token or token).token_type
+
+	if token_type == nil then
+		return nil
+	end
+
+	return token_type.identifier or token_type.symbol or token_type.text or token_type.literal
+end
@@ -450,6 +450,17 @@ impl Deprecated {
     }
 
     pub fn try_instead(&self, parameters: &[String]) -> Option<String> {
+        self.try_instead_parenthesized(parameters, &[])
+    }
+
+    /// Like [`Deprecated::try_instead`], but the parameters marked in `needs_parentheses` are
+    /// wrapped in parentheses wherever they aren't a whole argument, so that `#%1` with `a + b`
+    /// becomes `#(a + b)` rather than `#a + b`.
+    pub fn try_instead_parenthesized(
+        &self,
+        parameters: &[String],
+        needs_parentheses: &[bool],
+    ) -> Option<String> {
         profiling::scope!("Deprecated::try_instead");
 
         let regex_pattern = Deprecated::regex_pattern();
@@ -458,6 +469,22 @@ impl Deprecated {
             let mut success = true;
 
             let new_message = regex_pattern.replace_all(replace_format, |captures: &Captures| {
+                let whole = captures.get(0).unwrap();
+                let is_argument = replace_format[..whole.start()]
+                    .trim_end()
+                    .ends_with(['(', ',', '{'])
+                    && replace_format[whole.end()..]
+                        .trim_start()
+                        .starts_with([')', ',', '}']);
+
+                let parameter = |index: usize| {
+                    if !is_argument && needs_parentheses.get(index) == Some(&true) {
+                        Cow::Owned(format!("({})", parameters[index]))
+                    } else {
+                        Cow::Borrowed(parameters[index].as_str())
+                    }
+                };
+
                 if let Some(number) = captures.name("number") {
                     let number = match number.as_str().parse::<u32>() {
                         Ok(number) => number,
@@ -472,13 +499,18 @@ impl Deprecated {
                         return Cow::Borrowed("");
                     }
 
-                    return Cow::Borrowed(&parameters[number as usize - 1]);
+                    return parameter(number as usize - 1);
                 }
 
                 let capture = captures.get(1).unwrap();
                 match capture.as_str() {
                     "%" => Cow::Borrowed("%"),
-                    "..." => Cow::Owned(parameters.join(", ")),
+                    "..." => Cow::Owned(
+                        (0..parameters.len())
+                            .map(parameter)
+                            .collect::<Vec<_>>()
+                            .join(", "),
+                    ),
                     other => unreachable!("Unexpected capture in deprecated formatting: {}", other),
                 }
             });
@@ -899,4 +931,23 @@ mod tests {
             Some("print(a, b, c)".to_owned())
         );
     }
+
+    #[test]
+    fn deprecated_parenthesized() {
+        let deprecated = Deprecated {
+            message: "You shouldn't see this".to_owned(),
+            replace: vec!["#%1 + select(%2, %...)".to_owned()],
+        };
+
+        assert_eq!(
+            deprecated
+                .try_instead_parenthesized(&string_vec(vec!["a + b", "c or d"]), &[true, true]),
+            Some("#(a + b) + select(c or d, a + b, c or d)".to_owned())
+        );
+
+        assert_eq!(
+            deprecated.try_instead_parenthesized(&string_vec(vec!["a", "b"]), &[false, false]),
+            Some("#a + select(b, a, b)".to_owned())
+        );
+    }
 }
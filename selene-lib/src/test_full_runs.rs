@@ -31,3 +31,33 @@ fn test_std_mistakes() {
 fn test_std_mistakes_roblox() {
     test_full_run_config("std_mistakes", "roblox_mistakes", CheckerConfig::default());
 }
+
+#[test]
+fn test_custom_lints() {
+    test_full_run_config(
+        "custom_lints",
+        "custom_lints",
+        toml::from_str(
+            r#"
+            [[custom-lints]]
+            name = "no_setmetatable"
+            message = "debug.setmetatable can break metatables other code relies on"
+            global = "debug.setmetatable"
+
+            [[custom-lints]]
+            name = "no_insecure_require"
+            message = "use the `secure_socket` library instead"
+            severity = "deny"
+            call = "require"
+            arguments = [{ index = 1, type = ["socket", "ssl"] }]
+
+            [[custom-lints]]
+            name = "instance_parent"
+            message = "Instance.new needs a parent"
+            call = "Instance.new"
+            required-arguments = 2
+            "#,
+        )
+        .unwrap(),
+    );
+}
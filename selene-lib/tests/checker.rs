@@ -2,7 +2,7 @@ use std::collections::HashMap;
 
 use selene_lib::{standard_library::StandardLibrary, *};
 
-use full_moon::parse;
+use full_moon::{node::Node, parse};
 use serde_json::json;
 
 macro_rules! map {
@@ -48,6 +48,40 @@ fn errors_with_bad_config() {
     }
 }
 
+#[test]
+fn all_lints_have_info() {
+    let empty_if = all_lints()
+        .iter()
+        .find(|lint| lint.name == "empty_if")
+        .expect("empty_if is not in all_lints");
+
+    assert_eq!(empty_if.severity, lints::Severity::Warning);
+    assert_eq!(empty_if.lint_type, lints::LintType::Style);
+    assert_eq!(
+        empty_if.default_config(),
+        json!({ "comments_count": false })
+    );
+
+    let divide_by_zero = all_lints()
+        .iter()
+        .find(|lint| lint.name == "divide_by_zero")
+        .expect("divide_by_zero is not in all_lints");
+
+    assert_eq!(divide_by_zero.default_config(), serde_json::Value::Null);
+    assert_eq!(
+        divide_by_zero.severity_with(&CheckerConfig::<serde_json::Value> {
+            lints: map! {
+                "complexity".to_owned() => LintVariation::Deny,
+            },
+            ..CheckerConfig::default()
+        }),
+        lints::Severity::Error
+    );
+
+    assert!(all_lints().iter().all(|lint| lint_exists(lint.name)));
+    assert!(!lint_exists("not_a_real_lint"));
+}
+
 #[test]
 fn uses_lint_variation_allow() {
     let checker: Checker<serde_json::Value> = Checker::new(
@@ -65,3 +99,206 @@ fn uses_lint_variation_allow() {
         .test_on(&parse("if true then\n\treturn\nend").unwrap())
         .is_empty());
 }
+
+#[test]
+fn uses_lint_category_variation() {
+    let checker: Checker<serde_json::Value> = Checker::new(
+        CheckerConfig {
+            lints: map! {
+                "style".to_owned() => LintVariation::Allow,
+                "shadowing".to_owned() => LintVariation::Deny,
+            },
+            ..CheckerConfig::default()
+        },
+        StandardLibrary::default(),
+    )
+    .unwrap();
+
+    let diagnostics = checker
+        .test_on(&parse("local x = 1\nlocal x = 2\nif true then\nend").unwrap())
+        .into_iter()
+        .filter(|diagnostic| diagnostic.severity != lints::Severity::Allow)
+        .collect::<Vec<_>>();
+
+    assert_eq!(diagnostics.len(), 1);
+    assert_eq!(diagnostics[0].diagnostic.code, "shadowing");
+    assert_eq!(diagnostics[0].severity, lints::Severity::Error);
+}
+
+struct NoPrintLint;
+
+impl lints::Lint for NoPrintLint {
+    type Config = ();
+    type Error = std::convert::Infallible;
+
+    const SEVERITY: lints::Severity = lints::Severity::Error;
+    const LINT_TYPE: lints::LintType = lints::LintType::Style;
+
+    fn new(_: Self::Config) -> Result<Self, Self::Error> {
+        Ok(NoPrintLint)
+    }
+
+    fn pass(
+        &self,
+        ast: &full_moon::ast::Ast,
+        _: &lints::Context,
+        _: &lints::AstContext,
+    ) -> Vec<lints::Diagnostic> {
+        ast.nodes()
+            .tokens()
+            .filter(|token| token.to_string() == "print")
+            .map(|token| {
+                lints::Diagnostic::new(
+                    "no_print",
+                    "don't use print".to_owned(),
+                    lints::Label::new((
+                        token.token().start_position().bytes(),
+                        token.token().end_position().bytes(),
+                    )),
+                )
+            })
+            .collect()
+    }
+}
+
+#[test]
+fn uses_registered_lints() {
+    let mut registry = LintRegistry::default();
+    registry.register::<NoPrintLint>("no_print");
+    assert!(registry.remove("empty_if"));
+    assert!(!registry.remove("not_a_real_lint"));
+
+    let checker: Checker<serde_json::Value> = Checker::with_registry(
+        CheckerConfig::default(),
+        StandardLibrary::default(),
+        registry,
+    )
+    .unwrap();
+
+    assert_eq!(checker.lints().last().unwrap().name, "no_print");
+
+    let diagnostics = checker.test_on(
+        &parse("if true then\nend\nprint()\n-- selene: allow(no_print)\nprint()").unwrap(),
+    );
+
+    assert!(diagnostics
+        .iter()
+        .all(|diagnostic| diagnostic.diagnostic.code != "empty_if"));
+
+    let no_print = diagnostics
+        .iter()
+        .filter(|diagnostic| diagnostic.diagnostic.code == "no_print")
+        .collect::<Vec<_>>();
+
+    assert_eq!(no_print.len(), 1);
+    assert_eq!(no_print[0].severity, lints::Severity::Error);
+}
+
+#[test]
+fn registered_lints_can_be_configured() {
+    let mut registry = LintRegistry::empty();
+
+    registry.register_dyn(
+        LintInfo::new("no_print", lints::Severity::Warning, lints::LintType::Style),
+        |config: Option<serde_json::Value>| match config {
+            Some(config) => Err(format!("unexpected config {config}").into()),
+            None => Ok(Box::new(NoPrintLint)),
+        },
+    );
+
+    let checker: Checker<serde_json::Value> = Checker::with_registry(
+        CheckerConfig::default(),
+        StandardLibrary::default(),
+        LintRegistry::empty(),
+    )
+    .unwrap();
+    assert!(checker.test_on(&parse("print()").unwrap()).is_empty());
+
+    match Checker::with_registry(
+        CheckerConfig {
+            config: map! {
+                "no_print".to_owned() => json!(true),
+            },
+            ..CheckerConfig::default()
+        },
+        StandardLibrary::default(),
+        registry,
+    ) {
+        Err(error) => {
+            assert_eq!(error.name, "no_print");
+            match error.problem {
+                CheckerErrorProblem::LintNewError(_) => {}
+                other => panic!("error was not LintNewError: {other:?}"),
+            }
+        }
+
+        _ => panic!("with_registry returned Ok"),
+    }
+}
+
+#[test]
+fn errors_with_bad_custom_lints() {
+    let problem = |custom_lint: serde_json::Value| {
+        let config: CheckerConfig<serde_json::Value> =
+            serde_json::from_value(json!({ "custom-lints": [custom_lint] })).unwrap();
+
+        match Checker::new(config, StandardLibrary::default()) {
+            Err(error) => error.to_string(),
+            Ok(_) => panic!("new returned Ok"),
+        }
+    };
+
+    assert_eq!(
+        problem(json!({ "name": "empty_if", "message": "", "global": "foo" })),
+        "[empty_if] a lint with the same name already exists"
+    );
+    assert_eq!(
+        problem(json!({ "name": "no_foo", "message": "" })),
+        "[no_foo] custom lints need either `global` or `call`"
+    );
+    assert_eq!(
+        problem(json!({ "name": "no_foo", "message": "", "global": "foo:bar" })),
+        "[no_foo] `foo:bar` is not a name path, such as `debug.setmetatable`"
+    );
+    assert_eq!(
+        problem(json!({
+            "name": "no_foo",
+            "message": "",
+            "call": "foo",
+            "arguments": [{ "index": 1, "type": "number" }],
+            "required-arguments": 1,
+        })),
+        "[no_foo] `arguments` and `required-arguments` can't be used together"
+    );
+}
+
+#[test]
+fn threads_dont_change_diagnostics() {
+    let checker =
+        Checker::<serde_json::Value>::new(CheckerConfig::default(), StandardLibrary::default())
+            .unwrap();
+
+    let ast = parse(
+        r#"
+        local unused = 1
+        local x = x
+        if x then end
+        print(undefined_global, 1 / 0, x ~= x)
+        local function f(a, a) return a end
+        "#,
+    )
+    .unwrap();
+
+    let to_json = |diagnostics: Vec<CheckerDiagnostic>| serde_json::to_value(diagnostics).unwrap();
+    let expected = to_json(checker.test_on(&ast));
+
+    assert!(expected.as_array().unwrap().len() > 5);
+
+    for threads in [0, 2, 4, 64] {
+        assert_eq!(
+            to_json(checker.test_on_with_threads(&ast, threads)),
+            expected,
+            "diagnostics changed with {threads} threads"
+        );
+    }
+}
@@ -0,0 +1,22 @@
+debug.setmetatable(value, nil)
+print(debug.setmetatable)
+print(debug.setmetatable.field)
+print(debug.getmetatable(value))
+
+print(require("socket"))
+print(require("http"))
+print(require "ssl")
+
+print(Instance.new("Part"))
+print(Instance.new("Model", workspace))
+print(Instance.new(...))
+
+local function shadowed()
+	local debug = {}
+	debug.setmetatable(value, nil)
+
+	local require = print
+	require("socket")
+end
+
+shadowed()
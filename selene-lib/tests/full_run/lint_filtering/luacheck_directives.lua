@@ -0,0 +1,51 @@
+local unusedOnThisLine = 1 -- luacheck: ignore 211
+local unusedHere = 2
+
+local function a()
+    -- luacheck: ignore 211
+    local unusedInA = 1
+end
+
+local function b()
+    local unusedInB = 1
+end
+
+-- luacheck: push ignore 113
+print(undefinedInPush)
+-- luacheck: pop
+print(undefinedAfterPop)
+
+-- luacheck: globals GLOBAL_STATE, read globals love
+GLOBAL_STATE = love.graphics
+print(otherGlobal)
+
+local _, used = a(), b() -- luacheck: ignore _
+local notIgnored, usedToo = a(), b() -- luacheck: ignore _.*
+print(used, usedToo)
+
+print(
+    undefinedInCall, -- luacheck: ignore 113
+    alsoUndefinedInCall
+)
+
+-- selene: allow(unused_variable)
+do
+    -- luacheck: push ignore 4
+    local shadowed = 1
+    do
+        local shadowed = 2
+        print(shadowed)
+    end
+end
+-- luacheck: pop
+
+-- With nothing after it, only what luacheck would have warned about is ignored
+local function c()
+    -- luacheck: ignore
+    local unusedInC = 1
+    print(1 / 0)
+end
+c()
+
+-- luacheck: pop
+-- luacheck: max line length 200
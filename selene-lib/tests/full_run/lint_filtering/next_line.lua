@@ -0,0 +1,15 @@
+-- selene: allow-next-line(unused_variable)
+local unusedButAllowed = 1
+local unusedOnTheLineAfter = 2
+
+local first, second = call(
+    -- selene: allow-next-line(undefined_variable)
+    undefinedButAllowed,
+    undefinedOnTheLineAfter
+)
+
+-- selene: allow-next-line(undefined_variable, unused_variable) -- reason: generated by a tool
+local fromTool = undefinedGlobal
+
+--# selene: allow-next-line(unused_variable)
+local cantBeGlobal = 3
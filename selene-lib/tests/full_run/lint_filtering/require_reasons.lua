@@ -0,0 +1,14 @@
+-- selene: allow(unused_variable) -- reason: kept for debugging
+local withReason = 1
+
+-- selene: allow(unused_variable)
+local withoutReason = 2
+
+-- selene: allow(unused_variable) -- reason:
+local emptyReason = 3
+
+-- selene: allow-next-line(unused_variable, shadowing)
+local nextLineWithoutReason = 4
+
+-- selene: deny(unused_variable)
+local denyDoesNotNeedOne = 5
@@ -0,0 +1,25 @@
+--# selene: allow(empty_if)
+--# selene: allow(global_usage)
+
+-- selene: allow(unused_variable)
+local unused = 1
+
+-- selene: allow(unused_variable)
+local used = 2
+print(used)
+
+-- selene: allow(unused_variable, shadowing)
+local alsoUnused = 3
+
+-- selene: allow(unused_lint_filter)
+-- selene: allow(undefined_variable)
+print("nothing is undefined here")
+
+-- selene: deny(unused_variable)
+local denied = 4
+
+if true then
+end
+
+-- selene: allow-next-line(undefined_variable)
+print("fine")
@@ -0,0 +1,4 @@
+table.foreach({}, function(k, v) end)
+print(#x)
+
+table.foreach({}, 3)
@@ -0,0 +1,8 @@
+print(#(a + b))
+print(#t.list + 1)
+print(#(a or b))
+print(#(not a))
+game:Connect(function() end)
+game:Connect(function() end):Disconnect()
+newfn("s")
+newfn("s")
@@ -0,0 +1,10 @@
+print(getn(a + b))
+print(getn(t.list) + 1)
+print(getn((a or b)))
+print(getn(not a))
+game:connect(function() end)
+game:connect(function() end):Disconnect()
+oldfn "s"
+oldfn(
+    "s" -- comment
+)
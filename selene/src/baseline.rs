@@ -0,0 +1,285 @@
+//! Baselines record the diagnostics a project already has, so that they can be hidden
+//! while new ones are still reported. This lets a lint be adopted without fixing every
+//! existing case at once.
+//!
+//! Diagnostics are matched by file, lint, and a hash of the code that was flagged rather than
+//! by position, so that adding or removing unrelated lines doesn't invalidate the baseline.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fmt, fs, io,
+    path::Path,
+    sync::Mutex,
+};
+
+use selene_lib::lints::Diagnostic;
+use serde::{Deserialize, Serialize};
+use sha2::{Digest, Sha256};
+
+const BASELINE_VERSION: u32 = 1;
+
+#[derive(Clone, Debug, PartialEq, Eq, PartialOrd, Ord, Hash, Deserialize, Serialize)]
+pub struct BaselineKey {
+    pub file: String,
+    pub code: String,
+    pub hash: String,
+}
+
+impl BaselineKey {
+    pub fn new(filename: &Path, diagnostic: &Diagnostic, source: &str) -> Self {
+        let range = diagnostic.primary_label.range;
+        let flagged = source
+            .get(range.0 as usize..range.1 as usize)
+            .unwrap_or_default();
+
+        // Whitespace is ignored so that reindenting code doesn't invalidate the baseline
+        let mut hasher = Sha256::new();
+        for word in flagged.split_whitespace() {
+            hasher.update(word.as_bytes());
+            hasher.update(b" ");
+        }
+
+        Self {
+            file: normalize_filename(filename),
+            code: diagnostic.code.to_owned(),
+            hash: format!("{:x}", hasher.finalize()),
+        }
+    }
+}
+
+fn normalize_filename(filename: &Path) -> String {
+    let filename = filename.to_string_lossy().replace('\\', "/");
+
+    match filename.strip_prefix("./") {
+        Some(stripped) => stripped.to_owned(),
+        None => filename,
+    }
+}
+
+#[derive(Deserialize, Serialize)]
+struct BaselineFile {
+    version: u32,
+    entries: Vec<BaselineEntry>,
+}
+
+#[derive(Deserialize, Serialize)]
+struct BaselineEntry {
+    #[serde(flatten)]
+    key: BaselineKey,
+    count: usize,
+}
+
+#[derive(Debug)]
+pub enum BaselineError {
+    Io(io::Error),
+    Json(serde_json::Error),
+    UnsupportedVersion(u32),
+}
+
+impl fmt::Display for BaselineError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            BaselineError::Io(error) => write!(formatter, "{error}"),
+            BaselineError::Json(error) => write!(formatter, "baseline is not valid: {error}"),
+            BaselineError::UnsupportedVersion(version) => write!(
+                formatter,
+                "baseline is version {version}, but only version {BASELINE_VERSION} is supported"
+            ),
+        }
+    }
+}
+
+impl std::error::Error for BaselineError {}
+
+#[derive(Default)]
+pub struct Baseline {
+    // Counted, since the same code can be flagged multiple times in the same file
+    entries: Mutex<BTreeMap<BaselineKey, usize>>,
+    checked_files: Mutex<HashSet<String>>,
+}
+
+impl Baseline {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn from_json(contents: &str) -> Result<Self, BaselineError> {
+        let file: BaselineFile = serde_json::from_str(contents).map_err(BaselineError::Json)?;
+
+        if file.version != BASELINE_VERSION {
+            return Err(BaselineError::UnsupportedVersion(file.version));
+        }
+
+        let mut entries = BTreeMap::new();
+        for entry in file.entries {
+            *entries.entry(entry.key).or_default() += entry.count;
+        }
+
+        Ok(Self {
+            entries: Mutex::new(entries),
+            checked_files: Mutex::default(),
+        })
+    }
+
+    pub fn load(path: &Path) -> Result<Self, BaselineError> {
+        Self::from_json(&fs::read_to_string(path).map_err(BaselineError::Io)?)
+    }
+
+    pub fn to_json(&self) -> String {
+        let file = BaselineFile {
+            version: BASELINE_VERSION,
+            entries: self
+                .entries
+                .lock()
+                .unwrap()
+                .iter()
+                .map(|(key, count)| BaselineEntry {
+                    key: key.clone(),
+                    count: *count,
+                })
+                .collect(),
+        };
+
+        serde_json::to_string_pretty(&file).expect("couldn't serialize baseline")
+    }
+
+    pub fn write(&self, path: &Path) -> Result<(), BaselineError> {
+        fs::write(path, self.to_json()).map_err(BaselineError::Io)
+    }
+
+    pub fn len(&self) -> usize {
+        self.entries.lock().unwrap().values().sum()
+    }
+
+    /// Marks a file as checked, so that its entries can be reported if they no longer occur.
+    pub fn check_file(&self, filename: &Path) {
+        self.checked_files
+            .lock()
+            .unwrap()
+            .insert(normalize_filename(filename));
+    }
+
+    pub fn record(&self, key: BaselineKey) {
+        *self.entries.lock().unwrap().entry(key).or_default() += 1;
+    }
+
+    /// Uses up an entry matching the key, returning whether there was one.
+    pub fn take(&self, key: &BaselineKey) -> bool {
+        let mut entries = self.entries.lock().unwrap();
+
+        match entries.get_mut(key) {
+            Some(count) if *count > 0 => {
+                *count -= 1;
+                true
+            }
+
+            _ => false,
+        }
+    }
+
+    /// Entries in checked files that were never matched, meaning the code was fixed and they
+    /// can be removed from the baseline.
+    pub fn unmatched(&self) -> Vec<(BaselineKey, usize)> {
+        let checked_files = self.checked_files.lock().unwrap();
+
+        self.entries
+            .lock()
+            .unwrap()
+            .iter()
+            .filter(|(key, count)| **count > 0 && checked_files.contains(&key.file))
+            .map(|(key, count)| (key.clone(), *count))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use selene_lib::lints::Label;
+
+    fn diagnostic(code: &'static str, range: (u32, u32)) -> Diagnostic {
+        Diagnostic::new(code, "message".to_owned(), Label::new(range))
+    }
+
+    #[test]
+    fn test_keys_survive_moving_lines() {
+        let before = "local x = 1 / 0\n";
+        let after = "-- new comment\n\tlocal x = 1  /  0\n";
+
+        assert_eq!(
+            BaselineKey::new(
+                Path::new("./src/code.lua"),
+                &diagnostic("divide_by_zero", (10, 15)),
+                before
+            ),
+            BaselineKey::new(
+                Path::new("src\\code.lua"),
+                &diagnostic("divide_by_zero", (26, 33)),
+                after
+            ),
+        );
+
+        assert_ne!(
+            BaselineKey::new(
+                Path::new("code.lua"),
+                &diagnostic("divide_by_zero", (10, 15)),
+                before
+            ),
+            BaselineKey::new(
+                Path::new("code.lua"),
+                &diagnostic("divide_by_zero", (10, 15)),
+                "local x = 2 / 0\n"
+            ),
+        );
+    }
+
+    #[test]
+    fn test_round_trip() {
+        let source = "print(1 / 0)\nprint(1 / 0)\nprint(2 / 0)\n";
+        let key = |start: u32| {
+            BaselineKey::new(
+                Path::new("code.lua"),
+                &diagnostic("divide_by_zero", (start, start + 5)),
+                source,
+            )
+        };
+
+        let baseline = Baseline::new();
+        baseline.record(key(6));
+        baseline.record(key(19));
+        baseline.record(key(32));
+        assert_eq!(baseline.len(), 3);
+
+        let baseline = Baseline::from_json(&baseline.to_json()).unwrap();
+        baseline.check_file(Path::new("code.lua"));
+
+        // Only two of the same diagnostic were recorded, so a third is new
+        assert!(baseline.take(&key(6)));
+        assert!(baseline.take(&key(19)));
+        assert!(!baseline.take(&key(6)));
+
+        let unmatched = baseline.unmatched();
+        assert_eq!(unmatched.len(), 1);
+        assert_eq!(unmatched[0].0, key(32));
+    }
+
+    #[test]
+    fn test_unchecked_files_not_unmatched() {
+        let baseline = Baseline::new();
+        baseline.record(BaselineKey::new(
+            Path::new("other.lua"),
+            &diagnostic("divide_by_zero", (0, 5)),
+            "1 / 0",
+        ));
+
+        assert!(baseline.unmatched().is_empty());
+    }
+
+    #[test]
+    fn test_unsupported_version() {
+        assert!(matches!(
+            Baseline::from_json(r#"{ "version": 2, "entries": [] }"#),
+            Err(BaselineError::UnsupportedVersion(2))
+        ));
+    }
+}
@@ -0,0 +1,237 @@
+//! An opt-in cache of diagnostics, so that files which haven't changed since the last run
+//! don't need to be parsed and linted again.
+//!
+//! Everything other than the file that affects its diagnostics--the selene version, the config,
+//! the standard library, and any plugins--is hashed together to pick a directory. Inside it, entries are
+//! named after the hash of a file's contents.
+
+use std::{
+    collections::HashMap,
+    fs, io,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
+};
+
+use selene_lib::{standard_library::StandardLibrary, CheckerConfig, CheckerDiagnostic};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::checkers::Plugin;
+
+pub const CACHE_DIRECTORY: &str = ".selene-cache";
+
+// Entries that haven't been used in this long are removed when pruning
+const MAX_ENTRY_AGE: Duration = Duration::from_secs(60 * 60 * 24 * 7);
+
+pub struct Cache {
+    root: PathBuf,
+    directory: PathBuf,
+}
+
+impl Cache {
+    pub fn new<V: Serialize>(
+        root: &Path,
+        config: &CheckerConfig<V>,
+        standard_library: &StandardLibrary,
+        plugins: &[Plugin],
+    ) -> Self {
+        // Going through serde_json::Value sorts the keys of the config's maps
+        let to_json = |value: serde_json::Value| value.to_string();
+
+        let mut hasher = Sha256::new();
+        hasher.update(env!("CARGO_PKG_VERSION").as_bytes());
+        hasher.update(b"\0");
+        hasher.update(to_json(
+            serde_json::to_value(config).expect("couldn't serialize config"),
+        ));
+        hasher.update(b"\0");
+        hasher.update(to_json(
+            serde_json::to_value(standard_library).expect("couldn't serialize standard library"),
+        ));
+        hasher.update(b"\0");
+        hasher.update(to_json(
+            serde_json::to_value(plugins).expect("couldn't serialize plugins"),
+        ));
+
+        Self {
+            root: root.to_path_buf(),
+            directory: root.join(format!("{:x}", hasher.finalize())),
+        }
+    }
+
+    fn entry_path(&self, contents: &str) -> PathBuf {
+        self.directory
+            .join(format!("{:x}.json", Sha256::digest(contents.as_bytes())))
+    }
+
+    /// Gets the diagnostics for a file with these contents, if they were cached.
+    /// Anything going wrong is treated as the file not being cached.
+    pub fn get(&self, contents: &str) -> Option<Vec<CheckerDiagnostic>> {
+        let path = self.entry_path(contents);
+        let diagnostics = serde_json::from_slice(&fs::read(&path).ok()?).ok()?;
+
+        // Marks the entry as used, so it isn't pruned
+        if let Ok(file) = fs::File::options().append(true).open(&path) {
+            file.set_modified(SystemTime::now()).ok();
+        }
+
+        Some(diagnostics)
+    }
+
+    /// Caches the diagnostics for a file. The cache is only an optimization,
+    /// so failing to write to it is ignored.
+    pub fn insert(&self, contents: &str, diagnostics: &[CheckerDiagnostic]) {
+        if fs::create_dir_all(&self.directory).is_err() {
+            return;
+        }
+
+        let path = self.entry_path(contents);
+        let temporary_path = path.with_extension(format!("{}.tmp", std::process::id()));
+
+        // Written to a temporary file first so that other runs never read half of an entry
+        let contents = serde_json::to_vec(diagnostics).expect("couldn't serialize diagnostics");
+        if fs::write(&temporary_path, contents).is_ok()
+            && fs::rename(&temporary_path, &path).is_err()
+        {
+            fs::remove_file(&temporary_path).ok();
+        }
+    }
+}
+
+/// Removes entries for other versions, configs, or standard libraries, as well as entries
+/// that haven't been used in a while. Configs with overrides have a cache for each checker,
+/// all sharing the same root, so every cache used in a run has to be pruned together.
+pub fn prune<'a>(caches: impl IntoIterator<Item = &'a Cache>) -> io::Result<()> {
+    let mut roots: HashMap<&Path, Vec<&Path>> = HashMap::new();
+    for cache in caches {
+        roots.entry(&cache.root).or_default().push(&cache.directory);
+    }
+
+    for (root, directories) in roots {
+        let Ok(entries) = fs::read_dir(root) else {
+            continue;
+        };
+
+        for entry in entries {
+            let entry = entry?;
+            if !directories.contains(&entry.path().as_path()) && entry.file_type()?.is_dir() {
+                fs::remove_dir_all(entry.path())?;
+            }
+        }
+
+        for directory in directories {
+            prune_old_entries(directory)?;
+        }
+    }
+
+    Ok(())
+}
+
+fn prune_old_entries(directory: &Path) -> io::Result<()> {
+    let Ok(entries) = fs::read_dir(directory) else {
+        return Ok(());
+    };
+
+    let now = SystemTime::now();
+    for entry in entries {
+        let entry = entry?;
+        let modified = entry.metadata()?.modified()?;
+
+        if now.duration_since(modified).unwrap_or_default() > MAX_ENTRY_AGE {
+            fs::remove_file(entry.path())?;
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use selene_lib::lints::{Diagnostic, Label, Severity};
+
+    fn cache_in(root: &Path, config: &CheckerConfig<toml::value::Value>) -> Cache {
+        Cache::new(
+            root,
+            config,
+            &StandardLibrary::from_name("lua51").unwrap(),
+            &[],
+        )
+    }
+
+    #[test]
+    fn test_cache() {
+        let root = std::env::temp_dir().join(format!("selene-cache-test-{}", std::process::id()));
+        let config = CheckerConfig::default();
+        let cache = cache_in(&root, &config);
+
+        assert!(cache.get("print(1 / 0)").is_none());
+
+        cache.insert(
+            "print(1 / 0)",
+            &[CheckerDiagnostic {
+                diagnostic: Diagnostic::new(
+                    "divide_by_zero",
+                    "dividing by zero is not allowed, use math.huge instead".to_owned(),
+                    Label::new((6, 11)),
+                ),
+                severity: Severity::Warning,
+            }],
+        );
+
+        let diagnostics = cache.get("print(1 / 0)").unwrap();
+        assert_eq!(diagnostics.len(), 1);
+        assert_eq!(diagnostics[0].diagnostic.code, "divide_by_zero");
+        assert_eq!(diagnostics[0].diagnostic.primary_label.range, (6, 11));
+        assert_eq!(diagnostics[0].severity, Severity::Warning);
+
+        assert!(cache.get("print(2 / 0)").is_none());
+
+        // A different config can't use the same entries, and pruning with it removes them
+        let other_config = CheckerConfig {
+            std: Some("lua52".to_owned()),
+            ..CheckerConfig::default()
+        };
+
+        let other_cache = cache_in(&root, &other_config);
+        assert!(other_cache.get("print(1 / 0)").is_none());
+
+        other_cache.insert("print(1 / 0)", &[]);
+        prune([&other_cache]).unwrap();
+        assert!(cache.get("print(1 / 0)").is_none());
+        assert_eq!(other_cache.get("print(1 / 0)").unwrap().len(), 0);
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+    #[test]
+    fn test_prune_shared_root() {
+        let root = std::env::temp_dir().join(format!("selene-cache-shared-{}", std::process::id()));
+
+        // An override's checker has its own cache next to the one of the config it's in
+        let config = CheckerConfig::default();
+        let override_config = CheckerConfig {
+            std: Some("lua51+lua52".to_owned()),
+            ..CheckerConfig::default()
+        };
+        let stale_config = CheckerConfig {
+            std: Some("lua53".to_owned()),
+            ..CheckerConfig::default()
+        };
+
+        let cache = cache_in(&root, &config);
+        let override_cache = cache_in(&root, &override_config);
+        let stale_cache = cache_in(&root, &stale_config);
+
+        cache.insert("print(1)", &[]);
+        override_cache.insert("print(2)", &[]);
+        stale_cache.insert("print(3)", &[]);
+
+        prune([&cache, &override_cache]).unwrap();
+
+        assert!(cache.get("print(1)").is_some());
+        assert!(override_cache.get("print(2)").is_some());
+        assert!(stale_cache.get("print(3)").is_none());
+
+        fs::remove_dir_all(&root).unwrap();
+    }
+}
@@ -12,6 +12,9 @@ fn capabilities() -> serde_json::Value {
     serde_json::json!({
         "validateConfig": {
             "version": "1.0.0"
+        },
+        "lsp": {
+            "version": "1.0.0"
         }
     })
 }
@@ -26,6 +29,13 @@ pub fn print_capabilities(display_style: DisplayStyle) {
         // extensions still read from it.
         DisplayStyle::Json => {}
 
+        // These formats only describe the results of a run.
+        DisplayStyle::Sarif
+        | DisplayStyle::Github
+        | DisplayStyle::Gitlab
+        | DisplayStyle::Checkstyle
+        | DisplayStyle::Junit => {}
+
         DisplayStyle::Json2 => {
             println!(
                 "{}",
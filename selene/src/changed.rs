@@ -0,0 +1,355 @@
+//! Finds the files and lines that git reports as changed, so that only those are checked.
+//! This keeps pre-commit hooks and pull request checks fast on large projects.
+//!
+//! git is run as a command rather than read directly, so that it behaves exactly as it
+//! would for the user, including their config.
+
+use std::{
+    collections::HashMap,
+    fmt, fs, io,
+    ops::RangeInclusive,
+    path::{Path, PathBuf},
+    process::Command,
+};
+
+#[derive(Clone, Copy, Debug)]
+pub enum ChangedSince<'a> {
+    /// Everything that differs from a revision, including changes that aren't committed yet.
+    Revision(&'a str),
+
+    /// Only changes that are staged to be committed.
+    Staged,
+}
+
+#[derive(Clone, Debug, PartialEq, Eq)]
+pub enum ChangedLines {
+    /// The file is new, so every line in it has changed.
+    All,
+
+    /// 1-indexed, inclusive ranges of the lines that were added or modified.
+    Ranges(Vec<RangeInclusive<usize>>),
+}
+
+impl ChangedLines {
+    pub fn overlaps(&self, lines: RangeInclusive<usize>) -> bool {
+        match self {
+            ChangedLines::All => true,
+            ChangedLines::Ranges(ranges) => ranges
+                .iter()
+                .any(|range| range.start() <= lines.end() && lines.start() <= range.end()),
+        }
+    }
+}
+
+#[derive(Debug)]
+pub enum ChangedFilesError {
+    Io(io::Error),
+    Git(String),
+}
+
+impl fmt::Display for ChangedFilesError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ChangedFilesError::Io(error) => write!(formatter, "couldn't run git: {error}"),
+            ChangedFilesError::Git(error) => write!(formatter, "git failed: {error}"),
+        }
+    }
+}
+
+impl std::error::Error for ChangedFilesError {}
+
+pub struct ChangedFiles {
+    // Keyed by canonicalized path, so that any way of writing a path to the same file matches
+    files: HashMap<PathBuf, ChangedLines>,
+
+    // The root of the repository, only with --staged
+    staged_root: Option<PathBuf>,
+}
+
+fn git(directory: &Path, args: &[&str]) -> Result<String, ChangedFilesError> {
+    let output = Command::new("git")
+        .current_dir(directory)
+        .args(["-c", "core.quotePath=false"])
+        .args(args)
+        .output()
+        .map_err(ChangedFilesError::Io)?;
+
+    if !output.status.success() {
+        return Err(ChangedFilesError::Git(
+            String::from_utf8_lossy(&output.stderr).trim().to_owned(),
+        ));
+    }
+
+    Ok(String::from_utf8_lossy(&output.stdout).into_owned())
+}
+
+impl ChangedFiles {
+    pub fn from_git(since: ChangedSince) -> Result<Self, ChangedFilesError> {
+        Self::from_git_in(Path::new("."), since)
+    }
+
+    fn from_git_in(directory: &Path, since: ChangedSince) -> Result<Self, ChangedFilesError> {
+        let git = |args: &[&str]| git(directory, args);
+
+        let root = git(&["rev-parse", "--show-toplevel"])?;
+        let root = fs::canonicalize(root.trim()).map_err(ChangedFilesError::Io)?;
+
+        // Renames are shown as an added file, so that every line of them counts as changed.
+        // Deleted files are left out, since there's nothing left to check.
+        let mut diff_args = vec![
+            "diff",
+            "--no-color",
+            "--no-ext-diff",
+            "--no-renames",
+            "--unified=0",
+            "--diff-filter=d",
+        ];
+
+        match since {
+            ChangedSince::Revision(revision) => diff_args.extend([revision, "--"]),
+            ChangedSince::Staged => diff_args.extend(["--cached", "--"]),
+        }
+
+        let mut files = parse_diff(&root, &git(&diff_args)?);
+
+        // Files git doesn't know about yet are new, but don't show up in a diff
+        if let ChangedSince::Revision(_) = since {
+            // `:/` lists them from the root of the repository, not just the current directory
+            let untracked = git(&[
+                "ls-files",
+                "--others",
+                "--exclude-standard",
+                "--full-name",
+                "--",
+                ":/",
+            ])?;
+
+            for path in untracked.lines() {
+                files.insert(root.join(unquote(path)), ChangedLines::All);
+            }
+        }
+
+        Ok(Self {
+            files,
+            staged_root: match since {
+                ChangedSince::Revision(_) => None,
+                ChangedSince::Staged => Some(root),
+            },
+        })
+    }
+
+    /// With --staged, what will be committed is checked rather than the working tree, which can
+    /// have changes that aren't staged. `None` if this isn't --staged, or the file isn't staged.
+    pub fn staged_contents(&self, path: &Path) -> Option<Result<String, ChangedFilesError>> {
+        let root = self.staged_root.as_ref()?;
+        let path = fs::canonicalize(path).ok()?;
+        if !self.files.contains_key(&path) {
+            return None;
+        }
+
+        // Index paths are relative to the root of the repository, and always use `/`
+        let relative_path = path
+            .strip_prefix(root)
+            .ok()?
+            .components()
+            .map(|component| component.as_os_str().to_string_lossy())
+            .collect::<Vec<_>>()
+            .join("/");
+
+        Some(git(root, &["show", &format!(":{relative_path}")]))
+    }
+
+    pub fn contains(&self, path: &Path) -> bool {
+        match fs::canonicalize(path) {
+            Ok(path) => self.files.contains_key(&path),
+            Err(_) => false,
+        }
+    }
+
+    /// The lines that changed in a file, or `None` if git didn't report it as changed.
+    pub fn changed_lines(&self, path: &Path) -> Option<&ChangedLines> {
+        self.files.get(&fs::canonicalize(path).ok()?)
+    }
+}
+
+// Paths with unusual characters are quoted and escaped like a C string
+fn unquote(path: &str) -> String {
+    let Some(path) = path
+        .strip_prefix('"')
+        .and_then(|path| path.strip_suffix('"'))
+    else {
+        return path.to_owned();
+    };
+
+    let mut unquoted = String::with_capacity(path.len());
+    let mut characters = path.chars();
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            unquoted.push(character);
+            continue;
+        }
+
+        match characters.next() {
+            Some('t') => unquoted.push('\t'),
+            Some('n') => unquoted.push('\n'),
+            Some(character) => unquoted.push(character),
+            None => {}
+        }
+    }
+
+    unquoted
+}
+
+// Parses the new side of a hunk header, such as `@@ -10,2 +12,3 @@`
+fn parse_hunk_header(line: &str) -> Option<RangeInclusive<usize>> {
+    let new_side = line.split(' ').find_map(|part| part.strip_prefix('+'))?;
+
+    let (start, count) = match new_side.split_once(',') {
+        Some((start, count)) => (start.parse::<usize>().ok()?, count.parse::<usize>().ok()?),
+        None => (new_side.parse().ok()?, 1),
+    };
+
+    // Hunks that only remove lines don't change anything that's left
+    if count == 0 {
+        return None;
+    }
+
+    Some(start..=start + count - 1)
+}
+
+fn parse_diff(root: &Path, diff: &str) -> HashMap<PathBuf, ChangedLines> {
+    let mut files = HashMap::new();
+    let mut current_file = None;
+    let mut in_header = false;
+
+    for line in diff.lines() {
+        if line.starts_with("diff --git ") {
+            current_file = None;
+            in_header = true;
+        } else if in_header && line.starts_with("+++ ") {
+            // Added lines can also start with `+++`, so this is only read in the header
+            current_file = unquote(&line["+++ ".len()..])
+                .strip_prefix("b/")
+                .map(|path| root.join(path));
+
+            if let Some(path) = &current_file {
+                files.insert(path.clone(), ChangedLines::Ranges(Vec::new()));
+            }
+        } else if line.starts_with("@@ ") {
+            in_header = false;
+
+            let (Some(path), Some(range)) = (&current_file, parse_hunk_header(line)) else {
+                continue;
+            };
+
+            if let Some(ChangedLines::Ranges(ranges)) = files.get_mut(path) {
+                ranges.push(range);
+            }
+        }
+    }
+
+    files
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_diff() {
+        let diff = "\
+diff --git a/src/changed.lua b/src/changed.lua
+index 1111111..2222222 100644
+--- a/src/changed.lua
++++ b/src/changed.lua
+@@ -3 +3 @@ local function f()
+-\tprint(1)
++\tprint(2)
+@@ -10,0 +11,3 @@ end
++++ b/not a file header
++print(3)
++print(4)
+@@ -20,2 +23,0 @@ end
+-print(5)
+-print(6)
+diff --git a/\"with \\\"quotes\\\".lua\" b/\"with \\\"quotes\\\".lua\"
+new file mode 100644
+index 0000000..3333333
+--- /dev/null
++++ \"b/with \\\"quotes\\\".lua\"
+@@ -0,0 +1,2 @@
++local x = 1
++return x
+";
+
+        let root = Path::new("/project");
+        let files = parse_diff(root, diff);
+
+        assert_eq!(files.len(), 2);
+        assert_eq!(
+            files[&root.join("src/changed.lua")],
+            ChangedLines::Ranges(vec![3..=3, 11..=13])
+        );
+        assert_eq!(
+            files[&root.join("with \"quotes\".lua")],
+            ChangedLines::Ranges(vec![1..=2])
+        );
+    }
+
+    #[test]
+    fn test_overlaps() {
+        let lines = ChangedLines::Ranges(vec![3..=3, 11..=13]);
+
+        assert!(lines.overlaps(3..=3));
+        assert!(lines.overlaps(1..=4));
+        assert!(lines.overlaps(13..=20));
+        assert!(!lines.overlaps(4..=10));
+        assert!(!lines.overlaps(14..=14));
+
+        assert!(ChangedLines::All.overlaps(100..=100));
+    }
+    #[test]
+    fn test_staged_contents() {
+        let directory = std::env::temp_dir().join(format!("selene-staged-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+        let directory = fs::canonicalize(&directory).unwrap();
+
+        let git = |args: &[&str]| git(&directory, args).unwrap();
+        git(&["init", "--quiet"]);
+
+        let path = directory.join("code.lua");
+        fs::write(&path, "local x = 1\n").unwrap();
+        git(&["add", "code.lua"]);
+        git(&[
+            "-c",
+            "user.name=selene",
+            "-c",
+            "user.email=selene@example.com",
+            "commit",
+            "--quiet",
+            "--message",
+            "Initial commit",
+        ]);
+
+        fs::write(&path, "local x = 2\n").unwrap();
+        git(&["add", "code.lua"]);
+        fs::write(&path, "local x = 3\n").unwrap();
+
+        let staged = ChangedFiles::from_git_in(&directory, ChangedSince::Staged).unwrap();
+        assert_eq!(
+            staged.staged_contents(&path).unwrap().unwrap(),
+            "local x = 2\n"
+        );
+        assert!(staged
+            .staged_contents(&directory.join("other.lua"))
+            .is_none());
+
+        let since_head =
+            ChangedFiles::from_git_in(&directory, ChangedSince::Revision("HEAD")).unwrap();
+        assert!(since_head.contains(&path));
+        assert!(since_head.staged_contents(&path).is_none());
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -0,0 +1,628 @@
+//! Loading a checker for every config used in a run. Every file is checked with the nearest
+//! config to it, along with any `[[overrides]]` in that config that match it. Configs that end
+//! up the same share a checker, since collecting the standard library can be slow (especially
+//! Roblox's).
+
+use std::{
+    collections::HashMap,
+    fmt::Write,
+    fs,
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use selene_lib::{standard_library::StandardLibrary, Checker, CheckerConfig, LintRegistry};
+use serde::Serialize;
+
+use crate::{
+    cache::{self, Cache},
+    config::{self, LoadedConfig},
+    opts, standard_library,
+};
+
+/// A checker, along with everything else that comes from the config it was created from.
+pub struct ConfiguredChecker {
+    pub checker: Arc<Checker<toml::value::Value>>,
+    pub standard_library: Arc<StandardLibrary>,
+    pub cache: Option<Cache>,
+    exclude_set: globset::GlobSet,
+    // Absolute, since excludes are matched relative to it
+    directory: Option<PathBuf>,
+}
+
+impl ConfiguredChecker {
+    /// Whether the config excludes a file, either relative to the current directory
+    /// or to the directory of the config.
+    pub fn is_excluded(&self, path: &Path) -> bool {
+        self.exclude_set.is_match(path)
+            || matches(&self.exclude_set, self.directory.as_deref(), path)
+    }
+}
+
+fn matches(glob_set: &globset::GlobSet, directory: Option<&Path>, path: &Path) -> bool {
+    let Some(directory) = directory else {
+        return false;
+    };
+
+    match config::absolute_path(path).strip_prefix(directory) {
+        Ok(relative_path) => glob_set.is_match(relative_path),
+        Err(_) => false,
+    }
+}
+
+struct ConfigFile {
+    config: CheckerConfig<toml::value::Value>,
+    directories: Vec<PathBuf>,
+    // Absolute, the config and every config it extends
+    paths: Vec<PathBuf>,
+    directory: Option<PathBuf>,
+    exclude_set: globset::GlobSet,
+    override_sets: Vec<globset::GlobSet>,
+
+    // Keyed by which overrides apply. `None` if the checker couldn't be created.
+    checkers: HashMap<Vec<usize>, Option<Arc<ConfiguredChecker>>>,
+}
+
+impl ConfigFile {
+    fn matching_overrides(&self, path: &Path) -> Vec<usize> {
+        self.override_sets
+            .iter()
+            .enumerate()
+            .filter(|(_, glob_set)| {
+                glob_set.is_match(path) || matches(glob_set, self.directory.as_deref(), path)
+            })
+            .map(|(index, _)| index)
+            .collect()
+    }
+
+    // Every standard library the config or its overrides could ask for, including the bases
+    // of the ones that have been collected
+    fn standard_library_names(&self) -> impl Iterator<Item = &str> {
+        std::iter::once(self.config.std())
+            .chain(
+                self.config
+                    .overrides
+                    .iter()
+                    .filter_map(|config_override| config_override.std.as_deref()),
+            )
+            .chain(
+                self.checkers
+                    .values()
+                    .flatten()
+                    .filter_map(|checker| checker.standard_library.base.as_deref()),
+            )
+            .flat_map(|names| names.split('+'))
+    }
+}
+
+/// A lint written in Lua, from `plugins` in the config.
+#[derive(Serialize)]
+pub struct Plugin {
+    /// The name of the file without its extension, which is the name of the lint
+    pub name: String,
+    // Only hashed for the cache when selene is built without plugin support
+    #[cfg_attr(not(feature = "plugins"), allow(dead_code))]
+    pub source: String,
+}
+
+// The parts of a checker that only depend on the config, shared between every config file
+// and override that ends up the same
+type SharedChecker = (Arc<Checker<toml::value::Value>>, Arc<StandardLibrary>);
+
+pub struct Checkers {
+    // Set with --config, in which case every file uses it
+    config_path: Option<PathBuf>,
+    use_cache: bool,
+
+    // The nearest config to every directory a file has been found in
+    config_paths: HashMap<PathBuf, Option<PathBuf>>,
+
+    // `None` if the config couldn't be loaded, so that its errors are only reported once
+    config_files: HashMap<Option<PathBuf>, Option<ConfigFile>>,
+
+    // Keyed by the config after `extends` and overrides are applied, and the directories
+    // standard libraries are looked for in
+    shared: HashMap<String, SharedChecker>,
+
+    errors: Vec<String>,
+}
+
+impl Checkers {
+    pub fn new(config_path: Option<PathBuf>, use_cache: bool) -> Self {
+        Self {
+            config_path,
+            use_cache,
+            config_paths: HashMap::new(),
+            config_files: HashMap::new(),
+            shared: HashMap::new(),
+            errors: Vec::new(),
+        }
+    }
+
+    pub fn from_options(options: &opts::Options) -> Self {
+        Self::new(
+            options.config.as_ref().map(PathBuf::from),
+            !options.no_cache,
+        )
+    }
+
+    /// Gets the checker for a file, loading its config if it hasn't been already.
+    /// If it can't be loaded, `None` is returned, and the errors can be found with `take_errors`.
+    pub fn for_file(&mut self, path: &Path) -> Option<Arc<ConfiguredChecker>> {
+        let config_path = match &self.config_path {
+            Some(config_path) => Some(config_path.clone()),
+            None => {
+                let directory = path.parent().unwrap_or(path).to_path_buf();
+
+                self.config_paths
+                    .entry(directory)
+                    .or_insert_with(|| config::find_config(path))
+                    .clone()
+            }
+        };
+
+        if !self.config_files.contains_key(&config_path) {
+            let config_file = self.load_config_file(config_path.as_deref());
+            self.config_files.insert(config_path.clone(), config_file);
+        }
+
+        let config_file = self.config_files.get_mut(&config_path).unwrap().as_mut()?;
+        let overrides = config_file.matching_overrides(path);
+
+        if let Some(checker) = config_file.checkers.get(&overrides) {
+            return checker.clone();
+        }
+
+        let checker = create_checker(
+            &mut self.shared,
+            &mut self.errors,
+            self.use_cache,
+            config_file,
+            &overrides,
+        )
+        .map(Arc::new);
+
+        config_file.checkers.insert(overrides, checker.clone());
+        checker
+    }
+
+    /// Whether any config couldn't be loaded.
+    pub fn has_failed(&self) -> bool {
+        self.config_files
+            .values()
+            .any(|config_file| match config_file {
+                Some(config_file) => config_file.checkers.values().any(Option::is_none),
+                None => true,
+            })
+    }
+
+    /// Takes every error that has happened while loading configs so far.
+    pub fn take_errors(&mut self) -> Vec<String> {
+        std::mem::take(&mut self.errors)
+    }
+
+    pub fn loaded(&self) -> impl Iterator<Item = &ConfiguredChecker> {
+        self.config_files
+            .values()
+            .flatten()
+            .flat_map(|config_file| config_file.checkers.values().flatten())
+            .map(AsRef::as_ref)
+    }
+
+    /// Whether a file is read when loading configs, so the checkers have to be created again
+    /// when it changes. This is any `selene.toml`, since it can change which config a file uses,
+    /// along with every config that has been loaded, the configs they extend, and the standard
+    /// libraries they use.
+    pub fn is_config_file(&self, path: &Path) -> bool {
+        let path = config::absolute_path(path);
+
+        if path
+            .file_name()
+            .is_some_and(|name| name == config::CONFIG_FILE_NAME)
+        {
+            return true;
+        }
+
+        let config_files = self.config_files.values().flatten();
+
+        if self
+            .config_files
+            .keys()
+            .flatten()
+            .any(|config_path| config::absolute_path(config_path) == path)
+            || config_files
+                .clone()
+                .any(|config_file| config_file.paths.contains(&path))
+        {
+            return true;
+        }
+
+        if !matches!(
+            path.extension().and_then(|extension| extension.to_str()),
+            Some("toml" | "yml" | "yaml")
+        ) {
+            return false;
+        }
+
+        let (Some(name), Some(parent)) = (
+            path.file_stem().and_then(|name| name.to_str()),
+            path.parent(),
+        ) else {
+            return false;
+        };
+
+        // Standard libraries are looked for in the current directory, then next to the configs
+        let in_current_dir = std::env::current_dir().is_ok_and(|current_dir| current_dir == parent);
+
+        config_files.into_iter().any(|config_file| {
+            (in_current_dir
+                || config_file
+                    .directories
+                    .iter()
+                    .any(|directory| config::absolute_path(directory) == parent))
+                && config_file
+                    .standard_library_names()
+                    .any(|standard_library_name| standard_library_name == name)
+        })
+    }
+
+    fn load_config_file(&mut self, config_path: Option<&Path>) -> Option<ConfigFile> {
+        let LoadedConfig {
+            config,
+            directories,
+            paths,
+        } = match config_path {
+            Some(config_path) => match config::load_config(config_path) {
+                Ok(loaded) => loaded,
+                Err(error) => {
+                    self.errors.push(format!("Couldn't load config: {error}"));
+                    return None;
+                }
+            },
+
+            None => LoadedConfig {
+                config: CheckerConfig::default(),
+                directories: Vec::new(),
+                paths: Vec::new(),
+            },
+        };
+
+        let mut build_glob_set = |patterns: &[String]| {
+            let mut builder = globset::GlobSetBuilder::new();
+            for pattern in patterns {
+                builder.add(match globset::Glob::new(pattern) {
+                    Ok(glob) => glob,
+                    Err(error) => {
+                        self.errors.push(format!("Invalid glob pattern: {error}"));
+                        return None;
+                    }
+                });
+            }
+
+            match builder.build() {
+                Ok(glob_set) => Some(glob_set),
+                Err(error) => {
+                    self.errors.push(error.to_string());
+                    None
+                }
+            }
+        };
+
+        let exclude_set = build_glob_set(&config.exclude)?;
+
+        let mut override_sets = Vec::new();
+        for config_override in &config.overrides {
+            override_sets.push(build_glob_set(&config_override.files)?);
+        }
+
+        Some(ConfigFile {
+            directory: config_path
+                .and_then(Path::parent)
+                .map(config::absolute_path),
+            config,
+            directories,
+            paths: paths
+                .iter()
+                .map(|path| config::absolute_path(path))
+                .collect(),
+            exclude_set,
+            override_sets,
+            checkers: HashMap::new(),
+        })
+    }
+}
+
+fn create_checker(
+    shared: &mut HashMap<String, SharedChecker>,
+    errors: &mut Vec<String>,
+    use_cache: bool,
+    config_file: &ConfigFile,
+    overrides: &[usize],
+) -> Option<ConfiguredChecker> {
+    let config = config_file.config.with_overrides(
+        overrides
+            .iter()
+            .map(|&index| &config_file.config.overrides[index]),
+    );
+
+    let key = format!(
+        "{}\0{:?}",
+        serde_json::to_value(&config).expect("couldn't serialize config"),
+        config_file.directories
+    );
+
+    let standard_library = match shared.get(&key) {
+        Some((_, standard_library)) => Arc::clone(standard_library),
+        None => match collect_standard_library(&config, &config_file.directories) {
+            Ok(standard_library) => Arc::new(standard_library),
+            Err(error) => {
+                errors.push(error);
+                return None;
+            }
+        },
+    };
+
+    let plugins = match read_plugins(&config, &config_file.directories) {
+        Ok(plugins) => plugins,
+        Err(error) => {
+            errors.push(error);
+            return None;
+        }
+    };
+
+    // Created before the checker, since that takes the config
+    let cache = if config.cache && use_cache {
+        let root = match &config_file.directory {
+            Some(directory) => directory.clone(),
+            None => std::env::current_dir().unwrap(),
+        };
+
+        Some(Cache::new(
+            &root.join(cache::CACHE_DIRECTORY),
+            &config,
+            &standard_library,
+            &plugins,
+        ))
+    } else {
+        None
+    };
+
+    let checker = match shared.get(&key) {
+        Some((checker, _)) => Arc::clone(checker),
+        None => {
+            let registry = match create_registry(&plugins) {
+                Ok(registry) => registry,
+                Err(error) => {
+                    errors.push(error);
+                    return None;
+                }
+            };
+
+            let checker =
+                match Checker::with_registry(config, (*standard_library).clone(), registry) {
+                    Ok(checker) => Arc::new(checker),
+                    Err(error) => {
+                        errors.push(error.to_string());
+                        return None;
+                    }
+                };
+
+            shared.insert(key, (Arc::clone(&checker), Arc::clone(&standard_library)));
+            checker
+        }
+    };
+
+    Some(ConfiguredChecker {
+        checker,
+        standard_library,
+        cache,
+        exclude_set: config_file.exclude_set.clone(),
+        directory: config_file.directory.clone(),
+    })
+}
+
+// Plugins are looked for next to the config, then next to every config it extends
+fn read_plugins(
+    config: &CheckerConfig<toml::value::Value>,
+    config_directories: &[PathBuf],
+) -> Result<Vec<Plugin>, String> {
+    config
+        .plugins
+        .iter()
+        .map(|plugin| {
+            let path = config_directories
+                .iter()
+                .map(|directory| directory.join(plugin))
+                .find(|path| path.exists())
+                .unwrap_or_else(|| PathBuf::from(plugin));
+
+            let name = path
+                .file_stem()
+                .and_then(|name| name.to_str())
+                .ok_or_else(|| format!("Plugin `{plugin}` has no name"))?;
+
+            match fs::read_to_string(&path) {
+                Ok(source) => Ok(Plugin {
+                    name: name.to_owned(),
+                    source,
+                }),
+
+                Err(error) => Err(format!(
+                    "Couldn't read plugin `{}`: {error}",
+                    path.display()
+                )),
+            }
+        })
+        .collect()
+}
+
+fn create_registry(plugins: &[Plugin]) -> Result<LintRegistry<toml::value::Value>, String> {
+    #[cfg_attr(not(feature = "plugins"), allow(unused_mut))]
+    let mut registry = LintRegistry::default();
+
+    #[cfg(feature = "plugins")]
+    for plugin in plugins {
+        selene_lib::plugins::register_plugin(&mut registry, &plugin.name, &plugin.source)
+            .map_err(|error| format!("Couldn't load plugin: {error}"))?;
+    }
+
+    #[cfg(not(feature = "plugins"))]
+    if !plugins.is_empty() {
+        return Err(
+            "selene was built without plugin support, so `plugins` can't be used".to_owned(),
+        );
+    }
+
+    Ok(registry)
+}
+
+fn collect_standard_library(
+    config: &CheckerConfig<toml::value::Value>,
+    config_directories: &[PathBuf],
+) -> Result<StandardLibrary, String> {
+    let current_dir = std::env::current_dir().unwrap();
+
+    match standard_library::collect_standard_library(
+        config,
+        config.std(),
+        &current_dir,
+        config_directories,
+    ) {
+        Ok(Some(library)) => Ok(library),
+
+        Ok(None) => Err("Standard library was empty.".to_owned()),
+
+        Err(error) => {
+            let missing_files: Vec<_> = config
+                .std()
+                .split('+')
+                .filter(|name| {
+                    !PathBuf::from(format!("{name}.yml")).exists()
+                        && !PathBuf::from(format!("{name}.yaml")).exists()
+                        && !PathBuf::from(format!("{name}.toml")).exists()
+                })
+                .filter(|name| !cfg!(feature = "roblox") || *name != "roblox")
+                .collect();
+
+            if !missing_files.is_empty() {
+                let mut message = format!(
+                    "`std = \"{}\"`, but some libraries could not be found:",
+                    config.std()
+                );
+
+                for library_name in missing_files {
+                    write!(message, "\n  `{library_name}`").unwrap();
+                }
+
+                return Err(message);
+            }
+
+            Err(format!("Could not collect standard library: {error}"))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use selene_lib::lints::Severity;
+
+    fn temp_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("selene-checkers-{name}-{}", std::process::id()));
+        fs::create_dir_all(directory.join("nested")).unwrap();
+        directory
+    }
+
+    #[test]
+    fn test_is_config_file() {
+        let directory = temp_directory("is-config-file");
+
+        fs::write(
+            directory.join(config::CONFIG_FILE_NAME),
+            "extends = \"base.toml\"\n\n[[overrides]]\nfiles = [\"tests/**\"]\nstd = \"testez\"\n",
+        )
+        .unwrap();
+        fs::write(directory.join("base.toml"), "std = \"lua51+custom\"\n").unwrap();
+        fs::write(directory.join("custom.yml"), "globals: {}\n").unwrap();
+
+        let mut checkers = Checkers::new(None, false);
+        assert!(checkers.for_file(&directory.join("code.lua")).is_some());
+
+        assert!(checkers.is_config_file(&directory.join(config::CONFIG_FILE_NAME)));
+        assert!(checkers.is_config_file(&directory.join("nested").join(config::CONFIG_FILE_NAME)));
+        assert!(checkers.is_config_file(&directory.join("base.toml")));
+        assert!(checkers.is_config_file(&directory.join("custom.yml")));
+        assert!(checkers.is_config_file(&directory.join("testez.yaml")));
+
+        assert!(!checkers.is_config_file(&directory.join("Cargo.toml")));
+        assert!(!checkers.is_config_file(&directory.join("nested").join("custom.yml")));
+        assert!(!checkers.is_config_file(&directory.join("custom.lua")));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+    #[test]
+    fn test_nested_overrides() {
+        let directory = temp_directory("nested-overrides");
+        let nested = directory.join("nested");
+
+        fs::write(
+            nested.join(config::CONFIG_FILE_NAME),
+            "[lints]\nshadowing = \"deny\"\n\n[[overrides]]\nfiles = [\"tests/**\"]\nstd = \"extra\"\n\n[overrides.lints]\nshadowing = \"allow\"\n",
+        )
+        .unwrap();
+        fs::write(
+            nested.join("extra.yml"),
+            "globals:\n  extra_global:\n    any: true\n",
+        )
+        .unwrap();
+
+        // Written relative to the current directory, which is nowhere near the config, so the
+        // override's glob only matches relative to the config's directory
+        let normal_components = |path: &Path| {
+            path.components()
+                .filter(|component| matches!(component, std::path::Component::Normal(_)))
+                .map(|component| component.as_os_str().to_owned())
+                .collect::<Vec<_>>()
+        };
+
+        let mut relative_nested = PathBuf::new();
+        for _ in normal_components(&std::env::current_dir().unwrap()) {
+            relative_nested.push("..");
+        }
+        relative_nested.extend(normal_components(&nested));
+
+        let shadowing = |checker: &ConfiguredChecker| {
+            let ast = full_moon::parse("local x = 1\nlocal x = x + 1\nprint(x)\n").unwrap();
+
+            checker
+                .checker
+                .test_on(&ast)
+                .into_iter()
+                .find(|diagnostic| diagnostic.diagnostic.code == "shadowing")
+                .map(|diagnostic| diagnostic.severity)
+        };
+
+        let mut checkers = Checkers::new(None, false);
+
+        let test_checker = checkers
+            .for_file(&relative_nested.join("tests").join("spec.lua"))
+            .unwrap();
+        assert!(test_checker
+            .standard_library
+            .find_global(&["extra_global"])
+            .is_some());
+        assert_eq!(shadowing(&test_checker), Some(Severity::Allow));
+
+        let source_checker = checkers
+            .for_file(&relative_nested.join("src").join("code.lua"))
+            .unwrap();
+        assert!(source_checker
+            .standard_library
+            .find_global(&["extra_global"])
+            .is_none());
+        assert_eq!(shadowing(&source_checker), Some(Severity::Error));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
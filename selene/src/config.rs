@@ -0,0 +1,342 @@
+//! Finding and reading `selene.toml`. Every file uses the nearest `selene.toml` in its own
+//! directory or any directory above it, so that different parts of a project can be configured
+//! differently. A config can build on another with `extends = "../selene.toml"`, in which case
+//! anything it sets overrides the config it extends, and tables such as `[lints]` are merged.
+
+use std::{
+    fmt, fs, io,
+    path::{Component, Path, PathBuf},
+};
+
+use selene_lib::CheckerConfig;
+
+pub const CONFIG_FILE_NAME: &str = "selene.toml";
+
+#[derive(Debug)]
+pub enum ConfigError {
+    Io {
+        source: io::Error,
+        path: PathBuf,
+    },
+
+    Toml {
+        source: toml::de::Error,
+        path: PathBuf,
+    },
+
+    ExtendsNotString {
+        path: PathBuf,
+    },
+
+    ExtendsCycle {
+        path: PathBuf,
+    },
+}
+
+impl fmt::Display for ConfigError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            ConfigError::Io { source, path } => {
+                write!(
+                    formatter,
+                    "couldn't read config file `{}`: {source}",
+                    path.display()
+                )
+            }
+
+            ConfigError::Toml { source, path } => {
+                write!(
+                    formatter,
+                    "config file `{}` not in correct format: {source}",
+                    path.display()
+                )
+            }
+
+            ConfigError::ExtendsNotString { path } => {
+                write!(
+                    formatter,
+                    "`extends` in `{}` must be the path to another config",
+                    path.display()
+                )
+            }
+
+            ConfigError::ExtendsCycle { path } => {
+                write!(formatter, "`{}` ends up extending itself", path.display())
+            }
+        }
+    }
+}
+
+impl std::error::Error for ConfigError {}
+
+pub struct LoadedConfig {
+    pub config: CheckerConfig<toml::value::Value>,
+
+    /// The directory of the config, followed by the directories of every config it extends.
+    /// Standard libraries are looked for in all of them.
+    pub directories: Vec<PathBuf>,
+
+    /// The config, followed by every config it extends.
+    pub paths: Vec<PathBuf>,
+}
+
+/// Reads a config, along with every config it extends.
+pub fn load_config(path: &Path) -> Result<LoadedConfig, ConfigError> {
+    let contents = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        source,
+        path: path.to_path_buf(),
+    })?;
+
+    let toml_error = |source| ConfigError::Toml {
+        source,
+        path: path.to_path_buf(),
+    };
+
+    let directory = directory_of(path);
+
+    let config: CheckerConfig<toml::value::Value> =
+        toml::from_str(&contents).map_err(toml_error)?;
+
+    // Configs that don't extend anything are read directly, so errors point to where they are
+    if config.extends.is_none() {
+        return Ok(LoadedConfig {
+            config,
+            directories: vec![directory],
+            paths: vec![path.to_path_buf()],
+        });
+    }
+
+    let mut directories = Vec::new();
+    let mut paths = Vec::new();
+    let table = read_extended(path, &mut Vec::new(), &mut directories, &mut paths)?;
+
+    Ok(LoadedConfig {
+        config: toml::Value::Table(table).try_into().map_err(toml_error)?,
+        directories,
+        paths,
+    })
+}
+
+fn directory_of(path: &Path) -> PathBuf {
+    path.parent().map(Path::to_path_buf).unwrap_or_default()
+}
+
+fn read_extended(
+    path: &Path,
+    seen: &mut Vec<PathBuf>,
+    directories: &mut Vec<PathBuf>,
+    paths: &mut Vec<PathBuf>,
+) -> Result<toml::Table, ConfigError> {
+    let canonical_path = fs::canonicalize(path).map_err(|source| ConfigError::Io {
+        source,
+        path: path.to_path_buf(),
+    })?;
+
+    if seen.contains(&canonical_path) {
+        return Err(ConfigError::ExtendsCycle {
+            path: path.to_path_buf(),
+        });
+    }
+
+    seen.push(canonical_path);
+
+    let contents = fs::read_to_string(path).map_err(|source| ConfigError::Io {
+        source,
+        path: path.to_path_buf(),
+    })?;
+
+    let mut table: toml::Table = toml::from_str(&contents).map_err(|source| ConfigError::Toml {
+        source,
+        path: path.to_path_buf(),
+    })?;
+
+    // `rules` is an alias of `lints`, so they need to be merged as the same table
+    if !table.contains_key("lints") {
+        if let Some(rules) = table.remove("rules") {
+            table.insert("lints".to_owned(), rules);
+        }
+    }
+
+    let directory = directory_of(path);
+    directories.push(directory.clone());
+    paths.push(path.to_path_buf());
+
+    match table.remove("extends") {
+        Some(toml::Value::String(extends)) => {
+            let mut base = read_extended(&directory.join(extends), seen, directories, paths)?;
+            merge(&mut base, table);
+            Ok(base)
+        }
+
+        Some(_) => Err(ConfigError::ExtendsNotString {
+            path: path.to_path_buf(),
+        }),
+
+        None => Ok(table),
+    }
+}
+
+// Tables are merged key by key, anything else is replaced entirely
+fn merge(base: &mut toml::Table, overrides: toml::Table) {
+    for (key, value) in overrides {
+        match (base.get_mut(&key), value) {
+            (Some(toml::Value::Table(base_table)), toml::Value::Table(override_table)) => {
+                merge(base_table, override_table);
+            }
+
+            (_, value) => {
+                base.insert(key, value);
+            }
+        }
+    }
+}
+
+/// Makes a path absolute without touching the file system, removing any `.` or `..` in it.
+/// Unlike canonicalizing, this works for files that don't exist, such as stdin.
+pub fn absolute_path(path: &Path) -> PathBuf {
+    let mut absolute = PathBuf::new();
+
+    if path.is_relative() {
+        if let Ok(current_dir) = std::env::current_dir() {
+            absolute.push(current_dir);
+        }
+    }
+
+    for component in path.components() {
+        match component {
+            Component::CurDir => {}
+            Component::ParentDir => {
+                absolute.pop();
+            }
+            component => absolute.push(component),
+        }
+    }
+
+    absolute
+}
+
+/// Finds the nearest config to a file, looking in its directory and then every directory above it.
+pub fn find_config(path: &Path) -> Option<PathBuf> {
+    absolute_path(path)
+        .ancestors()
+        .skip(1)
+        .map(|directory| directory.join(CONFIG_FILE_NAME))
+        .find(|config_path| config_path.is_file())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use selene_lib::LintVariation;
+
+    fn temp_directory(name: &str) -> PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("selene-config-{name}-{}", std::process::id()));
+        fs::create_dir_all(directory.join("nested")).unwrap();
+        directory
+    }
+
+    #[test]
+    fn test_extends() {
+        let directory = temp_directory("extends");
+
+        fs::write(
+            directory.join(CONFIG_FILE_NAME),
+            "std = \"roblox\"\nexclude = [\"vendor/*\"]\n\n[rules]\nshadowing = \"allow\"\nunused_variable = \"deny\"\n\n[config.empty_if]\ncomments_count = true\n",
+        )
+        .unwrap();
+
+        fs::write(
+            directory.join("nested").join(CONFIG_FILE_NAME),
+            "extends = \"../selene.toml\"\nstd = \"lua51\"\n\n[lints]\nshadowing = \"warn\"\n",
+        )
+        .unwrap();
+
+        let loaded = load_config(&directory.join("nested").join(CONFIG_FILE_NAME)).unwrap();
+        let config = loaded.config;
+
+        assert_eq!(config.std(), "lua51");
+        assert_eq!(config.exclude, vec!["vendor/*".to_owned()]);
+        assert_eq!(config.lints["shadowing"], LintVariation::Warn);
+        assert_eq!(config.lints["unused_variable"], LintVariation::Deny);
+        assert!(config.config.contains_key("empty_if"));
+        assert_eq!(
+            loaded.directories,
+            vec![
+                directory.join("nested"),
+                directory.join("nested").join("..")
+            ]
+        );
+        assert_eq!(
+            loaded.paths,
+            vec![
+                directory.join("nested").join(CONFIG_FILE_NAME),
+                directory.join("nested").join("..").join(CONFIG_FILE_NAME)
+            ]
+        );
+
+        assert_eq!(
+            find_config(&directory.join("nested").join("code.lua")),
+            Some(directory.join("nested").join(CONFIG_FILE_NAME))
+        );
+        assert_eq!(
+            find_config(&directory.join("other").join("code.lua")),
+            Some(directory.join(CONFIG_FILE_NAME))
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_overrides() {
+        let directory = temp_directory("overrides");
+
+        fs::write(
+            directory.join(CONFIG_FILE_NAME),
+            "std = \"roblox\"\n\n[lints]\nshadowing = \"deny\"\n\n[[overrides]]\nfiles = [\"tests/**\"]\nstd = \"testez\"\n\n[overrides.lints]\nshadowing = \"allow\"\n\n[[overrides]]\nfiles = [\"tests/slow/**\"]\nstd = \"slow\"\n",
+        )
+        .unwrap();
+
+        let config = load_config(&directory.join(CONFIG_FILE_NAME))
+            .unwrap()
+            .config;
+
+        assert_eq!(config.overrides.len(), 2);
+        assert_eq!(config.overrides[0].files, vec!["tests/**".to_owned()]);
+
+        let overridden = config.with_overrides(&config.overrides);
+        assert_eq!(overridden.std(), "roblox+testez+slow");
+        assert_eq!(overridden.lints["shadowing"], LintVariation::Allow);
+        assert!(overridden.overrides.is_empty());
+
+        let first_only = config.with_overrides(&config.overrides[..1]);
+        assert_eq!(first_only.std(), "roblox+testez");
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_extends_cycle() {
+        let directory = temp_directory("cycle");
+
+        fs::write(
+            directory.join(CONFIG_FILE_NAME),
+            "extends = \"nested/selene.toml\"\n",
+        )
+        .unwrap();
+
+        fs::write(
+            directory.join("nested").join(CONFIG_FILE_NAME),
+            "extends = \"../selene.toml\"\n",
+        )
+        .unwrap();
+
+        assert!(matches!(
+            load_config(&directory.join(CONFIG_FILE_NAME)),
+            Err(ConfigError::ExtendsCycle { .. })
+        ));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
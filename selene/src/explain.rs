@@ -0,0 +1,210 @@
+//! `selene --explain` and `selene list-lints`, so that lints can be looked up without going
+//! online. The documentation of every lint is embedded from the book.
+
+use std::path::{Path, PathBuf};
+
+use selene_lib::{
+    lints::{LintType, Severity},
+    CheckerConfig, LintInfo,
+};
+
+use crate::{
+    config,
+    json_output::{self, JsonExplanation, JsonLint, JsonOutput},
+    opts::{self, DisplayStyle},
+};
+
+macro_rules! lint_documentation {
+    ($($name:ident,)+) => {
+        fn documentation(name: &str) -> Option<&'static str> {
+            match name {
+                $(
+                    stringify!($name) => Some(include_str!(concat!(
+                        "../../docs/src/lints/",
+                        stringify!($name),
+                        ".md"
+                    ))),
+                )+
+
+                _ => None,
+            }
+        }
+    };
+}
+
+lint_documentation! {
+    almost_swapped,
+    bad_string_escape,
+    compare_nan,
+    constant_table_comparison,
+    deprecated,
+    divide_by_zero,
+    duplicate_keys,
+    empty_if,
+    empty_loop,
+    global_usage,
+    high_cyclomatic_complexity,
+    if_same_then_else,
+    ifs_same_cond,
+    incorrect_standard_library_use,
+    manual_table_clone,
+    mismatched_arg_count,
+    multiple_statements,
+    must_use,
+    parenthese_conditions,
+    roblox_incorrect_color3_new_bounds,
+    roblox_incorrect_roact_usage,
+    roblox_suspicious_udim2_new,
+    shadowing,
+    suspicious_reverse_loop,
+    type_check_inside_call,
+    unbalanced_assignments,
+    undefined_variable,
+    unscoped_variables,
+    unused_lint_filter,
+    unused_variable,
+}
+
+fn severity_name(severity: Severity) -> &'static str {
+    match severity {
+        Severity::Allow => "allow",
+        Severity::Error => "error",
+        Severity::Warning => "warning",
+    }
+}
+
+fn find_lint(name: &str) -> Option<&'static LintInfo> {
+    selene_lib::all_lints()
+        .iter()
+        .find(|lint| lint.name == name)
+}
+
+/// Prints the documentation of a lint.
+pub fn explain(name: &str, display_style: DisplayStyle) -> Result<(), String> {
+    let lint = find_lint(name).ok_or_else(|| format!("there is no lint named `{name}`"))?;
+    let documentation = documentation(lint.name);
+
+    if display_style == DisplayStyle::Json2 {
+        json_output::print_json(JsonOutput::Explanation(JsonExplanation {
+            name: lint.name,
+            severity: lint.severity,
+            category: lint.lint_type,
+            documentation,
+        }));
+
+        return Ok(());
+    }
+
+    let (title, body) = match documentation {
+        Some(documentation) => match documentation.split_once('\n') {
+            Some((title, body)) if title.starts_with("# ") => (title.to_owned(), body),
+            _ => (format!("# {}", lint.name), documentation),
+        },
+
+        None => (
+            format!("# {}", lint.name),
+            "There is no documentation for this lint.\n",
+        ),
+    };
+
+    println!("{title}");
+    println!("Category: {}", lint.lint_type.name());
+    println!("Default severity: {}", severity_name(lint.severity));
+    println!();
+    print!("{body}");
+
+    Ok(())
+}
+
+// Found the same way as the config for stdin, so it's the nearest one to the current directory
+fn current_config(
+    options: &opts::Options,
+) -> Result<CheckerConfig<toml::value::Value>, config::ConfigError> {
+    let config_path = match &options.config {
+        Some(config_path) => Some(PathBuf::from(config_path)),
+        None => config::find_config(Path::new("-")),
+    };
+
+    match config_path {
+        Some(config_path) => Ok(config::load_config(&config_path)?.config),
+        None => Ok(CheckerConfig::default()),
+    }
+}
+
+/// Prints every lint, along with whether the current config enables it.
+pub fn list_lints(options: &opts::Options) -> Result<(), config::ConfigError> {
+    let config = current_config(options)?;
+
+    if options.display_style() == DisplayStyle::Json2 {
+        for lint in selene_lib::all_lints() {
+            json_output::print_json(JsonOutput::Lint(JsonLint {
+                name: lint.name,
+                severity: lint.severity,
+                category: lint.lint_type,
+                enabled: lint.severity_with(&config) != Severity::Allow,
+                config: lint.default_config(),
+            }));
+        }
+
+        return Ok(());
+    }
+
+    let name_width = selene_lib::all_lints()
+        .iter()
+        .map(|lint| lint.name.len())
+        .max()
+        .unwrap_or_default();
+
+    let category_width = LintType::ALL
+        .iter()
+        .map(|lint_type| lint_type.name().len())
+        .max()
+        .unwrap_or_default();
+
+    for lint in selene_lib::all_lints() {
+        let enabled = if lint.severity_with(&config) == Severity::Allow {
+            "disabled"
+        } else {
+            "enabled"
+        };
+
+        println!(
+            "{:name_width$}  {:7}  {:category_width$}  {enabled}",
+            lint.name,
+            severity_name(lint.severity),
+            lint.lint_type.name(),
+        );
+
+        if let serde_json::Value::Object(fields) = lint.default_config() {
+            for (field, default) in fields {
+                match toml::Value::try_from(&default) {
+                    Ok(default) => println!("    {field} = {default}"),
+                    Err(_) => println!("    {field} (not set by default)"),
+                }
+            }
+        }
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_every_lint_is_documented() {
+        for lint in selene_lib::all_lints() {
+            // Only reported for invalid `selene:` comments, which explain themselves
+            if lint.name == "invalid_lint_filter" {
+                continue;
+            }
+
+            assert!(
+                documentation(lint.name).is_some(),
+                "{} has no documentation",
+                lint.name
+            );
+        }
+    }
+}
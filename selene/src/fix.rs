@@ -0,0 +1,149 @@
+//! Applies the machine applicable suggestions from lints to source code, for `--fix`.
+
+use selene_lib::{
+    lints::{apply_suggestions, Applicability, Severity},
+    Checker,
+};
+
+// Fixes can create new diagnostics with their own fixes, but this should settle quickly.
+// This is only here to protect against suggestions that never stop producing more suggestions.
+const MAX_ITERATIONS: usize = 10;
+
+pub struct FixResult {
+    pub source: String,
+    pub fixes_applied: usize,
+}
+
+/// Repeatedly applies every machine applicable suggestion until there are none left.
+/// Returns None if nothing could be fixed.
+pub fn fix_source(checker: &Checker<toml::value::Value>, source: &str) -> Option<FixResult> {
+    profiling::scope!("fix_source");
+
+    let mut source = source.to_owned();
+    let mut fixes_applied = 0;
+
+    for _ in 0..MAX_ITERATIONS {
+        let ast = match full_moon::parse(&source) {
+            Ok(ast) => ast,
+            Err(_) => break,
+        };
+
+        let diagnostics = checker.test_on(&ast);
+
+        let (new_source, applied) = apply_suggestions(
+            &source,
+            diagnostics
+                .iter()
+                .filter(|diagnostic| diagnostic.severity != Severity::Allow)
+                .flat_map(|diagnostic| &diagnostic.diagnostic.suggestions)
+                .filter(|suggestion| suggestion.applicability == Applicability::MachineApplicable),
+        );
+
+        if applied == 0 {
+            break;
+        }
+
+        // A suggestion should never produce code that doesn't parse, but if one does,
+        // keep what we had rather than break the user's code.
+        if full_moon::parse(&new_source).is_err() {
+            break;
+        }
+
+        source = new_source;
+        fixes_applied += applied;
+    }
+
+    if fixes_applied == 0 {
+        return None;
+    }
+
+    Some(FixResult {
+        source,
+        fixes_applied,
+    })
+}
+
+pub fn unified_diff(filename: &str, old: &str, new: &str) -> String {
+    similar::TextDiff::from_lines(old, new)
+        .unified_diff()
+        .header(&format!("a/{filename}"), &format!("b/{filename}"))
+        .to_string()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use selene_lib::{standard_library::StandardLibrary, CheckerConfig};
+
+    fn checker() -> Checker<toml::value::Value> {
+        Checker::new(
+            CheckerConfig::default(),
+            StandardLibrary::from_name("lua51").unwrap(),
+        )
+        .unwrap()
+    }
+
+    #[test]
+    fn test_fix_source() {
+        let result = fix_source(&checker(), "local x = {}\nprint(table.getn(x))\n")
+            .expect("nothing was fixed");
+
+        assert_eq!(result.source, "local x = {}\nprint(#x)\n");
+        assert_eq!(result.fixes_applied, 1);
+    }
+
+    #[test]
+    fn test_fix_keeps_precedence() {
+        let result =
+            fix_source(&checker(), "print(table.getn(a + b))\n").expect("nothing was fixed");
+
+        assert_eq!(result.source, "print(#(a + b))\n");
+    }
+
+    #[test]
+    fn test_fix_keeps_method_receiver() {
+        let standard_library: StandardLibrary = serde_yaml::from_str(
+            r#"
+            globals:
+              game:
+                struct: Game
+            structs:
+              Game:
+                connect:
+                  args:
+                    - type: function
+                  method: true
+                  deprecated:
+                    message: "use Connect instead"
+                    replace:
+                      - "Connect(%1)"
+            "#,
+        )
+        .unwrap();
+
+        let checker = Checker::new(CheckerConfig::default(), standard_library).unwrap();
+        let result =
+            fix_source(&checker, "game:connect(function() end)\n").expect("nothing was fixed");
+
+        assert_eq!(result.source, "game:Connect(function() end)\n");
+    }
+
+    #[test]
+    fn test_nothing_to_fix() {
+        assert!(fix_source(&checker(), "print(1)\n").is_none());
+    }
+
+    #[test]
+    fn test_maybe_incorrect_not_applied() {
+        // almost_swapped suggestions are not machine applicable
+        assert!(fix_source(&checker(), "local a, b = 1, 2\na = b\nb = a\n").is_none());
+    }
+
+    #[test]
+    fn test_unified_diff() {
+        assert_eq!(
+            unified_diff("code.lua", "print(table.getn(x))\n", "print(#x)\n"),
+            "--- a/code.lua\n+++ b/code.lua\n@@ -1 +1 @@\n-print(table.getn(x))\n+print(#x)\n"
+        );
+    }
+}
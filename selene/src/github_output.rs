@@ -0,0 +1,88 @@
+//! Output as GitHub Actions workflow commands, which show up as annotations on pull requests.
+//! https://docs.github.com/en/actions/using-workflows/workflow-commands-for-github-actions
+
+use codespan_reporting::diagnostic::{Diagnostic as CodespanDiagnostic, Severity};
+
+use crate::json_output::label_to_serializable;
+
+// Workflow commands are line based, so newlines (and the escape character itself) must be escaped.
+fn escape_data(data: &str) -> String {
+    data.replace('%', "%25")
+        .replace('\r', "%0D")
+        .replace('\n', "%0A")
+}
+
+// Properties are additionally separated by commas and ended by colons.
+fn escape_property(property: &str) -> String {
+    escape_data(property)
+        .replace(':', "%3A")
+        .replace(',', "%2C")
+}
+
+pub fn diagnostic_to_command(
+    diagnostic: &CodespanDiagnostic<codespan::FileId>,
+    files: &codespan::Files<&str>,
+) -> String {
+    let label = diagnostic.labels.first().expect("no labels passed");
+    let filename = files
+        .name(label.file_id)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let span = label_to_serializable(&filename, label, files).span;
+
+    let command = match diagnostic.severity {
+        Severity::Bug | Severity::Error => "error",
+        Severity::Warning => "warning",
+        Severity::Note | Severity::Help => "notice",
+    };
+
+    let code = diagnostic.code.as_deref().unwrap_or_default();
+
+    let mut message = format!("[{code}] {}", diagnostic.message);
+    for note in &diagnostic.notes {
+        message.push('\n');
+        message.push_str(note);
+    }
+
+    // GitHub's lines and columns start at 1
+    format!(
+        "::{command} file={},line={},col={},endLine={},endColumn={},title={}::{}",
+        escape_property(&filename),
+        span.start_line + 1,
+        span.start_column + 1,
+        span.end_line + 1,
+        span.end_column + 1,
+        escape_property(code),
+        escape_data(&message),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan_reporting::diagnostic::Label as CodespanLabel;
+
+    #[test]
+    fn test_github_command() {
+        let mut files = codespan::Files::new();
+        let file_id = files.add("src\\code.lua", "local x = 1\nprint(table.getn(x))\n");
+
+        assert_eq!(
+            diagnostic_to_command(
+                &CodespanDiagnostic::warning()
+                    .with_code("deprecated")
+                    .with_message("standard library function `table.getn` is deprecated")
+                    .with_labels(vec![CodespanLabel::primary(file_id, 18..31)])
+                    .with_notes(vec!["try: #x".to_owned()]),
+                &files,
+            ),
+            "::warning file=src/code.lua,line=2,col=7,endLine=2,endColumn=20,title=deprecated::[deprecated] standard library function `table.getn` is deprecated%0Atry: #x"
+        );
+    }
+
+    #[test]
+    fn test_escaping() {
+        assert_eq!(escape_data("100%\r\n"), "100%25%0D%0A");
+        assert_eq!(escape_property("a,b:c"), "a%2Cb%3Ac");
+    }
+}
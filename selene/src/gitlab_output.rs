@@ -0,0 +1,204 @@
+//! Output as a GitLab Code Quality report, which shows up in merge request widgets.
+//! Like SARIF, the report is a single document, so issues are collected over the run.
+//! https://docs.gitlab.com/ee/ci/testing/code_quality.html#implement-a-custom-tool
+
+use std::{collections::HashMap, sync::Mutex};
+
+use codespan_reporting::diagnostic::{Diagnostic as CodespanDiagnostic, Severity};
+use serde::Serialize;
+use sha2::{Digest, Sha256};
+
+use crate::json_output::label_to_serializable;
+
+lazy_static::lazy_static! {
+    static ref ISSUES: Mutex<Vec<PendingIssue>> = Mutex::new(Vec::new());
+}
+
+#[derive(Serialize)]
+pub struct CodeQualityIssue {
+    description: String,
+    check_name: String,
+    fingerprint: String,
+    severity: CodeQualitySeverity,
+    location: CodeQualityLocation,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "lowercase")]
+enum CodeQualitySeverity {
+    Info,
+    Minor,
+    Major,
+    Blocker,
+}
+
+#[derive(Serialize)]
+struct CodeQualityLocation {
+    path: String,
+    positions: CodeQualityPositions,
+}
+
+#[derive(Serialize)]
+struct CodeQualityPositions {
+    begin: CodeQualityPosition,
+    end: CodeQualityPosition,
+}
+
+#[derive(Serialize)]
+struct CodeQualityPosition {
+    line: usize,
+    column: usize,
+}
+
+struct PendingIssue {
+    issue: CodeQualityIssue,
+    // Fingerprints are based on what's being flagged rather than where, so that they
+    // survive unrelated lines being added or removed.
+    fingerprint_source: String,
+    start_byte: usize,
+}
+
+pub fn push_diagnostic(
+    diagnostic: &CodespanDiagnostic<codespan::FileId>,
+    files: &codespan::Files<&str>,
+) {
+    let label = diagnostic.labels.first().expect("no labels passed");
+    let path = files
+        .name(label.file_id)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let span = label_to_serializable(&path, label, files).span;
+
+    let check_name = diagnostic.code.to_owned().unwrap_or_default();
+
+    let flagged_source = files
+        .source(label.file_id)
+        .get(label.range.clone())
+        .unwrap_or_default();
+
+    let severity = if check_name == "parse_error" {
+        CodeQualitySeverity::Blocker
+    } else {
+        match diagnostic.severity {
+            Severity::Bug | Severity::Error => CodeQualitySeverity::Major,
+            Severity::Warning => CodeQualitySeverity::Minor,
+            Severity::Note | Severity::Help => CodeQualitySeverity::Info,
+        }
+    };
+
+    let mut description = diagnostic.message.to_owned();
+    for note in &diagnostic.notes {
+        description.push('\n');
+        description.push_str(note);
+    }
+
+    ISSUES.lock().unwrap().push(PendingIssue {
+        fingerprint_source: format!("{path}\0{check_name}\0{flagged_source}"),
+        start_byte: label.range.start,
+        issue: CodeQualityIssue {
+            description,
+            fingerprint: String::new(),
+            severity,
+            location: CodeQualityLocation {
+                path,
+                // GitLab's lines and columns start at 1
+                positions: CodeQualityPositions {
+                    begin: CodeQualityPosition {
+                        line: span.start_line + 1,
+                        column: span.start_column + 1,
+                    },
+                    end: CodeQualityPosition {
+                        line: span.end_line + 1,
+                        column: span.end_column + 1,
+                    },
+                },
+            },
+            check_name,
+        },
+    });
+}
+
+/// Builds the report out of everything pushed so far.
+pub fn take_report() -> Vec<CodeQualityIssue> {
+    let mut pending = std::mem::take(&mut *ISSUES.lock().unwrap());
+    pending.sort_by(|a, b| {
+        (&a.issue.location.path, a.start_byte).cmp(&(&b.issue.location.path, b.start_byte))
+    });
+
+    // GitLab requires fingerprints to be unique, so the same code flagged twice in the same file
+    // is told apart by the order it appears in.
+    let mut occurrences: HashMap<String, usize> = HashMap::new();
+
+    pending
+        .into_iter()
+        .map(|mut pending| {
+            let occurrence = occurrences
+                .entry(pending.fingerprint_source.clone())
+                .or_default();
+
+            let mut hasher = Sha256::new();
+            hasher.update(pending.fingerprint_source.as_bytes());
+            hasher.update(occurrence.to_string().as_bytes());
+            *occurrence += 1;
+
+            pending.issue.fingerprint = format!("{:x}", hasher.finalize());
+            pending.issue
+        })
+        .collect()
+}
+
+pub fn print_report() {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&take_report())
+            .expect("unable to serialize code quality output")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan_reporting::diagnostic::Label as CodespanLabel;
+
+    #[test]
+    fn test_code_quality_report() {
+        let mut files = codespan::Files::new();
+        let file_id = files.add("code.lua", "print(1 / 0)\nprint(1 / 0)\n");
+        let moved_file_id = files.add("moved.lua", "\n\nprint(1 / 0)\n");
+
+        for (file_id, range) in [(file_id, 19..24), (file_id, 6..11), (moved_file_id, 8..13)] {
+            push_diagnostic(
+                &CodespanDiagnostic::warning()
+                    .with_code("divide_by_zero")
+                    .with_message("dividing by zero is not allowed, use math.huge instead")
+                    .with_labels(vec![CodespanLabel::primary(file_id, range)]),
+                &files,
+            );
+        }
+
+        let report = serde_json::to_value(take_report()).unwrap();
+        let issues = report.as_array().unwrap();
+
+        assert_eq!(issues.len(), 3);
+        assert_eq!(issues[0]["check_name"], "divide_by_zero");
+        assert_eq!(issues[0]["severity"], "minor");
+        assert_eq!(issues[0]["location"]["path"], "code.lua");
+        assert_eq!(issues[0]["location"]["positions"]["begin"]["line"], 1);
+        assert_eq!(issues[1]["location"]["positions"]["begin"]["line"], 2);
+        assert_ne!(issues[0]["fingerprint"], issues[1]["fingerprint"]);
+
+        // Only the contents matter, not the line they're on
+        let mut files = codespan::Files::new();
+        let file_id = files.add("moved.lua", "print(1 / 0)\n");
+        push_diagnostic(
+            &CodespanDiagnostic::warning()
+                .with_code("divide_by_zero")
+                .with_message("dividing by zero is not allowed, use math.huge instead")
+                .with_labels(vec![CodespanLabel::primary(file_id, 6..11)]),
+            &files,
+        );
+
+        let moved_report = serde_json::to_value(take_report()).unwrap();
+        assert_eq!(moved_report[0]["fingerprint"], issues[2]["fingerprint"]);
+    }
+}
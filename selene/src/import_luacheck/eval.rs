@@ -0,0 +1,611 @@
+//! `.luacheckrc` is Lua, but almost every one only assigns literals to globals, such as
+//! `std = "lua51"` or `files["spec"] = { std = "+busted" }`. Rather than running it, the AST is
+//! evaluated directly, and anything other than assignments of literals is an error.
+
+use std::{collections::HashMap, fmt};
+
+use full_moon::{
+    ast::{self, Expression, Value},
+    node::Node,
+    tokenizer::{Symbol, TokenReference, TokenType},
+};
+
+#[derive(Clone, Debug, PartialEq)]
+pub enum LuaValue {
+    Nil,
+    Boolean(bool),
+    Number(f64),
+    String(String),
+    Table(LuaTable),
+}
+
+impl LuaValue {
+    pub fn as_str(&self) -> Option<&str> {
+        match self {
+            LuaValue::String(string) => Some(string),
+            _ => None,
+        }
+    }
+
+    pub fn as_table(&self) -> Option<&LuaTable> {
+        match self {
+            LuaValue::Table(table) => Some(table),
+            _ => None,
+        }
+    }
+
+    fn type_name(&self) -> &'static str {
+        match self {
+            LuaValue::Nil => "nil",
+            LuaValue::Boolean(_) => "boolean",
+            LuaValue::Number(_) => "number",
+            LuaValue::String(_) => "string",
+            LuaValue::Table(_) => "table",
+        }
+    }
+}
+
+/// A table with string keys, in the order they were assigned, along with its array part.
+#[derive(Clone, Debug, Default, PartialEq)]
+pub struct LuaTable {
+    pub array: Vec<LuaValue>,
+    pub fields: Vec<(String, LuaValue)>,
+}
+
+impl LuaTable {
+    pub fn get(&self, key: &str) -> Option<&LuaValue> {
+        self.fields
+            .iter()
+            .find(|(field, _)| field == key)
+            .map(|(_, value)| value)
+    }
+
+    fn set(&mut self, key: String, value: LuaValue) {
+        let index = self.fields.iter().position(|(field, _)| *field == key);
+
+        match (index, value) {
+            (Some(index), LuaValue::Nil) => {
+                self.fields.remove(index);
+            }
+
+            (Some(index), value) => self.fields[index].1 = value,
+            (None, LuaValue::Nil) => {}
+            (None, value) => self.fields.push((key, value)),
+        }
+    }
+
+    // Like luacheck's `files` and `stds`, tables are created as they are indexed into
+    fn table_mut(&mut self, key: &str) -> Option<&mut LuaTable> {
+        if self.get(key).is_none() {
+            self.set(key.to_owned(), LuaValue::Table(LuaTable::default()));
+        }
+
+        self.fields
+            .iter_mut()
+            .find_map(|(field, value)| match value {
+                LuaValue::Table(table) if field == key => Some(table),
+                _ => None,
+            })
+    }
+}
+
+#[derive(Debug)]
+pub enum EvalError {
+    Parse(String),
+    Unsupported { line: usize, message: String },
+}
+
+impl fmt::Display for EvalError {
+    fn fmt(&self, formatter: &mut fmt::Formatter) -> fmt::Result {
+        match self {
+            EvalError::Parse(error) => write!(formatter, "couldn't parse: {error}"),
+            EvalError::Unsupported { line, message } => {
+                write!(formatter, "line {line}: {message}")
+            }
+        }
+    }
+}
+
+impl std::error::Error for EvalError {}
+
+fn unsupported(node: &impl Node, message: impl Into<String>) -> EvalError {
+    EvalError::Unsupported {
+        line: node
+            .start_position()
+            .map(|position| position.line())
+            .unwrap_or_default(),
+        message: message.into(),
+    }
+}
+
+/// Evaluates a `.luacheckrc`, returning every global it sets.
+pub fn evaluate(source: &str) -> Result<LuaTable, EvalError> {
+    let ast = full_moon::parse(source).map_err(|error| EvalError::Parse(error.to_string()))?;
+
+    let mut evaluator = Evaluator::default();
+
+    for stmt in ast.nodes().stmts() {
+        evaluator.stmt(stmt)?;
+    }
+
+    if let Some(last_stmt) = ast.nodes().last_stmt() {
+        return Err(unsupported(
+            last_stmt,
+            "only assignments are supported, not `return` or `break`",
+        ));
+    }
+
+    Ok(evaluator.globals)
+}
+
+#[derive(Default)]
+struct Evaluator {
+    globals: LuaTable,
+    locals: HashMap<String, LuaValue>,
+}
+
+fn name_of(token: &TokenReference) -> String {
+    token.token().to_string()
+}
+
+impl Evaluator {
+    fn stmt(&mut self, stmt: &ast::Stmt) -> Result<(), EvalError> {
+        match stmt {
+            ast::Stmt::Assignment(assignment) => {
+                let values = self.expressions(assignment.expressions())?;
+
+                for (var, value) in assignment.variables().iter().zip(pad(values)) {
+                    self.assign(var, value)?;
+                }
+
+                Ok(())
+            }
+
+            ast::Stmt::LocalAssignment(local_assignment) => {
+                let values = self.expressions(local_assignment.expressions())?;
+
+                for (name, value) in local_assignment.names().iter().zip(pad(values)) {
+                    self.locals.insert(name_of(name), value);
+                }
+
+                Ok(())
+            }
+
+            _ => Err(unsupported(
+                stmt,
+                format!(
+                    "only assignments are supported, not `{}`",
+                    stmt.to_string().trim()
+                ),
+            )),
+        }
+    }
+
+    fn expressions<'a>(
+        &self,
+        expressions: impl IntoIterator<Item = &'a Expression>,
+    ) -> Result<Vec<LuaValue>, EvalError> {
+        expressions
+            .into_iter()
+            .map(|expression| self.expression(expression))
+            .collect()
+    }
+
+    fn assign(&mut self, var: &ast::Var, value: LuaValue) -> Result<(), EvalError> {
+        let var_expression = match var {
+            ast::Var::Name(name) => {
+                let name = name_of(name);
+
+                match self.locals.get_mut(&name) {
+                    Some(local) => *local = value,
+                    None => self.globals.set(name, value),
+                }
+
+                return Ok(());
+            }
+
+            ast::Var::Expression(var_expression) => var_expression,
+            _ => return Err(unsupported(var, "unsupported assignment")),
+        };
+
+        let ast::Prefix::Name(root) = var_expression.prefix() else {
+            return Err(unsupported(var, "only names can be indexed into"));
+        };
+
+        let mut keys = Vec::new();
+        for suffix in var_expression.suffixes() {
+            keys.push(self.key(suffix)?);
+        }
+
+        let root = name_of(root);
+        let mut table = match self.locals.get_mut(&root) {
+            Some(LuaValue::Table(table)) => table,
+            Some(local) => {
+                return Err(unsupported(
+                    var,
+                    format!("can't index into a {}", local.type_name()),
+                ))
+            }
+
+            None => self
+                .globals
+                .table_mut(&root)
+                .ok_or_else(|| unsupported(var, format!("`{root}` is not a table")))?,
+        };
+
+        let last_key = keys.pop().expect("var expressions always have suffixes");
+
+        for key in keys {
+            table = table
+                .table_mut(&key)
+                .ok_or_else(|| unsupported(var, format!("`{key}` is not a table")))?;
+        }
+
+        table.set(last_key, value);
+        Ok(())
+    }
+
+    fn key(&self, suffix: &ast::Suffix) -> Result<String, EvalError> {
+        match suffix {
+            ast::Suffix::Index(ast::Index::Dot { name, .. }) => Ok(name_of(name)),
+
+            ast::Suffix::Index(ast::Index::Brackets { expression, .. }) => {
+                match self.expression(expression)? {
+                    LuaValue::String(key) => Ok(key),
+                    other => Err(unsupported(
+                        suffix,
+                        format!(
+                            "only strings can be used as keys, not a {}",
+                            other.type_name()
+                        ),
+                    )),
+                }
+            }
+
+            _ => Err(unsupported(suffix, "function calls are not supported")),
+        }
+    }
+
+    fn read(&self, var: &ast::Var) -> Result<LuaValue, EvalError> {
+        let (root, suffixes) = match var {
+            ast::Var::Name(name) => (name, Vec::new()),
+            ast::Var::Expression(var_expression) => match var_expression.prefix() {
+                ast::Prefix::Name(name) => (name, var_expression.suffixes().collect()),
+                _ => return Err(unsupported(var, "only names can be indexed into")),
+            },
+            _ => return Err(unsupported(var, "unsupported variable")),
+        };
+
+        let root = name_of(root);
+        let mut value = match self.locals.get(&root) {
+            Some(local) => local.clone(),
+            None => self.globals.get(&root).cloned().unwrap_or(LuaValue::Nil),
+        };
+
+        for suffix in suffixes {
+            let key = self.key(suffix)?;
+
+            value = match value {
+                LuaValue::Table(table) => table.get(&key).cloned().unwrap_or(LuaValue::Nil),
+                other => {
+                    return Err(unsupported(
+                        suffix,
+                        format!("can't index into a {}", other.type_name()),
+                    ))
+                }
+            };
+        }
+
+        Ok(value)
+    }
+
+    fn expression(&self, expression: &Expression) -> Result<LuaValue, EvalError> {
+        match expression {
+            Expression::Parentheses { expression, .. } => self.expression(expression),
+
+            Expression::BinaryOperator {
+                lhs,
+                binop: ast::BinOp::TwoDots(_),
+                rhs,
+            } => match (self.expression(lhs)?, self.expression(rhs)?) {
+                (LuaValue::String(lhs), LuaValue::String(rhs)) => Ok(LuaValue::String(lhs + &rhs)),
+
+                _ => Err(unsupported(expression, "only strings can be concatenated")),
+            },
+
+            Expression::UnaryOperator {
+                unop: ast::UnOp::Minus(_),
+                expression: operand,
+            } => match self.expression(operand)? {
+                LuaValue::Number(number) => Ok(LuaValue::Number(-number)),
+                _ => Err(unsupported(expression, "only numbers can be negated")),
+            },
+
+            Expression::Value { value, .. } => self.value(value),
+
+            _ => Err(unsupported(
+                expression,
+                format!("`{}` can't be evaluated", expression.to_string().trim()),
+            )),
+        }
+    }
+
+    fn value(&self, value: &Value) -> Result<LuaValue, EvalError> {
+        match value {
+            Value::ParenthesesExpression(expression) => self.expression(expression),
+            Value::TableConstructor(table_constructor) => self.table(table_constructor),
+            Value::Var(var) => self.read(var),
+
+            Value::String(token) => match token.token_type() {
+                TokenType::StringLiteral {
+                    literal,
+                    multi_line,
+                    ..
+                } => Ok(LuaValue::String(if multi_line.is_some() {
+                    literal.to_string()
+                } else {
+                    unescape(literal).map_err(|message| unsupported(token, message))?
+                })),
+
+                _ => unreachable!("string value isn't a string literal"),
+            },
+
+            Value::Number(token) => match token.token_type() {
+                TokenType::Number { text } => text
+                    .parse()
+                    .map(LuaValue::Number)
+                    .map_err(|_| unsupported(value, format!("can't read the number `{text}`"))),
+
+                _ => unreachable!("number value isn't a number"),
+            },
+
+            Value::Symbol(token) => match token.token_type() {
+                TokenType::Symbol {
+                    symbol: Symbol::True,
+                } => Ok(LuaValue::Boolean(true)),
+
+                TokenType::Symbol {
+                    symbol: Symbol::False,
+                } => Ok(LuaValue::Boolean(false)),
+
+                _ => Ok(LuaValue::Nil),
+            },
+
+            _ => Err(unsupported(
+                value,
+                format!("`{}` can't be evaluated", value.to_string().trim()),
+            )),
+        }
+    }
+
+    fn table(&self, table_constructor: &ast::TableConstructor) -> Result<LuaValue, EvalError> {
+        let mut table = LuaTable::default();
+
+        for field in table_constructor.fields() {
+            match field {
+                ast::Field::NameKey { key, value, .. } => {
+                    table.set(name_of(key), self.expression(value)?);
+                }
+
+                ast::Field::ExpressionKey { key, value, .. } => match self.expression(key)? {
+                    LuaValue::String(key) => table.set(key, self.expression(value)?),
+                    other => {
+                        return Err(unsupported(
+                            field,
+                            format!(
+                                "only strings can be used as keys, not a {}",
+                                other.type_name()
+                            ),
+                        ))
+                    }
+                },
+
+                ast::Field::NoKey(value) => table.array.push(self.expression(value)?),
+
+                _ => return Err(unsupported(field, "unsupported table field")),
+            }
+        }
+
+        Ok(LuaValue::Table(table))
+    }
+}
+
+// Assignments with more variables than values set the rest to nil
+fn pad(values: Vec<LuaValue>) -> impl Iterator<Item = LuaValue> {
+    values.into_iter().chain(std::iter::repeat(LuaValue::Nil))
+}
+
+// Decodes escapes the way Lua 5.1 through 5.4 do. Lua strings are bytes, so escapes can make
+// something that isn't UTF-8, which can't be translated.
+fn unescape(literal: &str) -> Result<String, String> {
+    let mut unescaped = Vec::with_capacity(literal.len());
+    let mut characters = literal.chars().peekable();
+
+    let push = |unescaped: &mut Vec<u8>, character: char| {
+        unescaped.extend_from_slice(character.encode_utf8(&mut [0; 4]).as_bytes());
+    };
+
+    while let Some(character) = characters.next() {
+        if character != '\\' {
+            push(&mut unescaped, character);
+            continue;
+        }
+
+        let Some(escaped) = characters.next() else {
+            break;
+        };
+
+        match escaped {
+            'a' => unescaped.push(0x07),
+            'b' => unescaped.push(0x08),
+            'f' => unescaped.push(0x0c),
+            'n' => unescaped.push(b'\n'),
+            'r' => unescaped.push(b'\r'),
+            't' => unescaped.push(b'\t'),
+            'v' => unescaped.push(0x0b),
+
+            // An escaped line break is kept, with `\r\n` and `\n\r` counting as one
+            '\n' | '\r' => {
+                unescaped.push(b'\n');
+                characters.next_if(|&next| matches!((escaped, next), ('\n', '\r') | ('\r', '\n')));
+            }
+
+            'z' => while characters.next_if(char::is_ascii_whitespace).is_some() {},
+
+            'x' => {
+                let digits = (0..2)
+                    .map_while(|_| characters.next_if(char::is_ascii_hexdigit))
+                    .collect::<String>();
+
+                match u8::from_str_radix(&digits, 16) {
+                    Ok(byte) if digits.len() == 2 => unescaped.push(byte),
+                    _ => return Err("`\\x` needs two hexadecimal digits".to_owned()),
+                }
+            }
+
+            'u' => {
+                let code_point = characters
+                    .next_if_eq(&'{')
+                    .map(|_| {
+                        std::iter::from_fn(|| characters.next_if(char::is_ascii_hexdigit))
+                            .collect::<String>()
+                    })
+                    .filter(|_| characters.next_if_eq(&'}').is_some());
+
+                match code_point
+                    .as_deref()
+                    .and_then(|digits| u32::from_str_radix(digits, 16).ok())
+                    .and_then(char::from_u32)
+                {
+                    Some(character) => push(&mut unescaped, character),
+                    None => {
+                        return Err("`\\u` needs a valid code point, such as `\\u{e9}`".to_owned())
+                    }
+                }
+            }
+
+            '0'..='9' => {
+                let digits = std::iter::once(escaped)
+                    .chain((0..2).map_while(|_| characters.next_if(char::is_ascii_digit)))
+                    .collect::<String>();
+
+                match digits.parse::<u8>() {
+                    Ok(byte) => unescaped.push(byte),
+                    Err(_) => return Err(format!("`\\{digits}` is too large to be a byte")),
+                }
+            }
+
+            // Anything else is kept as it is, like Lua 5.1 does, which covers `\\`, `\"`, and `\'`
+            other => push(&mut unescaped, other),
+        }
+    }
+
+    String::from_utf8(unescaped).map_err(|_| {
+        "string escapes something that isn't UTF-8, which can't be translated".to_owned()
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evaluate() {
+        let globals = evaluate(
+            r#"
+            -- Comments are fine
+            local shared = { "describe", "it" }
+
+            std = "lua51" .. "+busted"
+            max_cyclomatic_complexity = -(-20)
+            globals = shared
+            ignore = { "212/self", '4.*' }
+            files["spec/"] = { read_globals = { foo = { fields = { "bar" } } } }
+            files["spec/"].std = "+busted"
+            stds.custom = { globals = { x = {} } }
+            allow_defined = true
+            cache = nil
+            "#,
+        )
+        .unwrap();
+
+        assert_eq!(
+            globals.get("std"),
+            Some(&LuaValue::String("lua51+busted".into()))
+        );
+        assert_eq!(
+            globals.get("max_cyclomatic_complexity"),
+            Some(&LuaValue::Number(20.0))
+        );
+        assert_eq!(
+            globals
+                .get("globals")
+                .and_then(LuaValue::as_table)
+                .unwrap()
+                .array,
+            vec![
+                LuaValue::String("describe".into()),
+                LuaValue::String("it".into())
+            ]
+        );
+        assert_eq!(globals.get("allow_defined"), Some(&LuaValue::Boolean(true)));
+        assert_eq!(globals.get("cache"), None);
+
+        let spec = globals
+            .get("files")
+            .and_then(LuaValue::as_table)
+            .and_then(|files| files.get("spec/"))
+            .and_then(LuaValue::as_table)
+            .unwrap();
+
+        assert_eq!(spec.get("std"), Some(&LuaValue::String("+busted".into())));
+        assert!(spec.get("read_globals").is_some());
+
+        assert!(globals
+            .get("stds")
+            .and_then(LuaValue::as_table)
+            .and_then(|stds| stds.get("custom"))
+            .is_some());
+    }
+
+    #[test]
+    fn test_unsupported() {
+        assert!(matches!(
+            evaluate("std = 'lua51'\nif x then end"),
+            Err(EvalError::Unsupported { line: 2, .. })
+        ));
+
+        assert!(matches!(
+            evaluate("globals = { os.getenv('X') }"),
+            Err(EvalError::Unsupported { line: 1, .. })
+        ));
+
+        assert!(matches!(evaluate("std = "), Err(EvalError::Parse(_))));
+    }
+    #[test]
+    fn test_unescape() {
+        assert_eq!(
+            unescape(r#"a\tb\r\n\\ \"q\" \'s\' \a\b\f\v"#).unwrap(),
+            "a\tb\r\n\\ \"q\" 's' \x07\x08\x0c\x0b"
+        );
+
+        assert_eq!(unescape("line\\\r\nbreak").unwrap(), "line\nbreak");
+        assert_eq!(unescape("skip\\z  \n\t  this").unwrap(), "skipthis");
+
+        assert_eq!(unescape(r"\65\066\0671").unwrap(), "ABC1");
+        assert_eq!(unescape(r"\x41\x6a").unwrap(), "Aj");
+        assert_eq!(unescape(r"caf\u{E9} \u{1F600}").unwrap(), "café 😀");
+        assert_eq!(unescape(r"caf\195\169").unwrap(), "café");
+
+        assert!(unescape(r"\256").is_err());
+        assert!(unescape(r"\x4").is_err());
+        assert!(unescape(r"\u{D800}").is_err());
+        assert!(unescape(r"\u{41").is_err());
+        assert!(unescape(r"\255").is_err());
+
+        assert!(matches!(
+            evaluate(r#"std = "\xff""#),
+            Err(EvalError::Unsupported { line: 1, .. })
+        ));
+    }
+}
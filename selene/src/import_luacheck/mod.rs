@@ -0,0 +1,586 @@
+//! `selene import-luacheck`, which translates a `.luacheckrc` into a selene.toml. Globals become
+//! a standard library next to it, ignored warnings become allowed lints, and `files[...]`
+//! becomes `[[overrides]]`. Anything that can't be translated is reported rather than dropped
+//! silently.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    path::{Path, PathBuf},
+};
+
+use selene_lib::{
+    luacheck::{code_matches, lints_matching, LINT_WARNINGS},
+    standard_library::{Field, FieldKind, PropertyWritability, StandardLibrary},
+};
+
+use crate::config::CONFIG_FILE_NAME;
+
+pub mod eval;
+
+use eval::{LuaTable, LuaValue};
+
+const STD_NAME: &str = "luacheck";
+
+// Options that only change how luacheck runs or lints that selene doesn't have
+const IGNORED_OPTIONS: &[&str] = &[
+    "cache",
+    "codes",
+    "color",
+    "formatter",
+    "jobs",
+    "max_code_line_length",
+    "max_comment_line_length",
+    "max_line_length",
+    "max_string_line_length",
+    "quiet",
+    "ranges",
+    "self",
+    "unused_secondaries",
+];
+
+// Globals from luacheck's `busted` standard library, which is used in most projects with tests
+const BUSTED_GLOBALS: &[&str] = &[
+    "after_each",
+    "assert",
+    "before_each",
+    "context",
+    "describe",
+    "expose",
+    "finally",
+    "insulate",
+    "it",
+    "lazy_setup",
+    "lazy_teardown",
+    "mock",
+    "pending",
+    "randomize",
+    "setup",
+    "spec",
+    "spy",
+    "strict_setup",
+    "strict_teardown",
+    "stub",
+    "teardown",
+    "test",
+];
+
+/// Everything that is written out for a `.luacheckrc`.
+pub struct Imported {
+    pub config: toml::Table,
+    /// Standard libraries to write next to the config, by name.
+    pub standard_libraries: Vec<(String, StandardLibrary)>,
+    /// Everything that couldn't be translated.
+    pub warnings: Vec<String>,
+}
+
+// The parts of a set of luacheck options that selene has an equivalent for
+#[derive(Default)]
+struct Options {
+    lua_version: Option<&'static str>,
+    globals: BTreeMap<String, Field>,
+    lints: toml::Table,
+    config: toml::Table,
+}
+
+struct Translator<'a> {
+    stds: Option<&'a LuaTable>,
+    warnings: Vec<String>,
+}
+
+fn lua_version(std: &str) -> Option<&'static str> {
+    match std {
+        "lua51" | "luajit" | "min" => Some("lua51"),
+        "lua52" | "lua52c" => Some("lua52"),
+        "lua53" | "lua53c" | "lua54" | "lua54c" | "max" => Some("lua53"),
+        _ => None,
+    }
+}
+
+/// The version of Lua a `.luacheckrc` uses, from its `std`.
+pub fn lua_version_of(luacheckrc: &LuaTable) -> Option<&'static str> {
+    let std = luacheckrc.get("std")?.as_str()?;
+    lua_version(std.split('+').next()?.trim())
+}
+
+fn property(read_only: bool) -> Field {
+    Field::from_field_kind(FieldKind::Property(if read_only {
+        PropertyWritability::ReadOnly
+    } else {
+        PropertyWritability::FullWrite
+    }))
+}
+
+impl Translator<'_> {
+    fn warn(&mut self, context: &str, message: String) {
+        self.warnings.push(format!("{context}{message}"));
+    }
+
+    // A global definition, such as `{ fields = { "bar" }, read_only = false }`
+    fn global_definition(
+        &mut self,
+        path: String,
+        definition: &LuaValue,
+        read_only: bool,
+        globals: &mut BTreeMap<String, Field>,
+    ) {
+        let Some(definition) = definition.as_table() else {
+            globals.insert(path, property(read_only));
+            return;
+        };
+
+        let read_only = match definition.get("read_only") {
+            Some(LuaValue::Boolean(read_only)) => *read_only,
+            _ => read_only,
+        };
+
+        if definition.get("other_fields") == Some(&LuaValue::Boolean(true)) {
+            globals.insert(path, Field::from_field_kind(FieldKind::Any));
+            return;
+        }
+
+        match definition.get("fields").and_then(LuaValue::as_table) {
+            Some(fields) if !fields.array.is_empty() || !fields.fields.is_empty() => {
+                self.globals(fields, read_only, Some(&path), globals);
+            }
+
+            _ => {
+                globals.insert(path, property(read_only));
+            }
+        }
+    }
+
+    // A list of globals, such as `{ "foo", bar = { fields = { "baz" } } }`
+    fn globals(
+        &mut self,
+        list: &LuaTable,
+        read_only: bool,
+        prefix: Option<&str>,
+        globals: &mut BTreeMap<String, Field>,
+    ) {
+        let path = |name: &str| match prefix {
+            Some(prefix) => format!("{prefix}.{name}"),
+            None => name.to_owned(),
+        };
+
+        for name in &list.array {
+            match name.as_str() {
+                Some(name) => {
+                    globals.insert(path(name), property(read_only));
+                }
+
+                None => self.warn("", "globals must be strings or definitions".to_owned()),
+            }
+        }
+
+        for (name, definition) in &list.fields {
+            self.global_definition(path(name), definition, read_only, globals);
+        }
+    }
+
+    // A standard library definition, from `stds` or `std = { ... }`
+    fn std_definition(&mut self, definition: &LuaTable, options: &mut Options) {
+        if let Some(globals) = definition.get("globals").and_then(LuaValue::as_table) {
+            self.globals(globals, false, None, &mut options.globals);
+        }
+
+        if let Some(read_globals) = definition.get("read_globals").and_then(LuaValue::as_table) {
+            self.globals(read_globals, true, None, &mut options.globals);
+        }
+    }
+
+    fn std(&mut self, context: &str, std: &LuaValue, options: &mut Options) {
+        let std = match std {
+            LuaValue::String(std) => std,
+            LuaValue::Table(definition) => return self.std_definition(definition, options),
+            _ => return self.warn(context, "`std` must be a string or a table".to_owned()),
+        };
+
+        // `+` adds to the standard library instead of replacing it
+        for name in std
+            .split('+')
+            .map(str::trim)
+            .filter(|name| !name.is_empty())
+        {
+            if let Some(version) = lua_version(name) {
+                options.lua_version = Some(version);
+            } else if name == "busted" {
+                for global in BUSTED_GLOBALS {
+                    options
+                        .globals
+                        .insert((*global).to_owned(), Field::from_field_kind(FieldKind::Any));
+                }
+            } else if let Some(definition) = self
+                .stds
+                .and_then(|stds| stds.get(name))
+                .and_then(LuaValue::as_table)
+            {
+                self.std_definition(definition, options);
+            } else if name != "none" {
+                self.warn(
+                    context,
+                    format!("the standard library `{name}` has no equivalent in selene"),
+                );
+            }
+        }
+    }
+
+    fn ignore(&mut self, context: &str, patterns: &[String], options: &mut Options) {
+        let mut code_patterns = Vec::new();
+
+        for pattern in patterns {
+            match pattern.split_once('/') {
+                Some((code, _)) if lints_matching(code).next().is_some() => {
+                    self.warn(
+                        context,
+                        format!("`{pattern}` only ignores some names, which selene can't do"),
+                    );
+                }
+
+                Some(_) => {}
+                None => code_patterns.push(pattern.as_str()),
+            }
+        }
+
+        // A lint is only allowed if every warning it covers is ignored
+        for (lint, codes) in LINT_WARNINGS {
+            let ignored = codes
+                .iter()
+                .filter(|code| {
+                    code_patterns
+                        .iter()
+                        .any(|pattern| code_matches(pattern, code))
+                })
+                .count();
+
+            if ignored == codes.len() {
+                options
+                    .lints
+                    .insert((*lint).to_owned(), toml::Value::String("allow".to_owned()));
+            } else if ignored > 0 {
+                self.warn(
+                    context,
+                    format!(
+                        "only some of the warnings `{lint}` covers are ignored, so it is still enabled"
+                    ),
+                );
+            }
+        }
+    }
+
+    fn options(&mut self, context: &str, table: &LuaTable, top_level: bool) -> Options {
+        let mut options = Options::default();
+        let mut ignored = Vec::new();
+
+        for (option, value) in &table.fields {
+            match (option.as_str(), value) {
+                ("std", std) => self.std(context, std, &mut options),
+
+                ("globals" | "new_globals", LuaValue::Table(globals)) => {
+                    self.globals(globals, false, None, &mut options.globals);
+                }
+
+                ("read_globals" | "new_read_globals", LuaValue::Table(read_globals)) => {
+                    self.globals(read_globals, true, None, &mut options.globals);
+                }
+
+                ("ignore", LuaValue::Table(patterns)) => {
+                    ignored.extend(
+                        patterns
+                            .array
+                            .iter()
+                            .filter_map(LuaValue::as_str)
+                            .map(ToOwned::to_owned),
+                    );
+                }
+
+                ("global", LuaValue::Boolean(false)) => ignored.push("1".to_owned()),
+                ("unused", LuaValue::Boolean(false)) => ignored.push("2".to_owned()),
+                ("redefined", LuaValue::Boolean(false)) => ignored.push("4".to_owned()),
+                ("unused_args", LuaValue::Boolean(false)) => {
+                    ignored.extend(["212".to_owned(), "213".to_owned()]);
+                }
+
+                ("global" | "unused" | "redefined" | "unused_args", LuaValue::Boolean(true)) => {}
+
+                ("max_cyclomatic_complexity", LuaValue::Number(maximum)) => {
+                    options.lints.insert(
+                        "high_cyclomatic_complexity".to_owned(),
+                        toml::Value::String("warn".to_owned()),
+                    );
+
+                    options.config.insert(
+                        "high_cyclomatic_complexity".to_owned(),
+                        toml::Value::Table(toml::Table::from_iter([(
+                            "maximum_complexity".to_owned(),
+                            toml::Value::Integer(*maximum as i64),
+                        )])),
+                    );
+                }
+
+                ("exclude_files" | "files" | "stds", _) if top_level => {}
+
+                (option, _) if IGNORED_OPTIONS.contains(&option) => {}
+
+                (option, _) => self.warn(
+                    context,
+                    format!("`{option}` has no equivalent in selene and was left out"),
+                ),
+            }
+        }
+
+        self.ignore(context, &ignored, &mut options);
+        options
+    }
+}
+
+// luacheck treats paths without any glob characters as directories or files
+fn files_glob(pattern: &str) -> String {
+    if pattern.contains(['*', '?', '[']) || pattern.ends_with(".lua") {
+        pattern.to_owned()
+    } else {
+        format!("{}/**", pattern.trim_end_matches('/'))
+    }
+}
+
+fn standard_library(base: Option<&str>, globals: BTreeMap<String, Field>) -> StandardLibrary {
+    let mut standard_library = StandardLibrary::default();
+    standard_library.base = base.map(ToOwned::to_owned);
+    standard_library.globals = globals;
+    standard_library
+}
+
+/// Translates an evaluated `.luacheckrc`.
+pub fn translate(luacheckrc: &LuaTable) -> Imported {
+    let mut translator = Translator {
+        stds: luacheckrc.get("stds").and_then(LuaValue::as_table),
+        warnings: Vec::new(),
+    };
+
+    let options = translator.options("", luacheckrc, true);
+
+    let mut config = toml::Table::new();
+    let mut standard_libraries = Vec::new();
+
+    let lua_version = options.lua_version.unwrap_or("lua51");
+
+    // Code written for luacheck is likely to have `-- luacheck:` comments too
+    config.insert("luacheck-directives".to_owned(), toml::Value::Boolean(true));
+
+    if options.globals.is_empty() {
+        config.insert(
+            "std".to_owned(),
+            toml::Value::String(lua_version.to_owned()),
+        );
+    } else {
+        config.insert("std".to_owned(), toml::Value::String(STD_NAME.to_owned()));
+        standard_libraries.push((
+            STD_NAME.to_owned(),
+            standard_library(Some(lua_version), options.globals),
+        ));
+    }
+
+    if let Some(exclude_files) = luacheckrc.get("exclude_files").and_then(LuaValue::as_table) {
+        config.insert(
+            "exclude".to_owned(),
+            toml::Value::Array(
+                exclude_files
+                    .array
+                    .iter()
+                    .filter_map(LuaValue::as_str)
+                    .map(|pattern| toml::Value::String(pattern.to_owned()))
+                    .collect(),
+            ),
+        );
+    }
+
+    if !options.lints.is_empty() {
+        config.insert("lints".to_owned(), toml::Value::Table(options.lints));
+    }
+
+    if !options.config.is_empty() {
+        config.insert("config".to_owned(), toml::Value::Table(options.config));
+    }
+
+    let mut overrides = Vec::new();
+
+    for (pattern, value) in luacheckrc
+        .get("files")
+        .and_then(LuaValue::as_table)
+        .map(|files| files.fields.as_slice())
+        .unwrap_or_default()
+    {
+        let Some(table) = value.as_table() else {
+            continue;
+        };
+
+        let context = format!("files[\"{pattern}\"]: ");
+        let options = translator.options(&context, table, false);
+
+        if options
+            .lua_version
+            .is_some_and(|version| version != lua_version)
+        {
+            translator.warn(
+                &context,
+                "selene can't use a different version of Lua for some files".to_owned(),
+            );
+        }
+
+        let mut config_override = toml::Table::new();
+        config_override.insert(
+            "files".to_owned(),
+            toml::Value::Array(vec![toml::Value::String(files_glob(pattern))]),
+        );
+
+        if !options.globals.is_empty() {
+            let name = format!("{STD_NAME}_files_{}", overrides.len() + 1);
+            config_override.insert("std".to_owned(), toml::Value::String(name.clone()));
+            standard_libraries.push((name, standard_library(None, options.globals)));
+        }
+
+        if !options.lints.is_empty() {
+            config_override.insert("lints".to_owned(), toml::Value::Table(options.lints));
+        }
+
+        if !options.config.is_empty() {
+            config_override.insert("config".to_owned(), toml::Value::Table(options.config));
+        }
+
+        // Only `files` was set, so there's nothing to override
+        if config_override.len() > 1 {
+            overrides.push(toml::Value::Table(config_override));
+        }
+    }
+
+    if !overrides.is_empty() {
+        config.insert("overrides".to_owned(), toml::Value::Array(overrides));
+    }
+
+    Imported {
+        config,
+        standard_libraries,
+        warnings: translator.warnings,
+    }
+}
+
+/// Translates a `.luacheckrc`, writing selene.toml and any standard libraries next to it.
+pub fn import_luacheck(path: &Path, force: bool) -> Result<(), String> {
+    let contents = fs::read_to_string(path)
+        .map_err(|error| format!("couldn't read {}: {error}", path.display()))?;
+
+    let luacheckrc = eval::evaluate(&contents)
+        .map_err(|error| format!("couldn't evaluate {}: {error}", path.display()))?;
+
+    let imported = translate(&luacheckrc);
+
+    let directory = path.parent().unwrap_or_else(|| Path::new(""));
+
+    let mut outputs: Vec<(PathBuf, String)> = vec![(
+        directory.join(CONFIG_FILE_NAME),
+        format!(
+            "# Imported from {} by `selene import-luacheck`\n{}",
+            path.file_name().unwrap_or_default().to_string_lossy(),
+            toml::to_string(&imported.config).expect("couldn't serialize config")
+        ),
+    )];
+
+    for (name, standard_library) in &imported.standard_libraries {
+        outputs.push((
+            directory.join(format!("{name}.yml")),
+            serde_yaml::to_string(standard_library).expect("couldn't serialize standard library"),
+        ));
+    }
+
+    if !force {
+        if let Some((existing, _)) = outputs.iter().find(|(path, _)| path.exists()) {
+            return Err(format!(
+                "{} already exists, pass --force to replace it",
+                existing.display()
+            ));
+        }
+    }
+
+    for (path, contents) in outputs {
+        fs::write(&path, contents)
+            .map_err(|error| format!("couldn't write {}: {error}", path.display()))?;
+
+        println!("Created {}", path.display());
+    }
+
+    for warning in &imported.warnings {
+        println!("Not translated: {warning}");
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use selene_lib::CheckerConfig;
+
+    fn imported(source: &str) -> Imported {
+        translate(&eval::evaluate(source).unwrap())
+    }
+
+    #[test]
+    fn test_translate() {
+        let imported = imported(
+            r#"
+            std = "lua53+custom"
+            stds.custom = { read_globals = { love = { fields = { "graphics", "audio" } } } }
+            globals = { "GAME_STATE" }
+            ignore = { "4", "212", "21/_.*" }
+            max_cyclomatic_complexity = 15
+            max_line_length = 120
+            allow_defined = true
+            exclude_files = { "vendor/**" }
+
+            files["spec"] = { std = "+busted", ignore = { "113" } }
+            files["spec"].ignore = { "11" }
+            files["bin/*.lua"] = { max_line_length = false }
+            "#,
+        );
+
+        let config: CheckerConfig<toml::Value> =
+            toml::Value::Table(imported.config).try_into().unwrap();
+
+        assert_eq!(config.std(), STD_NAME);
+        assert!(config.luacheck_directives);
+        assert_eq!(config.exclude, vec!["vendor/**".to_owned()]);
+        assert!(config.lints.contains_key("shadowing"));
+        assert!(!config.lints.contains_key("unused_variable"));
+        assert!(config.config.contains_key("high_cyclomatic_complexity"));
+
+        assert_eq!(config.overrides.len(), 1);
+        assert_eq!(config.overrides[0].files, vec!["spec/**".to_owned()]);
+        assert_eq!(config.overrides[0].std.as_deref(), Some("luacheck_files_1"));
+        assert!(config.overrides[0].lints.contains_key("undefined_variable"));
+        assert!(config.overrides[0].lints.contains_key("unscoped_variables"));
+
+        let (name, std) = &imported.standard_libraries[0];
+        assert_eq!(name, STD_NAME);
+        assert_eq!(std.base.as_deref(), Some("lua53"));
+        assert!(std.globals.contains_key("love.graphics"));
+        assert!(std.globals.contains_key("GAME_STATE"));
+
+        let (_, busted) = &imported.standard_libraries[1];
+        assert!(busted.globals.contains_key("describe"));
+
+        assert_eq!(imported.warnings.len(), 3, "{:?}", imported.warnings);
+        assert!(imported
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("21/_.*")));
+        assert!(imported
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("unused_variable")));
+        assert!(imported
+            .warnings
+            .iter()
+            .any(|warning| warning.contains("allow_defined")));
+    }
+}
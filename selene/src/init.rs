@@ -0,0 +1,243 @@
+//! `selene init`, which writes a starter selene.toml. The standard library is guessed from the
+//! files in the project, and every lint that can be configured is listed with its defaults,
+//! so that they can be found and changed without looking through the documentation.
+
+use std::{fmt::Write, fs, path::Path};
+
+use crate::{config::CONFIG_FILE_NAME, import_luacheck};
+
+/// A standard library guessed from the files in a project.
+#[derive(Debug, PartialEq, Eq)]
+pub struct DetectedStd {
+    pub std: &'static str,
+    /// Why this standard library was picked, if it wasn't the fallback
+    pub reason: Option<String>,
+}
+
+// Roblox projects need the roblox feature, and are otherwise closest to plain Luau
+const ROBLOX_STD: &str = if cfg!(feature = "roblox") {
+    "roblox"
+} else {
+    "luau"
+};
+
+fn any_file_matches(directory: &Path, pattern: &str) -> Option<String> {
+    let pattern = format!(
+        "{}/{pattern}",
+        glob::Pattern::escape(&directory.to_string_lossy())
+    );
+
+    glob::glob(&pattern)
+        .ok()?
+        .flatten()
+        .find(|path| path.is_file())
+        .map(|path| {
+            path.strip_prefix(directory)
+                .unwrap_or(&path)
+                .display()
+                .to_string()
+        })
+}
+
+// Finds the version of Lua a rockspec depends on, such as `"lua >= 5.3"`
+fn std_from_rockspec(contents: &str) -> Option<&'static str> {
+    contents.split(['"', '\'']).find_map(|dependency| {
+        let version = dependency.strip_prefix("lua")?.trim_start();
+        let version = version.trim_start_matches(['>', '<', '=', '~', ' ']);
+        let minor = version.strip_prefix("5.")?.chars().next()?.to_digit(10)?;
+
+        Some(match minor {
+            0 | 1 => "lua51",
+            2 => "lua52",
+            _ => "lua53",
+        })
+    })
+}
+
+/// Guesses the standard library of the project in a directory.
+pub fn detect_std(directory: &Path) -> DetectedStd {
+    if let Some(project) = any_file_matches(directory, "*.project.json") {
+        return DetectedStd {
+            std: ROBLOX_STD,
+            reason: Some(format!("found Rojo project `{project}`")),
+        };
+    }
+
+    if let Some(file) = any_file_matches(directory, "**/*.luau") {
+        return DetectedStd {
+            std: "luau",
+            reason: Some(format!("found Luau file `{file}`")),
+        };
+    }
+
+    if let Ok(luacheckrc) = fs::read_to_string(directory.join(".luacheckrc")) {
+        if let Some(std) = import_luacheck::eval::evaluate(&luacheckrc)
+            .ok()
+            .as_ref()
+            .and_then(import_luacheck::lua_version_of)
+        {
+            return DetectedStd {
+                std,
+                reason: Some("from `std` in `.luacheckrc`".to_owned()),
+            };
+        }
+    }
+
+    if let Some(rockspec) = any_file_matches(directory, "*.rockspec") {
+        if let Some(std) = fs::read_to_string(directory.join(&rockspec))
+            .ok()
+            .as_deref()
+            .and_then(std_from_rockspec)
+        {
+            return DetectedStd {
+                std,
+                reason: Some(format!("from the Lua version `{rockspec}` depends on")),
+            };
+        }
+    }
+
+    DetectedStd {
+        std: "lua51",
+        reason: None,
+    }
+}
+
+/// Creates the contents of a starter selene.toml.
+pub fn generate_config(std: &str, pin_roblox_std: bool) -> String {
+    let mut config = format!("std = {}\n", toml::Value::String(std.to_owned()));
+
+    if pin_roblox_std {
+        config.push_str("roblox-std-source = \"pinned\"\n");
+    }
+
+    config.push_str("\n# The default configuration of every lint that can be configured\n");
+
+    for lint in selene_lib::all_lints() {
+        let serde_json::Value::Object(fields) = lint.default_config() else {
+            continue;
+        };
+
+        write!(config, "\n[config.{}]\n", lint.name).unwrap();
+
+        for (field, default) in fields {
+            match toml::Value::try_from(&default) {
+                Ok(default) => writeln!(config, "{field} = {default}").unwrap(),
+                Err(_) => writeln!(config, "# {field} is not set by default").unwrap(),
+            }
+        }
+    }
+
+    config
+}
+
+/// Writes a starter selene.toml into a directory.
+pub fn init(
+    directory: &Path,
+    std: Option<&str>,
+    pin_roblox_std: bool,
+    force: bool,
+) -> Result<(), String> {
+    let config_path = directory.join(CONFIG_FILE_NAME);
+    if config_path.exists() && !force {
+        return Err(format!(
+            "{CONFIG_FILE_NAME} already exists, pass --force to replace it"
+        ));
+    }
+
+    let std = match std {
+        Some(std) => std.to_owned(),
+        None => {
+            let detected = detect_std(directory);
+
+            match &detected.reason {
+                Some(reason) => println!("Using std `{}`, {reason}", detected.std),
+                None => println!(
+                    "Using std `{}`, since nothing else was detected",
+                    detected.std
+                ),
+            }
+
+            detected.std.to_owned()
+        }
+    };
+
+    if pin_roblox_std && !std.split('+').any(|name| name == "roblox") {
+        return Err(format!(
+            "--pin-roblox-std only applies to the roblox standard library, but std is `{std}`"
+        ));
+    }
+
+    fs::write(&config_path, generate_config(&std, pin_roblox_std))
+        .map_err(|error| format!("couldn't write {CONFIG_FILE_NAME}: {error}"))?;
+
+    println!("Created {CONFIG_FILE_NAME}");
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use selene_lib::{standard_library::StandardLibrary, Checker, CheckerConfig};
+
+    fn temp_directory(name: &str) -> std::path::PathBuf {
+        let directory =
+            std::env::temp_dir().join(format!("selene-init-{name}-{}", std::process::id()));
+        fs::create_dir_all(directory.join("src")).unwrap();
+        directory
+    }
+
+    #[test]
+    fn test_detect_std() {
+        let directory = temp_directory("detect");
+        assert_eq!(detect_std(&directory).std, "lua51");
+
+        fs::write(
+            directory.join("project-1.0-1.rockspec"),
+            "dependencies = {\n\t\"lua >= 5.2, < 5.5\",\n\t\"penlight\",\n}\n",
+        )
+        .unwrap();
+        assert_eq!(detect_std(&directory).std, "lua52");
+
+        fs::write(
+            directory.join(".luacheckrc"),
+            "std = \"lua53+busted\"\nglobals = { \"foo\" }\n",
+        )
+        .unwrap();
+        assert_eq!(detect_std(&directory).std, "lua53");
+
+        fs::write(directory.join("src").join("init.luau"), "return nil\n").unwrap();
+        assert_eq!(detect_std(&directory).std, "luau");
+
+        fs::write(directory.join("default.project.json"), "{}").unwrap();
+        assert_eq!(detect_std(&directory).std, ROBLOX_STD);
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_generated_config_is_valid() {
+        let config: CheckerConfig<toml::value::Value> =
+            toml::from_str(&generate_config("lua53", true)).unwrap();
+
+        assert_eq!(config.std(), "lua53");
+        assert!(config.config.contains_key("unused_variable"));
+
+        Checker::new(config, StandardLibrary::from_name("lua53").unwrap()).unwrap();
+    }
+
+    #[test]
+    fn test_init_does_not_replace_config() {
+        let directory = temp_directory("replace");
+        fs::write(directory.join(CONFIG_FILE_NAME), "std = \"roblox\"\n").unwrap();
+
+        assert!(init(&directory, Some("lua51"), false, false).is_err());
+        assert_eq!(
+            fs::read_to_string(directory.join(CONFIG_FILE_NAME)).unwrap(),
+            "std = \"roblox\"\n"
+        );
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+}
@@ -3,16 +3,24 @@ use std::io::{self, Write};
 use codespan_reporting::diagnostic::{
     Diagnostic as CodespanDiagnostic, Label as CodespanLabel, LabelStyle, Severity,
 };
+use selene_lib::{
+    lints::{Applicability, LintType, Severity as LintSeverity, Suggestion},
+    LintVariation,
+};
 use serde::Serialize;
 use termcolor::StandardStream;
 
 #[derive(Serialize)]
 #[serde(tag = "type")]
 pub enum JsonOutput {
+    BaselineUnmatched(JsonBaselineUnmatched),
     Capabilities(serde_json::Value),
     Diagnostic(JsonDiagnostic),
+    Explanation(JsonExplanation),
     InvalidConfig(crate::validate_config::InvalidConfigError),
+    Lint(JsonLint),
     Summary(JsonSummary),
+    Suppression(JsonSuppression),
 }
 
 #[derive(Serialize)]
@@ -22,6 +30,52 @@ pub struct JsonSummary {
     parse_errors: usize,
 }
 
+#[derive(Serialize)]
+pub struct JsonBaselineUnmatched {
+    pub file: String,
+    pub code: String,
+    pub count: usize,
+}
+
+/// A lint, from `selene list-lints`.
+#[derive(Serialize)]
+pub struct JsonLint {
+    pub name: &'static str,
+    /// The severity of the lint when it is not configured by the user
+    pub severity: LintSeverity,
+    pub category: LintType,
+    /// Whether the current config enables the lint
+    pub enabled: bool,
+    /// The default configuration of the lint, `null` if it can't be configured
+    pub config: serde_json::Value,
+}
+
+/// The documentation of a lint, from `selene --explain`.
+#[derive(Serialize)]
+pub struct JsonExplanation {
+    pub name: &'static str,
+    pub severity: LintSeverity,
+    pub category: LintType,
+    pub documentation: Option<&'static str>,
+}
+
+/// A `-- selene:` filter, from `selene suppressions`.
+#[derive(Serialize)]
+pub struct JsonSuppression {
+    pub file: String,
+    pub lint: String,
+    pub variation: LintVariation,
+    pub global: bool,
+    pub next_line: bool,
+    pub reason: Option<String>,
+    /// Whether the lint is one the file's checker runs
+    pub known: bool,
+    /// Where the comment is
+    pub comment: Span,
+    /// The code the filter applies to
+    pub range: Span,
+}
+
 #[derive(Serialize)]
 pub struct JsonDiagnostic {
     severity: Severity,
@@ -30,52 +84,131 @@ pub struct JsonDiagnostic {
     primary_label: Label,
     notes: Vec<String>,
     secondary_labels: Vec<Label>,
+    suggestions: Vec<JsonSuggestion>,
+
+    // Left out of the legacy format, which extensions still read
+    #[serde(skip_serializing_if = "Option::is_none")]
+    category: Option<LintType>,
+}
+
+impl JsonDiagnostic {
+    /// Adds the category of the lint that created the diagnostic, if it came from one.
+    pub fn with_category(mut self, category: Option<LintType>) -> Self {
+        self.category = category;
+        self
+    }
 }
 
 #[derive(Serialize)]
-struct Label {
-    filename: String,
-    span: Span,
+struct JsonSuggestion {
     message: String,
+    applicability: JsonApplicability,
+    edits: Vec<JsonEdit>,
 }
 
 #[derive(Serialize)]
-struct Span {
-    start: usize,
-    start_line: usize,
-    start_column: usize,
-    end: usize,
-    end_line: usize,
-    end_column: usize,
+#[serde(rename_all = "snake_case")]
+enum JsonApplicability {
+    MachineApplicable,
+    MaybeIncorrect,
+    HasPlaceholders,
+    Unspecified,
 }
 
-fn label_to_serializable(
-    filename: &str,
-    label: &CodespanLabel<codespan::FileId>,
+impl From<Applicability> for JsonApplicability {
+    fn from(applicability: Applicability) -> Self {
+        match applicability {
+            Applicability::MachineApplicable => JsonApplicability::MachineApplicable,
+            Applicability::MaybeIncorrect => JsonApplicability::MaybeIncorrect,
+            Applicability::HasPlaceholders => JsonApplicability::HasPlaceholders,
+            Applicability::Unspecified => JsonApplicability::Unspecified,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct JsonEdit {
+    span: Span,
+    replacement: String,
+}
+
+#[derive(Serialize)]
+pub struct Label {
+    pub filename: String,
+    pub span: Span,
+    pub message: String,
+}
+
+/// Lines and columns start at 0.
+#[derive(Serialize)]
+pub struct Span {
+    pub start: usize,
+    pub start_line: usize,
+    pub start_column: usize,
+    pub end: usize,
+    pub end_line: usize,
+    pub end_column: usize,
+}
+
+pub fn range_to_span(
+    file_id: codespan::FileId,
+    range: (usize, usize),
     files: &codespan::Files<&str>,
-) -> Label {
+) -> Span {
     let start_location = files
-        .location(label.file_id, label.range.start as u32)
+        .location(file_id, range.0 as u32)
         .expect("unable to determine start location for label");
     let end_location = files
-        .location(label.file_id, label.range.end as u32)
+        .location(file_id, range.1 as u32)
         .expect("unable to determine end location for label");
+    Span {
+        start: range.0,
+        start_line: start_location.line.into(),
+        start_column: start_location.column.into(),
+        end: range.1,
+        end_line: end_location.line.into(),
+        end_column: end_location.column.into(),
+    }
+}
+
+pub fn label_to_serializable(
+    filename: &str,
+    label: &CodespanLabel<codespan::FileId>,
+    files: &codespan::Files<&str>,
+) -> Label {
     Label {
         filename: filename.to_string(),
         message: label.message.to_owned(),
-        span: Span {
-            start: label.range.start,
-            start_line: start_location.line.into(),
-            start_column: start_location.column.into(),
-            end: label.range.end,
-            end_line: end_location.line.into(),
-            end_column: end_location.column.into(),
-        },
+        span: range_to_span(label.file_id, (label.range.start, label.range.end), files),
+    }
+}
+
+fn suggestion_to_serializable(
+    file_id: codespan::FileId,
+    suggestion: &Suggestion,
+    files: &codespan::Files<&str>,
+) -> JsonSuggestion {
+    JsonSuggestion {
+        message: suggestion.message.to_owned(),
+        applicability: suggestion.applicability.into(),
+        edits: suggestion
+            .edits
+            .iter()
+            .map(|edit| JsonEdit {
+                span: range_to_span(
+                    file_id,
+                    (edit.range.0 as usize, edit.range.1 as usize),
+                    files,
+                ),
+                replacement: edit.replacement.to_owned(),
+            })
+            .collect(),
     }
 }
 
 pub fn diagnostic_to_json(
     diagnostic: &CodespanDiagnostic<codespan::FileId>,
+    suggestions: &[Suggestion],
     files: &codespan::Files<&str>,
 ) -> JsonDiagnostic {
     let label = diagnostic.labels.first().expect("no labels passed");
@@ -93,6 +226,11 @@ pub fn diagnostic_to_json(
             .filter(|label| label.style == LabelStyle::Secondary)
             .map(|label| label_to_serializable(&filename, label, files))
             .collect(),
+        suggestions: suggestions
+            .iter()
+            .map(|suggestion| suggestion_to_serializable(label.file_id, suggestion, files))
+            .collect(),
+        category: None,
     }
 }
 
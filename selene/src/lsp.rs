@@ -0,0 +1,868 @@
+//! A language server, so that editors can show diagnostics as code is written without
+//! spawning selene for every change.
+//! https://microsoft.github.io/language-server-protocol/
+//!
+//! Documents are sent in full on every change, and checkers are only created again when
+//! a `selene.toml` or a standard library changes.
+
+use std::{collections::HashMap, error::Error, path::PathBuf, sync::Arc};
+
+use lsp_server::{Connection, ErrorCode, Message, Notification, Request, RequestId, Response};
+use lsp_types::{
+    notification::{self, Notification as _},
+    request::{self, Request as _},
+    CodeAction, CodeActionKind, CodeActionOrCommand, CodeActionParams,
+    CodeActionProviderCapability, DiagnosticRelatedInformation, DiagnosticSeverity, DiagnosticTag,
+    DidChangeWatchedFilesRegistrationOptions, FileSystemWatcher, GlobPattern, Hover, HoverContents,
+    HoverParams, HoverProviderCapability, InitializeParams, InitializeResult, Location,
+    LogMessageParams, MarkupContent, MarkupKind, MessageType, NumberOrString, Position,
+    PublishDiagnosticsParams, Range, Registration, RegistrationParams, ServerCapabilities,
+    ServerInfo, ShowMessageParams, TextDocumentSyncCapability, TextDocumentSyncKind, TextEdit, Url,
+    WorkspaceEdit,
+};
+use selene_lib::{
+    lints::{Applicability, Severity},
+    standard_library::{Field, FieldKind, PropertyWritability, Required},
+    CheckerDiagnostic,
+};
+use serde::de::DeserializeOwned;
+
+use crate::checkers::{Checkers, ConfiguredChecker};
+
+type LspResult<T> = Result<T, Box<dyn Error + Send + Sync>>;
+
+// Converts between byte offsets, which selene uses, and positions, which LSP measures in
+// UTF-16 code units.
+struct LineIndex<'a> {
+    text: &'a str,
+    line_starts: Vec<usize>,
+}
+
+impl<'a> LineIndex<'a> {
+    fn new(text: &'a str) -> Self {
+        let mut line_starts = vec![0];
+        line_starts.extend(text.match_indices('\n').map(|(index, _)| index + 1));
+
+        Self { text, line_starts }
+    }
+
+    fn position(&self, offset: usize) -> Position {
+        let offset = offset.min(self.text.len());
+        let line = self.line_starts.partition_point(|start| *start <= offset) - 1;
+        let line_start = self.line_starts[line];
+
+        Position::new(
+            line as u32,
+            self.text
+                .get(line_start..offset)
+                .map_or(0, |before| before.encode_utf16().count()) as u32,
+        )
+    }
+
+    fn range(&self, (start, end): (u32, u32)) -> Range {
+        Range::new(self.position(start as usize), self.position(end as usize))
+    }
+
+    fn offset(&self, position: Position) -> usize {
+        let Some(&line_start) = self.line_starts.get(position.line as usize) else {
+            return self.text.len();
+        };
+
+        let mut offset = line_start;
+        let mut character = 0;
+
+        for current in self.text[line_start..].chars() {
+            if character >= position.character as usize || current == '\n' {
+                break;
+            }
+
+            character += current.len_utf16();
+            offset += current.len_utf8();
+        }
+
+        offset
+    }
+
+    fn line_start(&self, offset: usize) -> usize {
+        self.line_starts[self.line_starts.partition_point(|start| *start <= offset) - 1]
+    }
+}
+
+struct Document {
+    text: String,
+    version: i32,
+    checker: Option<Arc<ConfiguredChecker>>,
+    diagnostics: Vec<CheckerDiagnostic>,
+}
+
+struct Server {
+    connection: Connection,
+    root: PathBuf,
+    checkers: Checkers,
+    documents: HashMap<Url, Document>,
+}
+
+fn invalid_params(id: RequestId, error: serde_json::Error) -> Response {
+    Response::new_err(id, ErrorCode::InvalidParams as i32, error.to_string())
+}
+
+fn to_lsp_diagnostic(
+    uri: &Url,
+    index: &LineIndex,
+    checker_diagnostic: &CheckerDiagnostic,
+) -> Option<lsp_types::Diagnostic> {
+    let diagnostic = &checker_diagnostic.diagnostic;
+
+    let severity = match checker_diagnostic.severity {
+        Severity::Allow => return None,
+        Severity::Error => DiagnosticSeverity::ERROR,
+        Severity::Warning => DiagnosticSeverity::WARNING,
+    };
+
+    let mut message = diagnostic.message.to_owned();
+    for extra in diagnostic
+        .primary_label
+        .message
+        .iter()
+        .chain(&diagnostic.notes)
+    {
+        message.push('\n');
+        message.push_str(extra);
+    }
+
+    let related_information = diagnostic
+        .secondary_labels
+        .iter()
+        .map(|label| DiagnosticRelatedInformation {
+            location: Location::new(uri.clone(), index.range(label.range)),
+            message: label.message.clone().unwrap_or_default(),
+        })
+        .collect::<Vec<_>>();
+
+    Some(lsp_types::Diagnostic {
+        range: index.range(diagnostic.primary_label.range),
+        severity: Some(severity),
+        code: Some(NumberOrString::String(diagnostic.code.to_owned())),
+        code_description: Url::parse(&format!(
+            "https://kampfkarren.github.io/selene/lints/{}.html",
+            diagnostic.code
+        ))
+        .ok()
+        .map(|href| lsp_types::CodeDescription { href }),
+        source: Some("selene".to_owned()),
+        message,
+        related_information: if related_information.is_empty() {
+            None
+        } else {
+            Some(related_information)
+        },
+        tags: match diagnostic.code {
+            "deprecated" => Some(vec![DiagnosticTag::DEPRECATED]),
+            "unused_variable" => Some(vec![DiagnosticTag::UNNECESSARY]),
+            _ => None,
+        },
+        data: None,
+    })
+}
+
+fn parse_error_to_lsp_diagnostic(
+    index: &LineIndex,
+    error: full_moon::Error,
+) -> lsp_types::Diagnostic {
+    let (message, range) = match error {
+        full_moon::Error::AstError(full_moon::ast::AstError::UnexpectedToken {
+            token,
+            additional,
+        }) => {
+            let mut message = format!("unexpected token `{token}`");
+            if let Some(additional) = additional {
+                message.push('\n');
+                message.push_str(&additional);
+            }
+
+            (
+                message,
+                (
+                    token.start_position().bytes() as u32,
+                    token.end_position().bytes() as u32,
+                ),
+            )
+        }
+
+        full_moon::Error::TokenizerError(error) => {
+            let position = error.position().bytes() as u32;
+            (error.error().to_string(), (position, position))
+        }
+
+        error => (error.to_string(), (0, 0)),
+    };
+
+    lsp_types::Diagnostic {
+        range: index.range(range),
+        severity: Some(DiagnosticSeverity::ERROR),
+        code: Some(NumberOrString::String("parse_error".to_owned())),
+        source: Some("selene".to_owned()),
+        message,
+        ..Default::default()
+    }
+}
+
+// Finds the dotted name under the cursor, such as `string.format`, up to the name being hovered.
+fn name_path_at(text: &str, offset: usize) -> Option<(Vec<&str>, (usize, usize))> {
+    let bytes = text.as_bytes();
+    let is_name = |byte: u8| byte.is_ascii_alphanumeric() || byte == b'_';
+
+    let mut end = offset;
+    while end < bytes.len() && is_name(bytes[end]) {
+        end += 1;
+    }
+
+    let mut start = offset.min(end);
+    while start > 0 && (is_name(bytes[start - 1]) || matches!(bytes[start - 1], b'.' | b':')) {
+        start -= 1;
+    }
+
+    let names = text[start..end].split(['.', ':']).collect::<Vec<_>>();
+
+    if names
+        .iter()
+        .any(|name| name.is_empty() || name.starts_with(|c: char| c.is_ascii_digit()))
+    {
+        return None;
+    }
+
+    Some((names, (start, end)))
+}
+
+fn describe_field(names: &[&str], field: &Field) -> Option<String> {
+    let name = names.join(".");
+
+    let mut description = match &field.field_kind {
+        FieldKind::Any => format!("```lua\n{name}: any\n```"),
+
+        FieldKind::Function(function_behavior) => {
+            let name = match (function_behavior.method, names.split_last()) {
+                (true, Some((last, rest))) if !rest.is_empty() => {
+                    format!("{}:{last}", rest.join("."))
+                }
+                _ => name,
+            };
+
+            let arguments = function_behavior
+                .arguments
+                .iter()
+                .map(|argument| match argument.required {
+                    Required::NotRequired => format!("{}?", argument.argument_type),
+                    Required::Required(_) => argument.argument_type.to_string(),
+                })
+                .collect::<Vec<_>>()
+                .join(", ");
+
+            let mut description = format!("```lua\nfunction {name}({arguments})\n```");
+            if function_behavior.must_use {
+                description.push_str("\n\nThe return value must be used.");
+            }
+
+            description
+        }
+
+        FieldKind::Property(writability) => format!(
+            "```lua\n{name}\n```\n\n{}",
+            match writability {
+                PropertyWritability::ReadOnly => "Read-only.",
+                PropertyWritability::NewFields => "New fields can be added.",
+                PropertyWritability::OverrideFields => "Can be overwritten.",
+                PropertyWritability::FullWrite =>
+                    "Can be overwritten, and new fields can be added.",
+            }
+        ),
+
+        FieldKind::Struct(struct_name) => format!("```lua\n{name}: {struct_name}\n```"),
+
+        FieldKind::Removed => return None,
+    };
+
+    if let Some(deprecated) = &field.deprecated {
+        description.push_str("\n\n**Deprecated:** ");
+        description.push_str(&deprecated.message);
+    }
+
+    Some(description)
+}
+
+impl Server {
+    fn send_notification<N: notification::Notification>(&self, params: N::Params) -> LspResult<()> {
+        self.connection
+            .sender
+            .send(Message::Notification(Notification::new(
+                N::METHOD.to_owned(),
+                params,
+            )))?;
+
+        Ok(())
+    }
+
+    fn register_watchers(&self) -> LspResult<()> {
+        let options = DidChangeWatchedFilesRegistrationOptions {
+            watchers: vec![FileSystemWatcher {
+                glob_pattern: GlobPattern::String("**/*.{toml,yml,yaml}".to_owned()),
+                kind: None,
+            }],
+        };
+
+        self.connection.sender.send(Message::Request(Request::new(
+            RequestId::from("selene/watchConfig".to_owned()),
+            request::RegisterCapability::METHOD.to_owned(),
+            RegistrationParams {
+                registrations: vec![Registration {
+                    id: "selene/watchConfig".to_owned(),
+                    method: notification::DidChangeWatchedFiles::METHOD.to_owned(),
+                    register_options: Some(serde_json::to_value(options)?),
+                }],
+            },
+        )))?;
+
+        Ok(())
+    }
+
+    fn show_errors(&self, errors: Vec<String>) -> LspResult<()> {
+        for error in errors {
+            self.send_notification::<notification::ShowMessage>(ShowMessageParams {
+                typ: MessageType::ERROR,
+                message: format!("selene: {error}"),
+            })?;
+        }
+
+        Ok(())
+    }
+
+    fn reload(&mut self) -> LspResult<()> {
+        let mut checkers = Checkers::new(None, false);
+        for uri in self.documents.keys() {
+            checkers.for_file(&self.path_of(uri));
+        }
+
+        self.show_errors(checkers.take_errors())?;
+
+        // The last working checkers are kept, so a typo in a config doesn't clear everything
+        if !checkers.has_failed() {
+            self.checkers = checkers;
+        }
+
+        let uris = self.documents.keys().cloned().collect::<Vec<_>>();
+        for uri in uris {
+            self.lint(&uri)?;
+        }
+
+        Ok(())
+    }
+
+    // Documents that haven't been saved yet use the config at the root of the workspace
+    fn path_of(&self, uri: &Url) -> PathBuf {
+        uri.to_file_path()
+            .unwrap_or_else(|_| self.root.join("untitled.lua"))
+    }
+
+    fn lint(&mut self, uri: &Url) -> LspResult<()> {
+        if !self.documents.contains_key(uri) {
+            return Ok(());
+        }
+
+        let path = self.path_of(uri);
+        let checker = self.checkers.for_file(&path);
+
+        let errors = self.checkers.take_errors();
+        self.show_errors(errors)?;
+
+        let Some(document) = self.documents.get_mut(uri) else {
+            return Ok(());
+        };
+
+        let index = LineIndex::new(&document.text);
+        document.checker = checker.clone();
+        document.diagnostics.clear();
+
+        let mut lsp_diagnostics = Vec::new();
+
+        if let Some(checker) = checker.filter(|checker| !checker.is_excluded(&path)) {
+            match full_moon::parse(&document.text) {
+                Ok(ast) => {
+                    document.diagnostics = checker.checker.test_on(&ast);
+                    document
+                        .diagnostics
+                        .sort_by_key(|diagnostic| diagnostic.diagnostic.start_position());
+
+                    lsp_diagnostics.extend(
+                        document
+                            .diagnostics
+                            .iter()
+                            .filter_map(|diagnostic| to_lsp_diagnostic(uri, &index, diagnostic)),
+                    );
+                }
+
+                Err(error) => lsp_diagnostics.push(parse_error_to_lsp_diagnostic(&index, error)),
+            }
+        }
+
+        let params =
+            PublishDiagnosticsParams::new(uri.clone(), lsp_diagnostics, Some(document.version));
+
+        self.send_notification::<notification::PublishDiagnostics>(params)
+    }
+
+    fn code_actions(&self, params: CodeActionParams) -> Vec<CodeActionOrCommand> {
+        let uri = params.text_document.uri;
+
+        let Some(document) = self.documents.get(&uri) else {
+            return Vec::new();
+        };
+
+        let index = LineIndex::new(&document.text);
+        let (start, end) = (
+            index.offset(params.range.start),
+            index.offset(params.range.end),
+        );
+
+        let newline = if document.text.contains("\r\n") {
+            "\r\n"
+        } else {
+            "\n"
+        };
+
+        let action = |title: String,
+                      diagnostic: &lsp_types::Diagnostic,
+                      edits: Vec<TextEdit>,
+                      is_preferred: bool| {
+            CodeActionOrCommand::CodeAction(CodeAction {
+                title,
+                kind: Some(CodeActionKind::QUICKFIX),
+                diagnostics: Some(vec![diagnostic.clone()]),
+                edit: Some(WorkspaceEdit::new(HashMap::from([(uri.clone(), edits)]))),
+                is_preferred: Some(is_preferred),
+                ..Default::default()
+            })
+        };
+
+        let mut actions = Vec::new();
+
+        for checker_diagnostic in &document.diagnostics {
+            let diagnostic = &checker_diagnostic.diagnostic;
+            let (diagnostic_start, diagnostic_end) = diagnostic.primary_label.range;
+
+            if diagnostic_start as usize > end || (diagnostic_end as usize) < start {
+                continue;
+            }
+
+            let Some(lsp_diagnostic) = to_lsp_diagnostic(&uri, &index, checker_diagnostic) else {
+                continue;
+            };
+
+            for suggestion in &diagnostic.suggestions {
+                let edits = suggestion
+                    .edits
+                    .iter()
+                    .map(|edit| TextEdit::new(index.range(edit.range), edit.replacement.clone()))
+                    .collect();
+
+                actions.push(action(
+                    suggestion.message.clone(),
+                    &lsp_diagnostic,
+                    edits,
+                    suggestion.applicability == Applicability::MachineApplicable,
+                ));
+            }
+
+            // Filters apply to the node after them, so the comment goes above the flagged line
+            let line_start = index.line_start(diagnostic_start as usize);
+            let indentation = document.text[line_start..]
+                .chars()
+                .take_while(|character| *character == ' ' || *character == '\t')
+                .collect::<String>();
+
+            actions.push(action(
+                format!("Allow `{}` here", diagnostic.code),
+                &lsp_diagnostic,
+                vec![TextEdit::new(
+                    Range::new(index.position(line_start), index.position(line_start)),
+                    format!(
+                        "{indentation}-- selene: allow({}){newline}",
+                        diagnostic.code
+                    ),
+                )],
+                false,
+            ));
+
+            actions.push(action(
+                format!("Allow `{}` in this file", diagnostic.code),
+                &lsp_diagnostic,
+                vec![TextEdit::new(
+                    Range::new(Position::new(0, 0), Position::new(0, 0)),
+                    format!("--# selene: allow({}){newline}", diagnostic.code),
+                )],
+                false,
+            ));
+        }
+
+        actions
+    }
+
+    fn hover(&self, params: HoverParams) -> Option<Hover> {
+        let position = params.text_document_position_params;
+        let document = self.documents.get(&position.text_document.uri)?;
+
+        let index = LineIndex::new(&document.text);
+        let (names, range) = name_path_at(&document.text, index.offset(position.position))?;
+        let field = document
+            .checker
+            .as_ref()?
+            .standard_library
+            .find_global(&names)?;
+
+        Some(Hover {
+            contents: HoverContents::Markup(MarkupContent {
+                kind: MarkupKind::Markdown,
+                value: describe_field(&names, field)?,
+            }),
+            range: Some(Range::new(index.position(range.0), index.position(range.1))),
+        })
+    }
+
+    fn handle_request(&mut self, request: Request) -> LspResult<()> {
+        let response = match request.method.as_str() {
+            request::CodeActionRequest::METHOD => match serde_json::from_value(request.params) {
+                Ok(params) => Response::new_ok(request.id, self.code_actions(params)),
+                Err(error) => invalid_params(request.id, error),
+            },
+
+            request::HoverRequest::METHOD => match serde_json::from_value(request.params) {
+                Ok(params) => Response::new_ok(request.id, self.hover(params)),
+                Err(error) => invalid_params(request.id, error),
+            },
+
+            _ => Response::new_err(
+                request.id,
+                ErrorCode::MethodNotFound as i32,
+                format!("unknown request `{}`", request.method),
+            ),
+        };
+
+        self.connection.sender.send(Message::Response(response))?;
+        Ok(())
+    }
+
+    // Notifications can't be replied to, so ones that can't be read are logged and skipped
+    fn notification_params<P: DeserializeOwned>(
+        &self,
+        notification: Notification,
+    ) -> LspResult<Option<P>> {
+        match serde_json::from_value(notification.params) {
+            Ok(params) => Ok(Some(params)),
+            Err(error) => {
+                self.send_notification::<notification::LogMessage>(LogMessageParams {
+                    typ: MessageType::ERROR,
+                    message: format!(
+                        "selene: invalid parameters for `{}`: {error}",
+                        notification.method
+                    ),
+                })?;
+
+                Ok(None)
+            }
+        }
+    }
+
+    fn handle_notification(&mut self, notification: Notification) -> LspResult<()> {
+        match notification.method.as_str() {
+            notification::DidOpenTextDocument::METHOD => {
+                let Some(params) =
+                    self.notification_params::<lsp_types::DidOpenTextDocumentParams>(notification)?
+                else {
+                    return Ok(());
+                };
+
+                let uri = params.text_document.uri;
+                self.documents.insert(
+                    uri.clone(),
+                    Document {
+                        text: params.text_document.text,
+                        version: params.text_document.version,
+                        checker: None,
+                        diagnostics: Vec::new(),
+                    },
+                );
+
+                self.lint(&uri)?;
+            }
+
+            notification::DidChangeTextDocument::METHOD => {
+                let Some(params) = self
+                    .notification_params::<lsp_types::DidChangeTextDocumentParams>(notification)?
+                else {
+                    return Ok(());
+                };
+
+                let uri = params.text_document.uri;
+                if let (Some(document), Some(change)) = (
+                    self.documents.get_mut(&uri),
+                    params.content_changes.into_iter().last(),
+                ) {
+                    document.text = change.text;
+                    document.version = params.text_document.version;
+                }
+
+                self.lint(&uri)?;
+            }
+
+            // Clients that can't watch files will at least tell us when the config is saved
+            notification::DidSaveTextDocument::METHOD => {
+                let Some(params) =
+                    self.notification_params::<lsp_types::DidSaveTextDocumentParams>(notification)?
+                else {
+                    return Ok(());
+                };
+
+                if let Ok(path) = params.text_document.uri.to_file_path() {
+                    if self.checkers.is_config_file(&path) {
+                        self.reload()?;
+                    }
+                }
+            }
+
+            notification::DidCloseTextDocument::METHOD => {
+                let Some(params) = self
+                    .notification_params::<lsp_types::DidCloseTextDocumentParams>(notification)?
+                else {
+                    return Ok(());
+                };
+
+                let uri = params.text_document.uri;
+                self.documents.remove(&uri);
+
+                self.send_notification::<notification::PublishDiagnostics>(
+                    PublishDiagnosticsParams::new(uri, Vec::new(), None),
+                )?;
+            }
+
+            notification::DidChangeWatchedFiles::METHOD => {
+                let Some(params) = self
+                    .notification_params::<lsp_types::DidChangeWatchedFilesParams>(notification)?
+                else {
+                    return Ok(());
+                };
+
+                if params.changes.iter().any(|change| {
+                    matches!(
+                        change.uri.to_file_path(),
+                        Ok(path) if self.checkers.is_config_file(&path)
+                    )
+                }) {
+                    self.reload()?;
+                }
+            }
+
+            _ => {}
+        }
+
+        Ok(())
+    }
+
+    fn main_loop(&mut self) -> LspResult<()> {
+        let receiver = self.connection.receiver.clone();
+
+        for message in &receiver {
+            match message {
+                Message::Request(request) => {
+                    if self.connection.handle_shutdown(&request)? {
+                        return Ok(());
+                    }
+
+                    self.handle_request(request)?;
+                }
+
+                Message::Notification(notification) => self.handle_notification(notification)?,
+
+                // The only requests we send are registrations, which don't need handling
+                Message::Response(_) => {}
+            }
+        }
+
+        Ok(())
+    }
+}
+
+fn server_capabilities() -> ServerCapabilities {
+    ServerCapabilities {
+        text_document_sync: Some(TextDocumentSyncCapability::Kind(TextDocumentSyncKind::FULL)),
+        code_action_provider: Some(CodeActionProviderCapability::Simple(true)),
+        hover_provider: Some(HoverProviderCapability::Simple(true)),
+        ..Default::default()
+    }
+}
+
+/// Runs the language server over stdin and stdout until the client shuts it down.
+pub fn run() -> LspResult<()> {
+    let (connection, io_threads) = Connection::stdio();
+
+    let (initialize_id, initialize_params) = connection.initialize_start()?;
+    let initialize_params: InitializeParams = serde_json::from_value(initialize_params)?;
+
+    connection.initialize_finish(
+        initialize_id,
+        serde_json::to_value(InitializeResult {
+            capabilities: server_capabilities(),
+            server_info: Some(ServerInfo {
+                name: "selene".to_owned(),
+                version: Some(env!("CARGO_PKG_VERSION").to_owned()),
+            }),
+        })?,
+    )?;
+
+    #[allow(deprecated)]
+    let root = initialize_params
+        .workspace_folders
+        .as_ref()
+        .and_then(|folders| folders.first())
+        .map(|folder| &folder.uri)
+        .or(initialize_params.root_uri.as_ref())
+        .and_then(|uri| uri.to_file_path().ok())
+        .unwrap_or_else(|| std::env::current_dir().unwrap());
+
+    // Standard libraries are looked for in the current directory, just like running selene there
+    std::env::set_current_dir(&root)?;
+
+    let can_watch_files = initialize_params
+        .capabilities
+        .workspace
+        .and_then(|workspace| workspace.did_change_watched_files)
+        .and_then(|did_change_watched_files| did_change_watched_files.dynamic_registration)
+        .unwrap_or(false);
+
+    let mut server = Server {
+        connection,
+        root,
+        checkers: Checkers::new(None, false),
+        documents: HashMap::new(),
+    };
+
+    if can_watch_files {
+        server.register_watchers()?;
+    }
+
+    server.reload()?;
+    server.main_loop()?;
+
+    // The connection has to be closed before the IO threads can finish
+    drop(server);
+    io_threads.join()?;
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use selene_lib::standard_library::StandardLibrary;
+
+    #[test]
+    fn test_line_index() {
+        let text = "local a = 1\nlocal 😀 = \"é\"\nprint(a)";
+        let index = LineIndex::new(text);
+
+        assert_eq!(index.position(0), Position::new(0, 0));
+        assert_eq!(index.position(12), Position::new(1, 0));
+
+        // Emojis are two UTF-16 code units, but four bytes
+        let after_emoji = text.find(" = \"é").unwrap();
+        assert_eq!(index.position(after_emoji), Position::new(1, 8));
+        assert_eq!(index.offset(Position::new(1, 8)), after_emoji);
+
+        assert_eq!(index.offset(Position::new(2, 100)), text.len());
+        assert_eq!(index.offset(Position::new(5, 0)), text.len());
+        assert_eq!(index.line_start(text.len()), text.rfind('\n').unwrap() + 1);
+    }
+
+    #[test]
+    fn test_name_path_at() {
+        let text = "print(string.format(x)) local y = a:b";
+
+        assert_eq!(
+            name_path_at(text, text.find("format").unwrap() + 2),
+            Some((vec!["string", "format"], (6, 19)))
+        );
+
+        assert_eq!(
+            name_path_at(text, text.find("string").unwrap()),
+            Some((vec!["string"], (6, 12)))
+        );
+
+        assert_eq!(
+            name_path_at(text, text.len()),
+            Some((vec!["a", "b"], (text.len() - 3, text.len())))
+        );
+
+        assert_eq!(name_path_at("x = ..", 5), None);
+        assert_eq!(name_path_at("x = 1.5", 6), None);
+    }
+
+    #[test]
+    fn test_describe_field() {
+        let standard_library = StandardLibrary::from_name("lua51").unwrap();
+
+        let format = standard_library.find_global(&["string", "format"]).unwrap();
+        assert!(describe_field(&["string", "format"], format)
+            .unwrap()
+            .starts_with("```lua\nfunction string.format(string, ...)"));
+
+        let getn = standard_library.find_global(&["table", "getn"]).unwrap();
+        assert!(describe_field(&["table", "getn"], getn)
+            .unwrap()
+            .contains("**Deprecated:**"));
+    }
+    #[test]
+    fn test_invalid_params() {
+        let (connection, client) = Connection::memory();
+        let mut server = Server {
+            connection,
+            root: std::env::temp_dir(),
+            checkers: Checkers::new(None, false),
+            documents: HashMap::new(),
+        };
+
+        server
+            .handle_request(Request::new(
+                RequestId::from(1),
+                request::HoverRequest::METHOD.to_owned(),
+                serde_json::json!({ "position": "nowhere" }),
+            ))
+            .unwrap();
+
+        match client.receiver.try_recv() {
+            Ok(Message::Response(response)) => {
+                assert_eq!(response.id, RequestId::from(1));
+                assert_eq!(
+                    response.error.unwrap().code,
+                    ErrorCode::InvalidParams as i32
+                );
+            }
+
+            other => panic!("expected a response, got {other:?}"),
+        }
+
+        server
+            .handle_notification(Notification::new(
+                notification::DidOpenTextDocument::METHOD.to_owned(),
+                serde_json::json!({ "textDocument": 5 }),
+            ))
+            .unwrap();
+
+        match client.receiver.try_recv() {
+            Ok(Message::Notification(notification)) => {
+                assert_eq!(notification.method, notification::LogMessage::METHOD);
+            }
+
+            other => panic!("expected a log message, got {other:?}"),
+        }
+
+        assert!(server.documents.is_empty());
+    }
+}
@@ -15,7 +15,10 @@ use codespan_reporting::{
     },
     term::DisplayStyle as CodespanDisplayStyle,
 };
-use selene_lib::{lints::Severity, *};
+use selene_lib::{
+    lints::{LintType, Severity, Suggestion},
+    *,
+};
 use structopt::{clap, StructOpt};
 use termcolor::{Color, ColorChoice, ColorSpec, StandardStream, WriteColor};
 use threadpool::ThreadPool;
@@ -24,16 +27,38 @@ use upgrade_std::upgrade_std;
 #[cfg(feature = "roblox")]
 use selene_lib::standard_library::StandardLibrary;
 
-use crate::{json_output::log_total_json, opts::DisplayStyle};
+use crate::{
+    baseline::{Baseline, BaselineKey},
+    changed::{ChangedFiles, ChangedSince},
+    checkers::{Checkers, ConfiguredChecker},
+    json_output::log_total_json,
+    opts::DisplayStyle,
+};
 
+mod baseline;
+mod cache;
 mod capabilities;
+mod changed;
+mod checkers;
+mod config;
+mod explain;
+mod fix;
+mod github_output;
+mod gitlab_output;
+mod import_luacheck;
+mod init;
 mod json_output;
+mod lsp;
 mod opts;
 #[cfg(feature = "roblox")]
 mod roblox;
+mod sarif_output;
 mod standard_library;
+mod suppressions;
 mod upgrade_std;
 mod validate_config;
+mod watch;
+mod xml_output;
 
 macro_rules! error {
     ($fmt:expr) => {
@@ -47,11 +72,14 @@ macro_rules! error {
 
 lazy_static::lazy_static! {
     static ref OPTIONS: RwLock<Option<opts::Options>> = RwLock::new(None);
+    static ref BASELINE: RwLock<Option<Baseline>> = RwLock::new(None);
+    static ref CHANGED_FILES: RwLock<Option<ChangedFiles>> = RwLock::new(None);
 }
 
 static LINT_ERRORS: AtomicUsize = AtomicUsize::new(0);
 static LINT_WARNINGS: AtomicUsize = AtomicUsize::new(0);
 static PARSE_ERRORS: AtomicUsize = AtomicUsize::new(0);
+static FIXES_APPLIED: AtomicUsize = AtomicUsize::new(0);
 
 fn get_color() -> ColorChoice {
     let lock = OPTIONS.read().unwrap();
@@ -95,6 +123,45 @@ fn log_total(parse_errors: usize, lint_errors: usize, lint_warnings: usize) -> i
     }
 }
 
+fn log_baseline_unmatched(unmatched: Vec<(BaselineKey, usize)>) {
+    let lock = OPTIONS.read().unwrap();
+    let opts = lock.as_ref().unwrap();
+
+    if opts.display_style() == DisplayStyle::Json2 {
+        for (key, count) in unmatched {
+            json_output::print_json(json_output::JsonOutput::BaselineUnmatched(
+                json_output::JsonBaselineUnmatched {
+                    file: key.file,
+                    code: key.code,
+                    count,
+                },
+            ));
+        }
+
+        return;
+    }
+
+    // Some display styles are a single document on stdout, so this can't go there
+    let mut stderr = StandardStream::stderr(get_color());
+    let mut log = || -> io::Result<()> {
+        stderr.set_color(ColorSpec::new().set_fg(Some(Color::Yellow)))?;
+        write!(&mut stderr, "NOTE: ")?;
+        stderr.reset()?;
+        writeln!(
+            &mut stderr,
+            "some diagnostics in the baseline no longer occur, and can be removed by writing it again:"
+        )?;
+
+        for (key, count) in &unmatched {
+            writeln!(&mut stderr, "  {} [{}] x{}", key.file, key.code, count)?;
+        }
+
+        Ok(())
+    };
+
+    log().ok();
+}
+
 fn log_total_text(
     mut stdout: StandardStream,
     parse_errors: usize,
@@ -126,6 +193,9 @@ fn emit_codespan(
     writer: &mut impl termcolor::WriteColor,
     files: &codespan::Files<&str>,
     diagnostic: &CodespanDiagnostic<codespan::FileId>,
+    suggestions: &[Suggestion],
+    // The category of the lint that created the diagnostic, if it came from one
+    category: Option<LintType>,
 ) {
     let lock = OPTIONS.read().unwrap();
     let opts = lock.as_ref().unwrap();
@@ -144,7 +214,12 @@ fn emit_codespan(
             writeln!(
                 writer,
                 "{}",
-                serde_json::to_string(&json_output::diagnostic_to_json(diagnostic, files)).unwrap()
+                serde_json::to_string(&json_output::diagnostic_to_json(
+                    diagnostic,
+                    suggestions,
+                    files
+                ))
+                .unwrap()
             )
             .unwrap();
         }
@@ -154,13 +229,35 @@ fn emit_codespan(
                 writer,
                 "{}",
                 serde_json::to_string(&json_output::JsonOutput::Diagnostic(
-                    json_output::diagnostic_to_json(diagnostic, files)
+                    json_output::diagnostic_to_json(diagnostic, suggestions, files)
+                        .with_category(category)
                 ))
                 .unwrap()
             )
             .unwrap();
         }
 
+        Some(opts::DisplayStyle::Sarif) => {
+            sarif_output::push_diagnostic(diagnostic, suggestions, files);
+        }
+
+        Some(opts::DisplayStyle::Github) => {
+            writeln!(
+                writer,
+                "{}",
+                github_output::diagnostic_to_command(diagnostic, files)
+            )
+            .unwrap();
+        }
+
+        Some(opts::DisplayStyle::Gitlab) => {
+            gitlab_output::push_diagnostic(diagnostic, files);
+        }
+
+        Some(opts::DisplayStyle::Checkstyle) | Some(opts::DisplayStyle::Junit) => {
+            xml_output::push_diagnostic(diagnostic, files);
+        }
+
         Some(opts::DisplayStyle::Rich) | Some(opts::DisplayStyle::Quiet) | None => {
             codespan_reporting::term::emit(writer, config, files, diagnostic)
                 .expect("couldn't emit error to codespan");
@@ -168,17 +265,117 @@ fn emit_codespan(
     }
 }
 
-fn emit_codespan_locked(
+#[derive(Clone, Copy, Debug, Default)]
+struct FileTotals {
+    parse_errors: usize,
+    lint_errors: usize,
+    lint_warnings: usize,
+}
+
+impl FileTotals {
+    const READ_ERROR: FileTotals = FileTotals {
+        parse_errors: 0,
+        lint_errors: 1,
+        lint_warnings: 0,
+    };
+
+    const PARSE_ERROR: FileTotals = FileTotals {
+        parse_errors: 1,
+        lint_errors: 0,
+        lint_warnings: 0,
+    };
+}
+
+fn read<R: Read>(checker: &ConfiguredChecker, filename: &Path, reader: R) {
+    // Output is buffered so that files linted in parallel don't interleave
+    let writer = termcolor::BufferWriter::stdout(get_color());
+    let mut output = writer.buffer();
+
+    let totals = lint(checker, filename, reader, &mut output);
+
+    PARSE_ERRORS.fetch_add(totals.parse_errors, Ordering::SeqCst);
+    LINT_ERRORS.fetch_add(totals.lint_errors, Ordering::SeqCst);
+    LINT_WARNINGS.fetch_add(totals.lint_warnings, Ordering::SeqCst);
+
+    writer.print(&output).expect("can't write to stdout");
+}
+
+fn emit_parse_error(
+    output: &mut impl WriteColor,
     files: &codespan::Files<&str>,
-    diagnostic: &CodespanDiagnostic<codespan::FileId>,
+    source_id: codespan::FileId,
+    filename: &Path,
+    error: full_moon::Error,
 ) {
-    let stdout = termcolor::StandardStream::stdout(get_color());
-    let mut stdout = stdout.lock();
-
-    emit_codespan(&mut stdout, files, diagnostic);
+    match error {
+        full_moon::Error::AstError(full_moon::ast::AstError::UnexpectedToken {
+            token,
+            additional,
+        }) => emit_codespan(
+            output,
+            files,
+            &CodespanDiagnostic {
+                severity: CodespanSeverity::Error,
+                code: Some("parse_error".to_owned()),
+                message: format!("unexpected token `{token}`"),
+                labels: vec![CodespanLabel::primary(
+                    source_id,
+                    codespan::Span::new(
+                        token.start_position().bytes() as u32,
+                        token.end_position().bytes() as u32,
+                    ),
+                )
+                .with_message(additional.unwrap_or_default())],
+                notes: Vec::new(),
+            },
+            &[],
+            None,
+        ),
+        full_moon::Error::TokenizerError(error) => emit_codespan(
+            output,
+            files,
+            &CodespanDiagnostic {
+                severity: CodespanSeverity::Error,
+                code: Some("parse_error".to_owned()),
+                message: match error.error() {
+                    full_moon::tokenizer::TokenizerErrorType::UnclosedComment => {
+                        "unclosed comment".to_string()
+                    }
+                    full_moon::tokenizer::TokenizerErrorType::UnclosedString => {
+                        "unclosed string".to_string()
+                    }
+                    full_moon::tokenizer::TokenizerErrorType::UnexpectedShebang => {
+                        "unexpected shebang".to_string()
+                    }
+                    full_moon::tokenizer::TokenizerErrorType::UnexpectedToken(character) => {
+                        format!("unexpected character {character}")
+                    }
+                    full_moon::tokenizer::TokenizerErrorType::InvalidSymbol(symbol) => {
+                        format!("invalid symbol {symbol}")
+                    }
+                },
+                labels: vec![CodespanLabel::primary(
+                    source_id,
+                    codespan::Span::new(
+                        error.position().bytes() as u32,
+                        error.position().bytes() as u32,
+                    ),
+                )],
+                notes: Vec::new(),
+            },
+            &[],
+            None,
+        ),
+        _ => error!("Error parsing {}: {}", filename.display(), error),
+    }
 }
 
-fn read<R: Read>(checker: &Checker<toml::value::Value>, filename: &Path, mut reader: R) {
+fn lint<R: Read>(
+    checker: &ConfiguredChecker,
+    filename: &Path,
+    mut reader: R,
+    output: &mut impl WriteColor,
+) -> FileTotals {
     let mut buffer = Vec::new();
     if let Err(error) = reader.read_to_end(&mut buffer) {
         error!(
@@ -187,107 +384,128 @@ fn read<R: Read>(checker: &Checker<toml::value::Value>, filename: &Path, mut rea
             error,
         );
 
-        LINT_ERRORS.fetch_add(1, Ordering::SeqCst);
-        return;
+        return FileTotals::READ_ERROR;
     }
 
-    let contents = String::from_utf8_lossy(&buffer);
+    let mut contents = String::from_utf8_lossy(&buffer);
 
     let lock = OPTIONS.read().unwrap();
     let opts = lock.as_ref().unwrap();
 
+    if matches!(
+        opts.display_style,
+        Some(DisplayStyle::Checkstyle) | Some(DisplayStyle::Junit)
+    ) {
+        xml_output::record_file(&filename.to_string_lossy());
+    }
+
+    if opts.fix || opts.fix_dry_run {
+        if let Some(fixed) = fix::fix_source(&checker.checker, &contents) {
+            if opts.fix_dry_run {
+                let diff =
+                    fix::unified_diff(&filename.to_string_lossy(), &contents, &fixed.source);
+
+                // Diagnostics go to stdout, which has to stay valid for machine readable display styles
+                eprint!("{diff}");
+            } else {
+                if let Err(error) = fs::write(filename, &fixed.source) {
+                    error!("Couldn't write fixes to {}: {}", filename.display(), error);
+                    return FileTotals::READ_ERROR;
+                }
+
+                FIXES_APPLIED.fetch_add(fixed.fixes_applied, Ordering::SeqCst);
+
+                // Only report what's left over
+                contents = fixed.source.into();
+            }
+        }
+    }
+
     let mut files = codespan::Files::new();
     let source_id = files.add(filename.as_os_str(), &*contents);
 
-    let ast = {
-        profiling::scope!("full_moon::parse");
+    let cache = checker.cache.as_ref();
 
-        match full_moon::parse(&contents) {
-            Ok(ast) => ast,
-            Err(error) => {
-                PARSE_ERRORS.fetch_add(1, Ordering::SeqCst);
-
-                match error {
-                    full_moon::Error::AstError(full_moon::ast::AstError::UnexpectedToken {
-                        token,
-                        additional,
-                    }) => emit_codespan_locked(
-                        &files,
-                        &CodespanDiagnostic {
-                            severity: CodespanSeverity::Error,
-                            code: Some("parse_error".to_owned()),
-                            message: format!("unexpected token `{token}`"),
-                            labels: vec![CodespanLabel::primary(
-                                source_id,
-                                codespan::Span::new(
-                                    token.start_position().bytes() as u32,
-                                    token.end_position().bytes() as u32,
-                                ),
-                            )
-                            .with_message(additional.unwrap_or_default())],
-                            notes: Vec::new(),
-                        },
-                    ),
-                    full_moon::Error::TokenizerError(error) => emit_codespan_locked(
-                        &files,
-                        &CodespanDiagnostic {
-                            severity: CodespanSeverity::Error,
-                            code: Some("parse_error".to_owned()),
-                            message: match error.error() {
-                                full_moon::tokenizer::TokenizerErrorType::UnclosedComment => {
-                                    "unclosed comment".to_string()
-                                }
-                                full_moon::tokenizer::TokenizerErrorType::UnclosedString => {
-                                    "unclosed string".to_string()
-                                }
-                                full_moon::tokenizer::TokenizerErrorType::UnexpectedShebang => {
-                                    "unexpected shebang".to_string()
-                                }
-                                full_moon::tokenizer::TokenizerErrorType::UnexpectedToken(
-                                    character,
-                                ) => {
-                                    format!("unexpected character {character}")
-                                }
-                                full_moon::tokenizer::TokenizerErrorType::InvalidSymbol(symbol) => {
-                                    format!("invalid symbol {symbol}")
-                                }
-                            },
-                            labels: vec![CodespanLabel::primary(
-                                source_id,
-                                codespan::Span::new(
-                                    error.position().bytes() as u32,
-                                    error.position().bytes() as u32,
-                                ),
-                            )],
-                            notes: Vec::new(),
-                        },
-                    ),
-                    _ => error!("Error parsing {}: {}", filename.display(), error),
+    let mut diagnostics = match cache.and_then(|cache| cache.get(&contents)) {
+        Some(diagnostics) => diagnostics,
+        None => {
+            let ast = {
+                profiling::scope!("full_moon::parse");
+
+                match full_moon::parse(&contents) {
+                    Ok(ast) => ast,
+                    Err(error) => {
+                        emit_parse_error(output, &files, source_id, filename, error);
+                        return FileTotals::PARSE_ERROR;
+                    }
                 }
+            };
 
-                return;
+            let diagnostics = checker
+                .checker
+                .test_on_with_threads(&ast, opts.lint_threads);
+            if let Some(cache) = cache {
+                cache.insert(&contents, &diagnostics);
             }
+
+            diagnostics
         }
     };
-
-    let mut diagnostics = checker.test_on(&ast);
     diagnostics.sort_by_key(|diagnostic| diagnostic.diagnostic.start_position());
 
-    let (mut errors, mut warnings) = (0, 0);
+    if opts.changed_lines_only {
+        if let Some(changed_lines) = CHANGED_FILES
+            .read()
+            .unwrap()
+            .as_ref()
+            .and_then(|changed_files| changed_files.changed_lines(filename))
+        {
+            let line = |byte| {
+                files
+                    .location(source_id, byte)
+                    .unwrap()
+                    .line
+                    .number()
+                    .to_usize()
+            };
+
+            diagnostics.retain(|diagnostic| {
+                let (start, end) = diagnostic.diagnostic.primary_label.range;
+                changed_lines.overlaps(line(start)..=line(end))
+            });
+        }
+    }
+
+    if let Some(baseline) = BASELINE.read().unwrap().as_ref() {
+        let key = |diagnostic: &CheckerDiagnostic| {
+            BaselineKey::new(filename, &diagnostic.diagnostic, &contents)
+        };
+
+        if opts.write_baseline.is_some() {
+            for diagnostic in &diagnostics {
+                if diagnostic.severity != Severity::Allow {
+                    baseline.record(key(diagnostic));
+                }
+            }
+
+            return FileTotals::default();
+        }
+
+        baseline.check_file(filename);
+        diagnostics.retain(|diagnostic| {
+            diagnostic.severity == Severity::Allow || !baseline.take(&key(diagnostic))
+        });
+    }
+
+    let mut totals = FileTotals::default();
     for diagnostic in &diagnostics {
         match diagnostic.severity {
             Severity::Allow => {}
-            Severity::Error => errors += 1,
-            Severity::Warning => warnings += 1,
+            Severity::Error => totals.lint_errors += 1,
+            Severity::Warning => totals.lint_warnings += 1,
         };
     }
 
-    LINT_ERRORS.fetch_add(errors, Ordering::SeqCst);
-    LINT_WARNINGS.fetch_add(warnings, Ordering::SeqCst);
-
-    let stdout = termcolor::StandardStream::stdout(get_color());
-    let mut stdout = stdout.lock();
-
     for diagnostic in diagnostics {
         if opts.luacheck {
             // Existing Luacheck consumers presumably use --formatter plain
@@ -301,12 +519,12 @@ fn read<R: Read>(checker: &Checker<toml::value::Value>, filename: &Path, mut rea
             let mut stack = Vec::new();
 
             let mut write = |stack: &mut Vec<_>, start: codespan::Location| -> io::Result<()> {
-                write!(stdout, "{}:", filename.display())?;
-                write!(stdout, "{}:{}", start.line.number(), start.column.number())?;
+                write!(output, "{}:", filename.display())?;
+                write!(output, "{}:{}", start.line.number(), start.column.number())?;
 
                 if opts.ranges {
                     write!(
-                        stdout,
+                        output,
                         "-{}",
                         if start.line != end.line {
                             // Report to the end of the line
@@ -332,7 +550,7 @@ fn read<R: Read>(checker: &Checker<toml::value::Value>, filename: &Path, mut rea
                 }
 
                 write!(
-                    stdout,
+                    output,
                     ": ({}000) ",
                     match diagnostic.severity {
                         Severity::Allow => return Ok(()),
@@ -341,14 +559,14 @@ fn read<R: Read>(checker: &Checker<toml::value::Value>, filename: &Path, mut rea
                     }
                 )?;
 
-                write!(stdout, "[{}] ", diagnostic.diagnostic.code)?;
-                write!(stdout, "{}", diagnostic.diagnostic.message)?;
+                write!(output, "[{}] ", diagnostic.diagnostic.code)?;
+                write!(output, "{}", diagnostic.diagnostic.message)?;
 
                 if !diagnostic.diagnostic.notes.is_empty() {
-                    write!(stdout, "\n{}", diagnostic.diagnostic.notes.join("\n"))?;
+                    write!(output, "\n{}", diagnostic.diagnostic.notes.join("\n"))?;
                 }
 
-                writeln!(stdout)?;
+                writeln!(output)?;
                 Ok(())
             };
 
@@ -362,6 +580,9 @@ fn read<R: Read>(checker: &Checker<toml::value::Value>, filename: &Path, mut rea
                 write(&mut stack, new_start).unwrap();
             }
         } else {
+            let mut diagnostic = diagnostic;
+            let suggestions = std::mem::take(&mut diagnostic.diagnostic.suggestions);
+
             let diagnostic = diagnostic.diagnostic.into_codespan_diagnostic(
                 source_id,
                 match diagnostic.severity {
@@ -371,12 +592,42 @@ fn read<R: Read>(checker: &Checker<toml::value::Value>, filename: &Path, mut rea
                 },
             );
 
-            emit_codespan(&mut stdout, &files, &diagnostic);
+            // Plugins and custom lints aren't built in, so only the checker knows their category
+            let category = checker
+                .checker
+                .lints()
+                .find(|lint| Some(lint.name) == diagnostic.code.as_deref())
+                .map(|lint| lint.lint_type);
+
+            emit_codespan(output, &files, &diagnostic, &suggestions, category);
         }
     }
+
+    totals
 }
 
-fn read_file(checker: &Checker<toml::value::Value>, filename: &Path) {
+fn read_file(checker: &ConfiguredChecker, filename: &Path) {
+    let staged_contents = CHANGED_FILES
+        .read()
+        .unwrap()
+        .as_ref()
+        .and_then(|changed_files| changed_files.staged_contents(filename));
+
+    match staged_contents {
+        Some(Ok(contents)) => {
+            read(checker, filename, contents.as_bytes());
+            return;
+        }
+
+        Some(Err(error)) => {
+            error!("Couldn't read staged file {}: {}", filename.display(), error);
+            LINT_ERRORS.fetch_add(1, Ordering::SeqCst);
+            return;
+        }
+
+        None => {}
+    }
+
     read(
         checker,
         filename,
@@ -391,6 +642,108 @@ fn read_file(checker: &Checker<toml::value::Value>, filename: &Path) {
     );
 }
 
+/// Finds every file to check from the given inputs other than stdin, along with their checkers.
+/// Files whose config couldn't be loaded are left out.
+fn collect_files(
+    options: &opts::Options,
+    checkers: &mut Checkers,
+) -> Vec<(PathBuf, Arc<ConfiguredChecker>)> {
+    let mut files = Vec::new();
+    let changed_files = CHANGED_FILES.read().unwrap();
+    let is_unchanged = |path: &Path| match &*changed_files {
+        Some(changed_files) => !changed_files.contains(path),
+        None => false,
+    };
+
+    for filename in &options.files {
+        if filename == "-" {
+            continue;
+        }
+
+        match fs::metadata(filename) {
+            Ok(metadata) => {
+                if metadata.is_file() {
+                    let path = PathBuf::from(filename);
+
+                    let Some(checker) = checkers.for_file(&path) else {
+                        continue;
+                    };
+
+                    if !options.no_exclude && checker.is_excluded(&path) {
+                        continue;
+                    }
+
+                    if is_unchanged(&path) {
+                        continue;
+                    }
+
+                    files.push((path, checker));
+                } else if metadata.is_dir() {
+                    for pattern in &options.pattern {
+                        let glob = match glob::glob(&format!(
+                            "{}/{}",
+                            filename.to_string_lossy(),
+                            pattern
+                        )) {
+                            Ok(glob) => glob,
+                            Err(error) => {
+                                error!("Invalid glob pattern: {}", error);
+                                std::process::exit(1);
+                            }
+                        };
+
+                        for entry in glob {
+                            match entry {
+                                Ok(path) => {
+                                    let Some(checker) = checkers.for_file(&path) else {
+                                        continue;
+                                    };
+
+                                    if !options.no_exclude && checker.is_excluded(&path) {
+                                        continue;
+                                    }
+
+                                    if is_unchanged(&path) {
+                                        continue;
+                                    }
+
+                                    files.push((path, checker));
+                                }
+
+                                Err(error) => {
+                                    error!(
+                                        "Couldn't open file {}: {}",
+                                        filename.to_string_lossy(),
+                                        error
+                                    );
+                                }
+                            };
+                        }
+                    }
+                } else {
+                    unreachable!("Somehow got a symlink from the files?");
+                }
+            }
+
+            Err(error) => {
+                error!(
+                    "Error getting metadata of {}: {}",
+                    filename.to_string_lossy(),
+                    error
+                );
+
+                LINT_ERRORS.fetch_add(1, Ordering::SeqCst);
+            }
+        };
+    }
+
+    for config_error in checkers.take_errors() {
+        error(&config_error);
+    }
+
+    files
+}
+
 fn start(mut options: opts::Options) {
     *OPTIONS.write().unwrap() = Some(options.clone());
 
@@ -400,6 +753,32 @@ fn start(mut options: opts::Options) {
         options.pattern.push(String::from("**/*.luau"));
     }
 
+    if options.fix && options.files.iter().any(|filename| filename == "-") {
+        error!("--fix can't rewrite stdin, use --fix-dry-run instead");
+        std::process::exit(1);
+    }
+
+    if options.watch {
+        if let Some(reason) = watch::unsupported_reason(&options) {
+            error!("{reason}");
+            std::process::exit(1);
+        }
+    }
+
+    if options.changed_lines_only && options.changed_since.is_none() && !options.staged {
+        error!("--changed-lines-only needs either --changed-since or --staged");
+        std::process::exit(1);
+    }
+
+    if let Some(lint) = &options.explain {
+        if let Err(error) = explain::explain(lint, options.display_style()) {
+            error!("Couldn't explain lint: {error}");
+            std::process::exit(1);
+        }
+
+        return;
+    }
+
     match &options.command {
         Some(opts::Command::ValidateConfig { stdin }) => {
             let (config_contents, config_path) = if *stdin {
@@ -443,7 +822,13 @@ fn start(mut options: opts::Options) {
                             .expect("can't write to stdout");
                     }
 
-                    opts::DisplayStyle::Json | opts::DisplayStyle::Quiet => {}
+                    opts::DisplayStyle::Json
+                    | opts::DisplayStyle::Quiet
+                    | opts::DisplayStyle::Sarif
+                    | opts::DisplayStyle::Github
+                    | opts::DisplayStyle::Gitlab
+                    | opts::DisplayStyle::Checkstyle
+                    | opts::DisplayStyle::Junit => {}
                 }
 
                 std::process::exit(1);
@@ -491,201 +876,197 @@ fn start(mut options: opts::Options) {
             return;
         }
 
-        None => {}
-    }
+        Some(opts::Command::Lsp) => {
+            if let Err(error) = lsp::run() {
+                error!("Language server stopped unexpectedly: {error}");
+                std::process::exit(1);
+            }
 
-    let (config, config_directory): (CheckerConfig<toml::value::Value>, Option<PathBuf>) =
-        match options.config {
-            Some(config_file) => {
-                let config_contents = match fs::read_to_string(&config_file) {
-                    Ok(contents) => contents,
-                    Err(error) => {
-                        error!("Couldn't read config file: {}", error);
-                        std::process::exit(1);
-                    }
-                };
+            return;
+        }
 
-                match toml::from_str(&config_contents) {
-                    Ok(config) => (
-                        config,
-                        Path::new(&config_file).parent().map(Path::to_path_buf),
-                    ),
-                    Err(error) => {
-                        error!("Config file not in correct format: {}", error);
-                        std::process::exit(1);
-                    }
-                }
+        Some(opts::Command::ListLints) => {
+            if let Err(error) = explain::list_lints(&options) {
+                error!("Couldn't load config: {error}");
+                std::process::exit(1);
             }
 
-            None => match fs::read_to_string("selene.toml") {
-                Ok(config_contents) => match toml::from_str(&config_contents) {
-                    Ok(config) => (config, None),
-                    Err(error) => {
-                        error!("Config file not in correct format: {}", error);
-                        std::process::exit(1);
-                    }
-                },
+            return;
+        }
 
-                Err(_) => (CheckerConfig::default(), None),
-            },
-        };
+        Some(opts::Command::Init {
+            std,
+            pin_roblox_std,
+            force,
+        }) => {
+            if let Err(error) = init::init(
+                &std::env::current_dir().unwrap(),
+                std.as_deref(),
+                *pin_roblox_std,
+                *force,
+            ) {
+                error!("Couldn't create config: {error}");
+                std::process::exit(1);
+            }
 
-    let current_dir = std::env::current_dir().unwrap();
+            return;
+        }
 
-    let standard_library = match standard_library::collect_standard_library(
-        &config,
-        config.std(),
-        &current_dir,
-        &config_directory,
-    ) {
-        Ok(Some(library)) => library,
+        Some(opts::Command::Suppressions { files }) => {
+            let options = opts::Options {
+                files: files.clone(),
+                ..options.clone()
+            };
 
-        Ok(None) => {
-            error!("Standard library was empty.");
-            std::process::exit(1);
-        }
+            let mut checkers = Checkers::from_options(&options);
+            let files = collect_files(&options, &mut checkers);
+            if checkers.has_failed() {
+                std::process::exit(1);
+            }
 
-        Err(error) => {
-            let missing_files: Vec<_> = config
-                .std()
-                .split('+')
-                .filter(|name| {
-                    !PathBuf::from(format!("{name}.yml")).exists()
-                        && !PathBuf::from(format!("{name}.yaml")).exists()
-                        && !PathBuf::from(format!("{name}.toml")).exists()
-                })
-                .filter(|name| !cfg!(feature = "roblox") || *name != "roblox")
-                .collect();
-
-            if !missing_files.is_empty() {
-                eprintln!(
-                    "`std = \"{}\"`, but some libraries could not be found:",
-                    config.std()
-                );
+            suppressions::print_suppressions(files, options.display_style());
 
-                for library_name in missing_files {
-                    eprintln!("  `{library_name}`");
-                }
+            return;
+        }
 
-                error!("Could not find all standard library files");
+        Some(opts::Command::ImportLuacheck { path, force }) => {
+            if let Err(error) = import_luacheck::import_luacheck(path, *force) {
+                error!("Couldn't import luacheck config: {error}");
                 std::process::exit(1);
             }
 
-            error!("Could not collect standard library: {error}");
+            return;
+        }
+
+        None => {}
+    }
+
+    let mut checkers = Checkers::from_options(&options);
+
+    if options.watch {
+        if let Err(error) = watch::watch(&options, checkers) {
+            error!("Couldn't watch files: {error}");
             std::process::exit(1);
         }
-    };
 
-    let mut builder = globset::GlobSetBuilder::new();
-    for pattern in &config.exclude {
-        builder.add(match globset::Glob::new(pattern) {
-            Ok(glob) => glob,
+        return;
+    }
+
+    if let Some(baseline_path) = &options.baseline {
+        match Baseline::load(baseline_path) {
+            Ok(baseline) => *BASELINE.write().unwrap() = Some(baseline),
             Err(error) => {
-                error!("Invalid glob pattern: {error}");
+                error!("Couldn't read baseline {}: {}", baseline_path.display(), error);
                 std::process::exit(1);
             }
-        });
+        }
+    } else if options.write_baseline.is_some() {
+        *BASELINE.write().unwrap() = Some(Baseline::new());
     }
 
-    let exclude_set = match builder.build() {
-        Ok(globset) => globset,
-        Err(error) => {
-            error!("{error}");
-            std::process::exit(1);
-        }
+    let changed_since = match &options.changed_since {
+        Some(revision) => Some(ChangedSince::Revision(revision)),
+        None if options.staged => Some(ChangedSince::Staged),
+        None => None,
     };
 
-    let checker = Arc::new(match Checker::new(config, standard_library) {
-        Ok(checker) => checker,
-        Err(error) => {
-            error!("{error}");
-            std::process::exit(1);
+    if let Some(changed_since) = changed_since {
+        match ChangedFiles::from_git(changed_since) {
+            Ok(changed_files) => *CHANGED_FILES.write().unwrap() = Some(changed_files),
+            Err(error) => {
+                error!("Couldn't find changed files: {error}");
+                std::process::exit(1);
+            }
         }
-    });
-
-    let pool = ThreadPool::new(options.num_threads);
+    }
 
-    for filename in &options.files {
-        if filename == "-" {
-            let checker = Arc::clone(&checker);
-            pool.execute(move || read(&checker, Path::new("-"), io::stdin().lock()));
-            continue;
-        }
+    // Every config is loaded before anything is checked, so that a broken config stops the run
+    let stdin_checker = if options.files.iter().any(|filename| filename == "-") {
+        checkers.for_file(Path::new("-"))
+    } else {
+        None
+    };
 
-        match fs::metadata(filename) {
-            Ok(metadata) => {
-                if metadata.is_file() {
-                    let checker = Arc::clone(&checker);
-                    let filename = filename.to_owned();
+    let files = collect_files(&options, &mut checkers);
+    if checkers.has_failed() {
+        std::process::exit(1);
+    }
 
-                    if !options.no_exclude && exclude_set.is_match(&filename) {
-                        continue;
-                    }
+    let pool = ThreadPool::new(options.num_threads);
 
-                    pool.execute(move || read_file(&checker, Path::new(&filename)));
-                } else if metadata.is_dir() {
-                    for pattern in &options.pattern {
-                        let glob = match glob::glob(&format!(
-                            "{}/{}",
-                            filename.to_string_lossy(),
-                            pattern
-                        )) {
-                            Ok(glob) => glob,
-                            Err(error) => {
-                                error!("Invalid glob pattern: {}", error);
-                                std::process::exit(1);
-                            }
-                        };
+    if let Some(checker) = stdin_checker {
+        pool.execute(move || read(&checker, Path::new("-"), io::stdin().lock()));
+    }
 
-                        for entry in glob {
-                            match entry {
-                                Ok(path) => {
-                                    if !options.no_exclude && exclude_set.is_match(&path) {
-                                        continue;
-                                    }
+    for (path, checker) in files {
+        pool.execute(move || read_file(&checker, &path));
+    }
 
-                                    let checker = Arc::clone(&checker);
+    pool.join();
 
-                                    pool.execute(move || read_file(&checker, &path));
-                                }
+    if let Err(error) = cache::prune(
+        checkers
+            .loaded()
+            .filter_map(|checker| checker.cache.as_ref()),
+    ) {
+        error!("Couldn't prune cache: {error}");
+    }
 
-                                Err(error) => {
-                                    error!(
-                                        "Couldn't open file {}: {}",
-                                        filename.to_string_lossy(),
-                                        error
-                                    );
-                                }
-                            };
-                        }
-                    }
-                } else {
-                    unreachable!("Somehow got a symlink from the files?");
-                }
-            }
+    let (parse_errors, lint_errors, lint_warnings) = (
+        PARSE_ERRORS.load(Ordering::SeqCst),
+        LINT_ERRORS.load(Ordering::SeqCst),
+        LINT_WARNINGS.load(Ordering::SeqCst),
+    );
 
-            Err(error) => {
+    if let Some(baseline) = BASELINE.read().unwrap().as_ref() {
+        if let Some(baseline_path) = &options.write_baseline {
+            if let Err(error) = baseline.write(baseline_path) {
                 error!(
-                    "Error getting metadata of {}: {}",
-                    filename.to_string_lossy(),
+                    "Couldn't write baseline {}: {}",
+                    baseline_path.display(),
                     error
                 );
+                std::process::exit(1);
+            }
 
-                LINT_ERRORS.fetch_add(1, Ordering::SeqCst);
+            // stdout is left to reports such as sarif, which have to be a single document
+            eprintln!(
+                "Wrote {} diagnostics to {}",
+                baseline.len(),
+                baseline_path.display()
+            );
+        } else {
+            let unmatched = baseline.unmatched();
+            if !unmatched.is_empty() {
+                log_baseline_unmatched(unmatched);
             }
-        };
+        }
     }
 
-    pool.join();
-
-    let (parse_errors, lint_errors, lint_warnings) = (
-        PARSE_ERRORS.load(Ordering::SeqCst),
-        LINT_ERRORS.load(Ordering::SeqCst),
-        LINT_WARNINGS.load(Ordering::SeqCst),
-    );
+    // Some display styles are a single document, which is only printed once everything is done
+    match options.display_style {
+        Some(DisplayStyle::Sarif) => sarif_output::print_log(
+            checkers
+                .loaded()
+                .flat_map(|checker| checker.checker.lints()),
+        ),
+        Some(DisplayStyle::Gitlab) => gitlab_output::print_report(),
+        Some(DisplayStyle::Checkstyle) => xml_output::print_checkstyle(),
+        Some(DisplayStyle::Junit) => xml_output::print_junit(),
+
+        _ if !options.luacheck && !options.no_summary => {
+            log_total(parse_errors, lint_errors, lint_warnings).ok();
+
+            let lock = OPTIONS.read().unwrap();
+            let opts = lock.as_ref().unwrap();
+
+            let fixes_applied = FIXES_APPLIED.load(Ordering::SeqCst);
+            if fixes_applied > 0 && opts.display_style() == DisplayStyle::Rich {
+                println!("Applied {fixes_applied} fixes");
+            }
+        }
 
-    if !options.luacheck && !options.no_summary {
-        log_total(parse_errors, lint_errors, lint_warnings).ok();
+        _ => {}
     }
 
     let error_count = parse_errors + lint_errors + lint_warnings + pool.panic_count();
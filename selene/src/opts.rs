@@ -14,13 +14,17 @@ pub struct Options {
     /// A toml file to configure the behavior of selene [default: selene.toml]
     // .default is not used here since if the user explicitly specifies the config file
     // we want it to error if it doesn't exist
-    #[structopt(long)]
+    #[structopt(long, global = true)]
     pub config: Option<String>,
 
     /// Number of threads to run on, default to the numbers of logical cores on your system
     #[structopt(long, default_value = get_num_cpus())]
     pub num_threads: usize,
 
+    /// Number of threads to run the lints of each file on, which speeds up very large files
+    #[structopt(long, default_value = "1")]
+    pub lint_threads: usize,
+
     /// Sets the display method
     // default_value is not used here since it triggers ArgumentConflict with quiet option
     #[structopt(
@@ -53,6 +57,52 @@ pub struct Options {
     #[structopt(long)]
     pub allow_warnings: bool,
 
+    /// Apply all machine applicable fixes, rewriting files in place
+    #[structopt(long, conflicts_with = "fix-dry-run")]
+    pub fix: bool,
+
+    /// Print the fixes --fix would apply as a unified diff to stderr, without changing any files
+    #[structopt(long)]
+    pub fix_dry_run: bool,
+
+    /// A baseline file created by --write-baseline. Diagnostics recorded in it will not be reported
+    #[structopt(long, parse(from_os_str), conflicts_with = "write-baseline")]
+    pub baseline: Option<PathBuf>,
+
+    /// Record every current diagnostic into a baseline file instead of reporting them
+    #[structopt(long, parse(from_os_str))]
+    pub write_baseline: Option<PathBuf>,
+
+    /// Keep running, checking files again whenever they change
+    #[structopt(
+        long,
+        conflicts_with_all = &[
+            "fix",
+            "fix-dry-run",
+            "baseline",
+            "write-baseline",
+            "changed-since",
+            "staged",
+        ],
+    )]
+    pub watch: bool,
+
+    /// Only check files that differ from this git revision, including changes that aren't committed
+    #[structopt(long, conflicts_with = "staged")]
+    pub changed_since: Option<String>,
+
+    /// Only check files with changes staged to be committed
+    #[structopt(long, conflicts_with = "fix")]
+    pub staged: bool,
+
+    /// With --changed-since or --staged, only report diagnostics on lines that changed
+    #[structopt(long)]
+    pub changed_lines_only: bool,
+
+    /// Don't read from or write to the cache, even if it's enabled in the config
+    #[structopt(long)]
+    pub no_cache: bool,
+
     /// Whether to pretend to be luacheck for existing consumers
     #[structopt(long, hidden(true))]
     pub luacheck: bool,
@@ -61,7 +111,16 @@ pub struct Options {
     #[structopt(long, hidden(true))]
     pub ranges: bool,
 
-    #[structopt(parse(from_os_str), min_values(1), index(1), required(true))]
+    /// Prints the documentation of a lint
+    #[structopt(long)]
+    pub explain: Option<String>,
+
+    #[structopt(
+        parse(from_os_str),
+        min_values(1),
+        index(1),
+        required_unless("explain")
+    )]
     pub files: Vec<OsString>,
 
     #[structopt(subcommand)]
@@ -110,6 +169,45 @@ pub enum Command {
 
     /// Prints the capabilities of the current build
     Capabilities,
+
+    /// Runs a language server over stdio, for editors to show diagnostics as you type
+    Lsp,
+
+    /// Prints every lint, along with its default severity, category, and configuration
+    ListLints,
+
+    /// Creates a selene.toml for the project in the current directory
+    Init {
+        /// The standard library to use, instead of guessing it from the project
+        #[structopt(long)]
+        std: Option<String>,
+
+        /// Generate the Roblox standard library into roblox.yml instead of updating it automatically
+        #[structopt(long)]
+        pin_roblox_std: bool,
+
+        /// Replace selene.toml if it already exists
+        #[structopt(long)]
+        force: bool,
+    },
+
+    /// Lists every `selene:` filter in the given files, grouped by lint and file
+    Suppressions {
+        /// The files and directories to look in
+        #[structopt(parse(from_os_str), default_value = ".")]
+        files: Vec<OsString>,
+    },
+
+    /// Converts a .luacheckrc into a selene.toml, along with a standard library for its globals
+    ImportLuacheck {
+        /// The .luacheckrc to convert. The files are created next to it
+        #[structopt(parse(from_os_str), default_value = ".luacheckrc")]
+        path: PathBuf,
+
+        /// Replace selene.toml and the standard libraries if they already exist
+        #[structopt(long)]
+        force: bool,
+    },
 }
 
 arg_enum! {
@@ -128,6 +226,11 @@ arg_enum! {
         Json2,
         Rich,
         Quiet,
+        Sarif,
+        Github,
+        Gitlab,
+        Checkstyle,
+        Junit,
     }
 }
 
@@ -0,0 +1,600 @@
+//! Output in the SARIF 2.1.0 format, used by code scanning dashboards.
+//! Unlike the other display styles, SARIF is a single document, so results are
+//! collected over the run and printed all at once at the end.
+//! https://docs.oasis-open.org/sarif/sarif/v2.1.0/sarif-v2.1.0.html
+
+use std::sync::Mutex;
+
+use codespan_reporting::diagnostic::{
+    Diagnostic as CodespanDiagnostic, Label as CodespanLabel, LabelStyle, Severity,
+};
+use selene_lib::{
+    lints::{Severity as LintSeverity, Suggestion},
+    LintInfo,
+};
+use serde::Serialize;
+
+const SARIF_SCHEMA: &str = "https://json.schemastore.org/sarif-2.1.0.json";
+const SARIF_VERSION: &str = "2.1.0";
+const SRCROOT: &str = "%SRCROOT%";
+
+lazy_static::lazy_static! {
+    static ref RESULTS: Mutex<Vec<SarifResult>> = Mutex::new(Vec::new());
+    static ref NOTIFICATIONS: Mutex<Vec<SarifNotification>> = Mutex::new(Vec::new());
+}
+
+#[derive(Serialize)]
+pub struct SarifLog {
+    #[serde(rename = "$schema")]
+    schema: &'static str,
+    version: &'static str,
+    runs: Vec<SarifRun>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRun {
+    tool: SarifTool,
+    invocations: Vec<SarifInvocation>,
+    // codespan columns are counted in characters, not the default of UTF-16 code units
+    column_kind: &'static str,
+    results: Vec<SarifResult>,
+}
+
+#[derive(Serialize)]
+struct SarifTool {
+    driver: SarifDriver,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifDriver {
+    name: &'static str,
+    version: &'static str,
+    information_uri: &'static str,
+    rules: Vec<SarifRule>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRule {
+    id: &'static str,
+    name: &'static str,
+    // Only lints built into selene are documented
+    #[serde(skip_serializing_if = "Option::is_none")]
+    help_uri: Option<String>,
+    default_configuration: SarifRuleConfiguration,
+    properties: SarifRuleProperties,
+}
+
+#[derive(Serialize)]
+struct SarifRuleConfiguration {
+    enabled: bool,
+    level: SarifLevel,
+}
+
+#[derive(Serialize)]
+struct SarifRuleProperties {
+    category: &'static str,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifInvocation {
+    execution_successful: bool,
+    tool_execution_notifications: Vec<SarifNotification>,
+}
+
+#[derive(Serialize)]
+struct SarifNotification {
+    descriptor: SarifDescriptor,
+    level: SarifLevel,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+}
+
+#[derive(Serialize)]
+struct SarifDescriptor {
+    id: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifResult {
+    rule_id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    rule_index: Option<usize>,
+    level: SarifLevel,
+    message: SarifMessage,
+    locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    related_locations: Vec<SarifLocation>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    fixes: Vec<SarifFix>,
+}
+
+#[derive(Clone, Copy, Serialize)]
+#[serde(rename_all = "camelCase")]
+enum SarifLevel {
+    Error,
+    Warning,
+    Note,
+    None,
+}
+
+impl From<Severity> for SarifLevel {
+    fn from(severity: Severity) -> Self {
+        match severity {
+            Severity::Bug | Severity::Error => SarifLevel::Error,
+            Severity::Warning => SarifLevel::Warning,
+            Severity::Note | Severity::Help => SarifLevel::Note,
+        }
+    }
+}
+
+impl From<LintSeverity> for SarifLevel {
+    fn from(severity: LintSeverity) -> Self {
+        match severity {
+            LintSeverity::Allow => SarifLevel::None,
+            LintSeverity::Error => SarifLevel::Error,
+            LintSeverity::Warning => SarifLevel::Warning,
+        }
+    }
+}
+
+#[derive(Serialize)]
+struct SarifMessage {
+    text: String,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifLocation {
+    physical_location: SarifPhysicalLocation,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    message: Option<SarifMessage>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifPhysicalLocation {
+    artifact_location: SarifArtifactLocation,
+    region: SarifRegion,
+}
+
+#[derive(Clone, Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactLocation {
+    uri: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    uri_base_id: Option<&'static str>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifRegion {
+    start_line: usize,
+    start_column: usize,
+    end_line: usize,
+    end_column: usize,
+    byte_offset: usize,
+    byte_length: usize,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifFix {
+    description: SarifMessage,
+    artifact_changes: Vec<SarifArtifactChange>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifArtifactChange {
+    artifact_location: SarifArtifactLocation,
+    replacements: Vec<SarifReplacement>,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifReplacement {
+    deleted_region: SarifByteRegion,
+    inserted_content: SarifMessage,
+}
+
+#[derive(Serialize)]
+#[serde(rename_all = "camelCase")]
+struct SarifByteRegion {
+    byte_offset: usize,
+    byte_length: usize,
+}
+
+// Percent-encodes everything but unreserved characters and the ones in `keep`
+fn percent_encode(path: &str, keep: &[char]) -> String {
+    let mut encoded = String::new();
+
+    for character in path.chars() {
+        if character.is_ascii_alphanumeric()
+            || "-._~".contains(character)
+            || keep.contains(&character)
+        {
+            encoded.push(character);
+        } else {
+            for byte in character.to_string().bytes() {
+                encoded.push_str(&format!("%{byte:02X}"));
+            }
+        }
+    }
+
+    encoded
+}
+
+// Relative paths are given relative to `%SRCROOT%`, which code scanning resolves to the checkout,
+// and absolute ones as `file://` URLs
+fn artifact_location(
+    files: &codespan::Files<&str>,
+    file_id: codespan::FileId,
+) -> SarifArtifactLocation {
+    let path = files.name(file_id).to_string_lossy().replace('\\', "/");
+    let is_windows_absolute = path
+        .as_bytes()
+        .get(..3)
+        .is_some_and(|start| start[0].is_ascii_alphabetic() && &start[1..] == b":/");
+
+    if path.starts_with('/') {
+        SarifArtifactLocation {
+            uri: format!("file://{}", percent_encode(&path, &['/'])),
+            uri_base_id: None,
+        }
+    } else if is_windows_absolute {
+        SarifArtifactLocation {
+            uri: format!("file:///{}", percent_encode(&path, &['/', ':'])),
+            uri_base_id: None,
+        }
+    } else {
+        SarifArtifactLocation {
+            uri: percent_encode(path.trim_start_matches("./"), &['/']),
+            uri_base_id: Some(SRCROOT),
+        }
+    }
+}
+
+fn region(
+    file_id: codespan::FileId,
+    range: (usize, usize),
+    files: &codespan::Files<&str>,
+) -> SarifRegion {
+    let start_location = files
+        .location(file_id, range.0 as u32)
+        .expect("unable to determine start location for label");
+    let end_location = files
+        .location(file_id, range.1 as u32)
+        .expect("unable to determine end location for label");
+
+    // SARIF lines and columns start at 1, codespan's start at 0
+    SarifRegion {
+        start_line: start_location.line.to_usize() + 1,
+        start_column: start_location.column.to_usize() + 1,
+        end_line: end_location.line.to_usize() + 1,
+        end_column: end_location.column.to_usize() + 1,
+        byte_offset: range.0,
+        byte_length: range.1 - range.0,
+    }
+}
+
+fn label_to_location(
+    label: &CodespanLabel<codespan::FileId>,
+    files: &codespan::Files<&str>,
+) -> SarifLocation {
+    SarifLocation {
+        physical_location: SarifPhysicalLocation {
+            artifact_location: artifact_location(files, label.file_id),
+            region: region(label.file_id, (label.range.start, label.range.end), files),
+        },
+        message: if label.message.is_empty() {
+            None
+        } else {
+            Some(SarifMessage {
+                text: label.message.to_owned(),
+            })
+        },
+    }
+}
+
+fn message_with_notes(diagnostic: &CodespanDiagnostic<codespan::FileId>) -> String {
+    let mut text = diagnostic.message.to_owned();
+
+    for note in &diagnostic.notes {
+        text.push('\n');
+        text.push_str(note);
+    }
+
+    text
+}
+
+/// Stores the diagnostic to be printed at the end of the run.
+/// Parse errors are not the result of a lint, so they become tool notifications.
+pub fn push_diagnostic(
+    diagnostic: &CodespanDiagnostic<codespan::FileId>,
+    suggestions: &[Suggestion],
+    files: &codespan::Files<&str>,
+) {
+    let code = diagnostic.code.to_owned().unwrap_or_default();
+    let locations = diagnostic
+        .labels
+        .iter()
+        .filter(|label| label.style == LabelStyle::Primary)
+        .map(|label| label_to_location(label, files))
+        .collect();
+
+    if code == "parse_error" {
+        NOTIFICATIONS.lock().unwrap().push(SarifNotification {
+            descriptor: SarifDescriptor { id: code },
+            level: diagnostic.severity.into(),
+            message: SarifMessage {
+                text: message_with_notes(diagnostic),
+            },
+            locations,
+        });
+
+        return;
+    }
+
+    let file_id = diagnostic.labels.first().expect("no labels passed").file_id;
+
+    let result = SarifResult {
+        // Filled in with the rules, which aren't known until the end
+        rule_index: None,
+        rule_id: code,
+        level: diagnostic.severity.into(),
+        message: SarifMessage {
+            text: message_with_notes(diagnostic),
+        },
+        locations,
+        related_locations: diagnostic
+            .labels
+            .iter()
+            .filter(|label| label.style == LabelStyle::Secondary)
+            .map(|label| label_to_location(label, files))
+            .collect(),
+        fixes: suggestions
+            .iter()
+            .map(|suggestion| SarifFix {
+                description: SarifMessage {
+                    text: suggestion.message.to_owned(),
+                },
+                artifact_changes: vec![SarifArtifactChange {
+                    artifact_location: artifact_location(files, file_id),
+                    replacements: suggestion
+                        .edits
+                        .iter()
+                        .map(|edit| SarifReplacement {
+                            deleted_region: SarifByteRegion {
+                                byte_offset: edit.range.0 as usize,
+                                byte_length: (edit.range.1 - edit.range.0) as usize,
+                            },
+                            inserted_content: SarifMessage {
+                                text: edit.replacement.to_owned(),
+                            },
+                        })
+                        .collect(),
+                }],
+            })
+            .collect(),
+    };
+
+    RESULTS.lock().unwrap().push(result);
+}
+
+// Every checker can have different plugins and custom lints, so the rules are every lint any of
+// them ran, sorted by name to keep the output stable
+fn rules<'a>(lints: impl IntoIterator<Item = &'a LintInfo>) -> Vec<SarifRule> {
+    let mut lints = lints.into_iter().collect::<Vec<_>>();
+    lints.sort_by_key(|lint| lint.name);
+    lints.dedup_by_key(|lint| lint.name);
+
+    lints
+        .into_iter()
+        .map(|lint| SarifRule {
+            id: lint.name,
+            name: lint.name,
+            help_uri: selene_lib::all_lints()
+                .iter()
+                .any(|built_in| built_in.name == lint.name)
+                .then(|| {
+                    format!(
+                        "https://kampfkarren.github.io/selene/lints/{}.html",
+                        lint.name
+                    )
+                }),
+            default_configuration: SarifRuleConfiguration {
+                enabled: lint.severity != LintSeverity::Allow,
+                level: lint.severity.into(),
+            },
+            properties: SarifRuleProperties {
+                category: lint.lint_type.name(),
+            },
+        })
+        .collect()
+}
+
+/// Builds the log out of everything pushed so far, with a rule for every lint given.
+/// Files are linted in parallel, so results are sorted to keep the output stable.
+pub fn take_log<'a>(lints: impl IntoIterator<Item = &'a LintInfo>) -> SarifLog {
+    let rules = rules(lints);
+
+    let mut results = std::mem::take(&mut *RESULTS.lock().unwrap());
+    for result in &mut results {
+        result.rule_index = rules.iter().position(|rule| rule.id == result.rule_id);
+    }
+
+    results.sort_by(|a, b| {
+        let key = |result: &SarifResult| {
+            result.locations.first().map(|location| {
+                (
+                    location.physical_location.artifact_location.uri.to_owned(),
+                    location.physical_location.region.byte_offset,
+                )
+            })
+        };
+
+        key(a).cmp(&key(b))
+    });
+
+    let mut notifications = std::mem::take(&mut *NOTIFICATIONS.lock().unwrap());
+    notifications.sort_by(|a, b| {
+        let key = |notification: &SarifNotification| {
+            notification
+                .locations
+                .first()
+                .map(|location| location.physical_location.artifact_location.uri.to_owned())
+        };
+
+        key(a).cmp(&key(b))
+    });
+
+    SarifLog {
+        schema: SARIF_SCHEMA,
+        version: SARIF_VERSION,
+        runs: vec![SarifRun {
+            tool: SarifTool {
+                driver: SarifDriver {
+                    name: "selene",
+                    version: env!("CARGO_PKG_VERSION"),
+                    information_uri: "https://kampfkarren.github.io/selene/",
+                    rules,
+                },
+            },
+            invocations: vec![SarifInvocation {
+                execution_successful: notifications.is_empty(),
+                tool_execution_notifications: notifications,
+            }],
+            column_kind: "unicodeCodePoints",
+            results,
+        }],
+    }
+}
+
+pub fn print_log<'a>(lints: impl IntoIterator<Item = &'a LintInfo>) {
+    println!(
+        "{}",
+        serde_json::to_string_pretty(&take_log(lints)).expect("unable to serialize sarif output")
+    );
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use selene_lib::lints::LintType;
+
+    #[test]
+    fn test_sarif_log() {
+        let mut files = codespan::Files::new();
+        let file_id = files.add("src\\code.lua", "local x = 1\nprint(1 / 0)\n");
+
+        push_diagnostic(
+            &CodespanDiagnostic::warning()
+                .with_code("divide_by_zero")
+                .with_message("dividing by zero is not allowed, use math.huge instead")
+                .with_labels(vec![CodespanLabel::primary(file_id, 18..23)]),
+            &[],
+            &files,
+        );
+
+        push_diagnostic(
+            &CodespanDiagnostic::warning()
+                .with_code("no_print")
+                .with_message("don't print")
+                .with_labels(vec![CodespanLabel::primary(file_id, 12..17)]),
+            &[],
+            &files,
+        );
+
+        push_diagnostic(
+            &CodespanDiagnostic::error()
+                .with_code("parse_error")
+                .with_message("unexpected token `)`")
+                .with_labels(vec![CodespanLabel::primary(file_id, 0..1)]),
+            &[],
+            &files,
+        );
+
+        // Such as a custom lint from selene.toml
+        let no_print = LintInfo::new("no_print", LintSeverity::Warning, LintType::Style);
+
+        let log = serde_json::to_value(take_log(selene_lib::all_lints().iter().chain([&no_print])))
+            .unwrap();
+        let run = &log["runs"][0];
+        let rules = &run["tool"]["driver"]["rules"];
+
+        assert_eq!(log["version"], "2.1.0");
+        assert_eq!(
+            rules.as_array().unwrap().len(),
+            selene_lib::all_lints().len() + 1
+        );
+
+        let rule =
+            |result: &serde_json::Value| &rules[result["ruleIndex"].as_u64().unwrap() as usize];
+
+        let result = &run["results"][1];
+        assert_eq!(result["ruleId"], "divide_by_zero");
+        assert_eq!(rule(result)["id"], "divide_by_zero");
+        assert_eq!(
+            rule(result)["helpUri"],
+            "https://kampfkarren.github.io/selene/lints/divide_by_zero.html"
+        );
+        assert_eq!(result["level"], "warning");
+
+        let custom_result = &run["results"][0];
+        assert_eq!(custom_result["ruleId"], "no_print");
+        assert_eq!(rule(custom_result)["id"], "no_print");
+        assert!(rule(custom_result).get("helpUri").is_none());
+
+        let location = &result["locations"][0]["physicalLocation"];
+        assert_eq!(location["artifactLocation"]["uri"], "src/code.lua");
+        assert_eq!(location["artifactLocation"]["uriBaseId"], "%SRCROOT%");
+        assert_eq!(location["region"]["startLine"], 2);
+        assert_eq!(location["region"]["startColumn"], 7);
+        assert_eq!(location["region"]["byteOffset"], 18);
+        assert_eq!(location["region"]["byteLength"], 5);
+
+        let invocation = &run["invocations"][0];
+        assert_eq!(invocation["executionSuccessful"], false);
+        assert_eq!(
+            invocation["toolExecutionNotifications"][0]["descriptor"]["id"],
+            "parse_error"
+        );
+    }
+
+    #[test]
+    fn test_artifact_location() {
+        let mut files = codespan::Files::new();
+        let mut location = |name: &str| {
+            let file_id = files.add(name, "");
+            serde_json::to_value(artifact_location(&files, file_id)).unwrap()
+        };
+
+        assert_eq!(
+            location("./src/my code #1 100%.lua"),
+            serde_json::json!({
+                "uri": "src/my%20code%20%231%20100%25.lua",
+                "uriBaseId": "%SRCROOT%",
+            })
+        );
+
+        assert_eq!(
+            location("src\\gr\u{fc}n.lua"),
+            serde_json::json!({ "uri": "src/gr%C3%BCn.lua", "uriBaseId": "%SRCROOT%" })
+        );
+
+        assert_eq!(
+            location("C:\\My Project\\code.lua"),
+            serde_json::json!({ "uri": "file:///C:/My%20Project/code.lua" })
+        );
+
+        assert_eq!(
+            location("/home/me/my project/code.lua"),
+            serde_json::json!({ "uri": "file:///home/me/my%20project/code.lua" })
+        );
+    }
+}
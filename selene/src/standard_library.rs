@@ -91,12 +91,12 @@ pub fn collect_standard_library<V>(
     config: &CheckerConfig<V>,
     standard_library_name: &str,
     directory: &Path,
-    config_directory: &Option<PathBuf>,
+    config_directories: &[PathBuf],
 ) -> Result<Option<StandardLibrary>, StandardLibraryError> {
     let mut standard_library: Option<StandardLibrary> = None;
 
     for segment in standard_library_name.split('+') {
-        let segment_library = match from_name(config, segment, directory, config_directory)? {
+        let segment_library = match from_name(config, segment, directory, config_directories)? {
             Some(segment_library) => segment_library,
             None => {
                 if cfg!(feature = "roblox") && segment == "roblox" {
@@ -144,14 +144,12 @@ fn from_name<V>(
     config: &CheckerConfig<V>,
     standard_library_name: &str,
     directory: &Path,
-    config_directory: &Option<PathBuf>,
+    config_directories: &[PathBuf],
 ) -> Result<Option<StandardLibrary>, StandardLibraryError> {
     let mut library: Option<StandardLibrary> = None;
 
-    let mut directories = vec![directory];
-    if let Some(directory) = config_directory {
-        directories.push(directory);
-    };
+    let directories =
+        std::iter::once(directory).chain(config_directories.iter().map(PathBuf::as_path));
 
     for directory in directories {
         let toml_file = directory.join(format!("{standard_library_name}.toml"));
@@ -199,7 +197,7 @@ fn from_name<V>(
         Some(mut library) => {
             if let Some(base_name) = &library.base {
                 if let Some(base) =
-                    collect_standard_library(config, base_name, directory, config_directory)
+                    collect_standard_library(config, base_name, directory, config_directories)
                         .map_err(|error| StandardLibraryError::BaseStd {
                             source: Box::new(error),
                             name: base_name.clone(),
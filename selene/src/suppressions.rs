@@ -0,0 +1,323 @@
+//! `selene suppressions`, which lists every `-- selene:` filter so that how much code is exempted
+//! from linting can be audited.
+
+use std::{
+    collections::BTreeMap,
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::Arc,
+};
+
+use selene_lib::{LintFilter, LintVariation};
+
+use crate::{
+    checkers::ConfiguredChecker,
+    json_output::{self, JsonOutput, JsonSuppression},
+    opts::DisplayStyle,
+};
+
+struct Suppression {
+    filter: LintFilter,
+    comment: json_output::Span,
+    range: json_output::Span,
+}
+
+fn variation_name(variation: LintVariation) -> &'static str {
+    match variation {
+        LintVariation::Allow => "allow",
+        LintVariation::Deny => "deny",
+        LintVariation::Warn => "warn",
+    }
+}
+
+// Grouped by lint and then by file
+#[derive(Default)]
+struct Suppressions {
+    lints: BTreeMap<String, BTreeMap<PathBuf, Vec<Suppression>>>,
+    file_count: usize,
+}
+
+impl Suppressions {
+    fn add_file(&mut self, path: &Path, contents: &str, lint_names: &[&str]) -> Result<(), ()> {
+        let ast = full_moon::parse(contents).map_err(|_| ())?;
+
+        let filters = selene_lib::lint_filters(&ast, lint_names);
+        if filters.is_empty() {
+            return Ok(());
+        }
+
+        self.file_count += 1;
+
+        let mut files = codespan::Files::new();
+        let file_id = files.add(path.display().to_string(), contents);
+
+        for filter in filters {
+            self.lints
+                .entry(filter.lint.clone())
+                .or_default()
+                .entry(path.to_path_buf())
+                .or_default()
+                .push(Suppression {
+                    comment: json_output::range_to_span(file_id, filter.comment_range, &files),
+                    range: json_output::range_to_span(file_id, filter.range, &files),
+                    filter,
+                });
+        }
+
+        Ok(())
+    }
+
+    fn write_json(self, output: &mut impl Write) -> io::Result<()> {
+        for (path, suppression) in
+            self.lints
+                .into_values()
+                .flatten()
+                .flat_map(|(path, suppressions)| {
+                    suppressions
+                        .into_iter()
+                        .map(move |suppression| (path.clone(), suppression))
+                })
+        {
+            writeln!(
+                output,
+                "{}",
+                serde_json::to_string(&JsonOutput::Suppression(JsonSuppression {
+                    file: path.display().to_string(),
+                    lint: suppression.filter.lint,
+                    variation: suppression.filter.variation,
+                    global: suppression.filter.global,
+                    next_line: suppression.filter.next_line,
+                    reason: suppression.filter.reason,
+                    known: suppression.filter.known,
+                    comment: suppression.comment,
+                    range: suppression.range,
+                }))
+                .expect("unable to serialize json output")
+            )?;
+        }
+
+        Ok(())
+    }
+
+    fn write_text(&self, output: &mut impl Write) -> io::Result<()> {
+        let mut filter_count = 0;
+
+        for (lint, files) in &self.lints {
+            let count = files.values().map(Vec::len).sum::<usize>();
+            filter_count += count;
+
+            write!(
+                output,
+                "{lint}: {count} {}",
+                if count == 1 { "filter" } else { "filters" }
+            )?;
+
+            // The same lint can exist for one config but not another, such as with plugins
+            if files
+                .values()
+                .flatten()
+                .all(|suppression| !suppression.filter.known)
+            {
+                write!(output, " (no lint with this name exists)")?;
+            }
+
+            writeln!(output)?;
+
+            for (path, suppressions) in files {
+                writeln!(output, "  {}", path.display())?;
+
+                for suppression in suppressions {
+                    let covers = if suppression.filter.global {
+                        "the entire file".to_owned()
+                    } else if suppression.filter.next_line {
+                        format!("line {}", suppression.comment.end_line + 2)
+                    } else if suppression.range.start_line == suppression.range.end_line {
+                        format!("line {}", suppression.range.start_line + 1)
+                    } else {
+                        format!(
+                            "lines {}-{}",
+                            suppression.range.start_line + 1,
+                            suppression.range.end_line + 1
+                        )
+                    };
+
+                    let location = format!(
+                        "{}:{}",
+                        suppression.comment.start_line + 1,
+                        suppression.comment.start_column + 1
+                    );
+
+                    write!(
+                        output,
+                        "    {location:<8} {} {covers}",
+                        variation_name(suppression.filter.variation)
+                    )?;
+
+                    if !suppression.filter.known {
+                        write!(output, " (unknown lint)")?;
+                    }
+
+                    match &suppression.filter.reason {
+                        Some(reason) => writeln!(output, ": {reason}")?,
+                        None => writeln!(output)?,
+                    }
+                }
+            }
+        }
+
+        writeln!(
+            output,
+            "{filter_count} {} in {} {}",
+            if filter_count == 1 {
+                "filter"
+            } else {
+                "filters"
+            },
+            self.file_count,
+            if self.file_count == 1 {
+                "file"
+            } else {
+                "files"
+            }
+        )
+    }
+}
+
+/// Prints every filter in the given files, grouped by lint and then by file. Filters for lints
+/// the file's checker doesn't run are listed too, since they do nothing.
+pub fn print_suppressions(
+    files: impl IntoIterator<Item = (PathBuf, Arc<ConfiguredChecker>)>,
+    display_style: DisplayStyle,
+) {
+    let mut suppressions = Suppressions::default();
+
+    for (path, checker) in files {
+        let contents = match fs::read_to_string(&path) {
+            Ok(contents) => contents,
+            Err(error) => {
+                crate::error(&format!("Couldn't read {}: {error}", path.display()));
+                continue;
+            }
+        };
+
+        let lint_names = checker
+            .checker
+            .lints()
+            .map(|lint| lint.name)
+            .collect::<Vec<_>>();
+
+        if suppressions
+            .add_file(&path, &contents, &lint_names)
+            .is_err()
+        {
+            crate::error(&format!("Couldn't parse {}", path.display()));
+        }
+    }
+
+    let mut stdout = io::stdout().lock();
+
+    if display_style == DisplayStyle::Json2 {
+        suppressions.write_json(&mut stdout)
+    } else {
+        suppressions.write_text(&mut stdout)
+    }
+    .expect("can't write to stdout");
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const LINT_NAMES: &[&str] = &["shadowing", "unused_variable", "undefined_variable"];
+
+    fn suppressions() -> Suppressions {
+        let mut suppressions = Suppressions::default();
+
+        suppressions
+            .add_file(
+                Path::new("a.lua"),
+                "--# selene: allow(shadowing)\n\
+                 -- selene: allow(unused_variable) -- reason: testing\n\
+                 local x = 1\n\
+                 -- selene: deny-next-line(undefined_variable)\n\
+                 print(y)\n",
+                LINT_NAMES,
+            )
+            .unwrap();
+
+        suppressions
+            .add_file(
+                Path::new("b.lua"),
+                "-- selene: allow(shadowing, not_a_lint)\n\
+                 local function f()\n\
+                 \tlocal x = 1\n\
+                 end\n",
+                LINT_NAMES,
+            )
+            .unwrap();
+
+        suppressions
+            .add_file(Path::new("c.lua"), "print(1)\n", LINT_NAMES)
+            .unwrap();
+
+        suppressions
+    }
+
+    #[test]
+    fn test_text() {
+        let mut output = Vec::new();
+        suppressions().write_text(&mut output).unwrap();
+
+        assert_eq!(
+            String::from_utf8(output).unwrap(),
+            "\
+not_a_lint: 1 filter (no lint with this name exists)
+  b.lua
+    1:1      allow lines 2-4 (unknown lint)
+shadowing: 2 filters
+  a.lua
+    1:1      allow the entire file
+  b.lua
+    1:1      allow lines 2-4
+undefined_variable: 1 filter
+  a.lua
+    4:1      deny line 5
+unused_variable: 1 filter
+  a.lua
+    2:1      allow line 3: testing
+5 filters in 2 files
+"
+        );
+    }
+
+    #[test]
+    fn test_json() {
+        let mut output = Vec::new();
+        suppressions().write_json(&mut output).unwrap();
+
+        let records = String::from_utf8(output)
+            .unwrap()
+            .lines()
+            .map(|line| serde_json::from_str::<serde_json::Value>(line).unwrap())
+            .collect::<Vec<_>>();
+
+        assert_eq!(records.len(), 5);
+
+        assert_eq!(records[0]["type"], "Suppression");
+        assert_eq!(records[0]["file"], "b.lua");
+        assert_eq!(records[0]["lint"], "not_a_lint");
+        assert_eq!(records[0]["known"], false);
+
+        assert_eq!(records[1]["file"], "a.lua");
+        assert_eq!(records[1]["lint"], "shadowing");
+        assert_eq!(records[1]["global"], true);
+        assert_eq!(records[1]["known"], true);
+
+        assert_eq!(records[4]["lint"], "unused_variable");
+        assert_eq!(records[4]["variation"], "allow");
+        assert_eq!(records[4]["reason"], "testing");
+        assert_eq!(records[4]["comment"]["start_line"], 1);
+        assert_eq!(records[4]["range"]["start_line"], 2);
+    }
+}
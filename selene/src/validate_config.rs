@@ -120,7 +120,7 @@ pub fn validate_config(
         ErrorRange { start, end }
     });
 
-    let Err(error) = crate::standard_library::collect_standard_library(&config, config.std(), directory, &None) else {
+    let Err(error) = crate::standard_library::collect_standard_library(&config, config.std(), directory, &[]) else {
         return Ok(());
     };
 
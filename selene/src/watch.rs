@@ -0,0 +1,359 @@
+//! Watch mode, which keeps the checkers loaded and checks files again whenever they change.
+//! Collecting the standard library can be slow (especially Roblox's), so it is only collected
+//! again when a config or a standard library changes.
+
+use std::{
+    collections::{BTreeMap, HashSet},
+    fs,
+    io::{self, Write},
+    path::{Path, PathBuf},
+    sync::{mpsc, Arc, Mutex},
+    time::Duration,
+};
+
+use notify::{Event, EventKind, RecursiveMode, Watcher};
+use termcolor::{Buffer, BufferWriter};
+use threadpool::ThreadPool;
+
+use crate::{
+    checkers::{Checkers, ConfiguredChecker},
+    collect_files, get_color, lint, log_total, opts, FileTotals,
+};
+
+// Editors often save a file with several writes, so changes close together are handled at once
+const DEBOUNCE: Duration = Duration::from_millis(100);
+
+struct CheckedFile {
+    output: Buffer,
+    totals: FileTotals,
+}
+
+struct WatchState<'a> {
+    options: &'a opts::Options,
+    checkers: Checkers,
+    files: BTreeMap<PathBuf, CheckedFile>,
+}
+
+impl WatchState<'_> {
+    fn check_files(&mut self, paths: Vec<(PathBuf, Arc<ConfiguredChecker>)>) {
+        let pool = ThreadPool::new(self.options.num_threads);
+        let checked = Arc::new(Mutex::new(Vec::new()));
+
+        for (path, checker) in paths {
+            let checked = Arc::clone(&checked);
+
+            pool.execute(move || {
+                // The file may have been removed since the change was seen
+                let Ok(file) = fs::File::open(&path) else {
+                    return;
+                };
+
+                let mut output = BufferWriter::stdout(get_color()).buffer();
+                let totals = lint(&checker, &path, file, &mut output);
+
+                checked
+                    .lock()
+                    .unwrap()
+                    .push((path, CheckedFile { output, totals }));
+            });
+        }
+
+        pool.join();
+
+        self.files
+            .extend(std::mem::take(&mut *checked.lock().unwrap()));
+    }
+
+    fn check_all_files(&mut self) {
+        self.files.clear();
+
+        let found = collect_files(self.options, &mut self.checkers);
+        self.check_files(found);
+    }
+
+    fn check_changed_files(&mut self, changed: &HashSet<PathBuf>) {
+        let found = collect_files(self.options, &mut self.checkers);
+
+        let found_set = found.iter().map(|(path, _)| path).collect::<HashSet<_>>();
+        self.files.retain(|path, _| found_set.contains(path));
+
+        let to_check = found
+            .into_iter()
+            .filter(|(path, _)| {
+                !self.files.contains_key(path)
+                    || match fs::canonicalize(path) {
+                        Ok(path) => changed.contains(&path),
+                        Err(_) => true,
+                    }
+            })
+            .collect();
+
+        self.check_files(to_check);
+    }
+
+    fn redraw(&self) -> io::Result<()> {
+        let writer = BufferWriter::stdout(get_color());
+
+        // Clears the screen and scrollback, then moves the cursor to the top left
+        let mut clear = writer.buffer();
+        write!(clear, "\x1B[2J\x1B[3J\x1B[H")?;
+        writer.print(&clear)?;
+
+        let mut totals = FileTotals::default();
+        for file in self.files.values() {
+            writer.print(&file.output)?;
+
+            totals.parse_errors += file.totals.parse_errors;
+            totals.lint_errors += file.totals.lint_errors;
+            totals.lint_warnings += file.totals.lint_warnings;
+        }
+
+        if !self.options.no_summary {
+            log_total(
+                totals.parse_errors,
+                totals.lint_errors,
+                totals.lint_warnings,
+            )?;
+        }
+
+        println!("Watching for changes...");
+
+        Ok(())
+    }
+}
+
+/// Why files can't be watched with these options, if they can't be.
+pub fn unsupported_reason(options: &opts::Options) -> Option<&'static str> {
+    if options.files.iter().any(|filename| filename == "-") {
+        return Some("--watch can't watch stdin");
+    }
+
+    if !matches!(
+        options.display_style(),
+        opts::DisplayStyle::Rich | opts::DisplayStyle::Quiet
+    ) {
+        return Some("--watch only supports the rich and quiet display styles");
+    }
+
+    None
+}
+
+// Waits for changes to stop for a moment, then gives every path that changed since the first event
+fn collect_changes(
+    first: notify::Result<Event>,
+    receiver: &mpsc::Receiver<notify::Result<Event>>,
+) -> HashSet<PathBuf> {
+    let mut changed = HashSet::new();
+
+    for event in
+        std::iter::once(first).chain(std::iter::from_fn(|| receiver.recv_timeout(DEBOUNCE).ok()))
+    {
+        match event {
+            Ok(event) if !matches!(event.kind, EventKind::Access(_)) => {
+                changed.extend(event.paths);
+            }
+
+            Ok(_) => {}
+
+            Err(error) => crate::error(&format!("Error while watching files: {error}")),
+        }
+    }
+
+    changed
+}
+
+// Whether the checkers have to be created again, rather than only checking the changed files
+fn needs_reload(
+    changed: &HashSet<PathBuf>,
+    config_file: Option<&Path>,
+    checkers: &Checkers,
+) -> bool {
+    changed
+        .iter()
+        .any(|path| config_file == Some(path.as_path()) || checkers.is_config_file(path))
+}
+
+/// Checks every file, then checks them again as they change until selene is stopped.
+pub fn watch(options: &opts::Options, checkers: Checkers) -> notify::Result<()> {
+    let (sender, receiver) = mpsc::channel();
+    let mut watcher = notify::recommended_watcher(sender)?;
+
+    let mut inputs = Vec::new();
+    for filename in &options.files {
+        let path = fs::canonicalize(filename)?;
+        watcher.watch(&path, RecursiveMode::Recursive)?;
+        inputs.push(path);
+    }
+
+    // Standard libraries are read from both the current directory and the config's directory
+    let config_file = options.config.as_ref().map(fs::canonicalize).transpose()?;
+
+    let mut config_directories = vec![fs::canonicalize(std::env::current_dir()?)?];
+    if let Some(directory) = config_file.as_ref().and_then(|path| path.parent()) {
+        config_directories.push(directory.to_path_buf());
+    }
+
+    for directory in &config_directories {
+        // Watching an already watched directory again would stop it being watched recursively
+        if !inputs.iter().any(|input| directory.starts_with(input)) {
+            watcher.watch(directory, RecursiveMode::NonRecursive)?;
+        }
+    }
+
+    let mut state = WatchState {
+        options,
+        checkers,
+        files: BTreeMap::new(),
+    };
+
+    state.check_all_files();
+    state.redraw()?;
+
+    while let Ok(event) = receiver.recv() {
+        let changed = collect_changes(event, &receiver);
+        if changed.is_empty() {
+            continue;
+        }
+
+        if needs_reload(&changed, config_file.as_deref(), &state.checkers) {
+            let mut checkers = Checkers::from_options(options);
+            let found = collect_files(options, &mut checkers);
+
+            // The errors have already been printed, so the last output is left alone
+            if checkers.has_failed() {
+                crate::error("Still using the previous config until this is fixed");
+                continue;
+            }
+
+            state.checkers = checkers;
+            state.files.clear();
+            state.check_files(found);
+        } else {
+            state.check_changed_files(&changed);
+        }
+
+        state.redraw()?;
+    }
+
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    use notify::event::{AccessKind, CreateKind, ModifyKind, RemoveKind};
+    use structopt::StructOpt;
+
+    fn event(kind: EventKind, path: &str) -> notify::Result<Event> {
+        Ok(Event::new(kind).add_path(PathBuf::from(path)))
+    }
+
+    fn paths(paths: &[&str]) -> HashSet<PathBuf> {
+        paths.iter().map(PathBuf::from).collect()
+    }
+
+    #[test]
+    fn test_collect_changes() {
+        let (sender, receiver) = mpsc::channel();
+
+        sender
+            .send(event(EventKind::Modify(ModifyKind::Any), "a.lua"))
+            .unwrap();
+        sender
+            .send(event(EventKind::Access(AccessKind::Any), "read.lua"))
+            .unwrap();
+
+        let late_sender = sender.clone();
+        let thread = std::thread::spawn(move || {
+            std::thread::sleep(DEBOUNCE / 2);
+            late_sender
+                .send(event(EventKind::Create(CreateKind::File), "b.lua"))
+                .unwrap();
+
+            std::thread::sleep(DEBOUNCE * 10);
+            late_sender
+                .send(event(EventKind::Remove(RemoveKind::File), "c.lua"))
+                .unwrap();
+        });
+
+        // Changes less than the debounce apart are grouped, and reads are ignored
+        let first = receiver.recv().unwrap();
+        assert_eq!(
+            collect_changes(first, &receiver),
+            paths(&["a.lua", "b.lua"])
+        );
+
+        let next = receiver.recv().unwrap();
+        assert_eq!(collect_changes(next, &receiver), paths(&["c.lua"]));
+
+        thread.join().unwrap();
+    }
+
+    #[test]
+    fn test_needs_reload() {
+        let directory = fs::canonicalize(std::env::temp_dir())
+            .unwrap()
+            .join(format!("selene-watch-reload-{}", std::process::id()));
+        fs::create_dir_all(&directory).unwrap();
+
+        fs::write(
+            directory.join(crate::config::CONFIG_FILE_NAME),
+            "std = \"lua51+custom\"\n",
+        )
+        .unwrap();
+        fs::write(directory.join("custom.yml"), "globals: {}\n").unwrap();
+
+        let mut checkers = Checkers::new(None, false);
+        assert!(checkers.for_file(&directory.join("code.lua")).is_some());
+
+        let changed = |names: &[&str]| {
+            names
+                .iter()
+                .map(|name| directory.join(name))
+                .collect::<HashSet<_>>()
+        };
+
+        assert!(needs_reload(&changed(&["selene.toml"]), None, &checkers));
+        assert!(needs_reload(
+            &changed(&["code.lua", "custom.yml"]),
+            None,
+            &checkers
+        ));
+
+        assert!(!needs_reload(&changed(&["code.lua"]), None, &checkers));
+        assert!(!needs_reload(&changed(&["Cargo.toml"]), None, &checkers));
+
+        // A config given with --config can have any name
+        let other_config = directory.join("other.toml");
+        assert!(needs_reload(
+            &changed(&["other.toml"]),
+            Some(&other_config),
+            &checkers
+        ));
+
+        fs::remove_dir_all(&directory).unwrap();
+    }
+
+    #[test]
+    fn test_unsupported_reason() {
+        let options = |args: &[&str]| {
+            opts::Options::from_iter_safe(["selene", "--watch"].iter().chain(args)).unwrap()
+        };
+
+        assert_eq!(unsupported_reason(&options(&["."])), None);
+        assert_eq!(
+            unsupported_reason(&options(&["--display-style", "quiet", "."])),
+            None
+        );
+
+        assert_eq!(
+            unsupported_reason(&options(&["-"])),
+            Some("--watch can't watch stdin")
+        );
+        assert_eq!(
+            unsupported_reason(&options(&["--display-style", "json", "."])),
+            Some("--watch only supports the rich and quiet display styles")
+        );
+    }
+}
@@ -0,0 +1,307 @@
+//! Output as Checkstyle XML or JUnit XML, which many CI systems can ingest.
+//! Both group diagnostics by file, so diagnostics are collected over the run and
+//! printed all at once at the end.
+
+use std::{collections::BTreeMap, fmt::Write, sync::Mutex};
+
+use codespan_reporting::diagnostic::{Diagnostic as CodespanDiagnostic, Severity};
+
+use crate::json_output::label_to_serializable;
+
+lazy_static::lazy_static! {
+    // BTreeMap keeps the files sorted, since they are linted in parallel
+    static ref FILES: Mutex<BTreeMap<String, Vec<XmlDiagnostic>>> = Mutex::new(BTreeMap::new());
+}
+
+struct XmlDiagnostic {
+    code: String,
+    severity: &'static str,
+    message: String,
+    line: usize,
+    column: usize,
+    start_byte: usize,
+}
+
+impl XmlDiagnostic {
+    fn is_parse_error(&self) -> bool {
+        self.code == "parse_error"
+    }
+}
+
+fn escape(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+
+    for character in text.chars() {
+        match character {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            '\n' => escaped.push_str("&#10;"),
+            '\r' => escaped.push_str("&#13;"),
+            '\t' => escaped.push_str("&#9;"),
+            // Control characters aren't allowed in XML 1.0, even when escaped
+            character if character.is_control() => {}
+            character => escaped.push(character),
+        }
+    }
+
+    escaped
+}
+
+/// Records that a file was checked, so that files without any diagnostics are still reported.
+pub fn record_file(filename: &str) {
+    FILES
+        .lock()
+        .unwrap()
+        .entry(filename.replace('\\', "/"))
+        .or_default();
+}
+
+pub fn push_diagnostic(
+    diagnostic: &CodespanDiagnostic<codespan::FileId>,
+    files: &codespan::Files<&str>,
+) {
+    let label = diagnostic.labels.first().expect("no labels passed");
+    let filename = files
+        .name(label.file_id)
+        .to_string_lossy()
+        .replace('\\', "/");
+    let span = label_to_serializable(&filename, label, files).span;
+
+    let mut message = diagnostic.message.to_owned();
+    for note in &diagnostic.notes {
+        message.push('\n');
+        message.push_str(note);
+    }
+
+    FILES
+        .lock()
+        .unwrap()
+        .entry(filename)
+        .or_default()
+        .push(XmlDiagnostic {
+            code: diagnostic.code.to_owned().unwrap_or_default(),
+            severity: match diagnostic.severity {
+                Severity::Bug | Severity::Error => "error",
+                Severity::Warning => "warning",
+                Severity::Note | Severity::Help => "info",
+            },
+            message,
+            // Both formats start lines and columns at 1
+            line: span.start_line + 1,
+            column: span.start_column + 1,
+            start_byte: label.range.start,
+        });
+}
+
+fn take_files() -> BTreeMap<String, Vec<XmlDiagnostic>> {
+    let mut files = std::mem::take(&mut *FILES.lock().unwrap());
+
+    for diagnostics in files.values_mut() {
+        diagnostics.sort_by_key(|diagnostic| diagnostic.start_byte);
+    }
+
+    files
+}
+
+pub fn checkstyle() -> String {
+    let mut output = String::new();
+
+    writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(output, r#"<checkstyle version="4.3">"#).unwrap();
+
+    for (filename, diagnostics) in take_files() {
+        writeln!(output, r#"  <file name="{}">"#, escape(&filename)).unwrap();
+
+        for diagnostic in diagnostics {
+            writeln!(
+                output,
+                r#"    <error line="{}" column="{}" severity="{}" message="{}" source="selene.{}"/>"#,
+                diagnostic.line,
+                diagnostic.column,
+                diagnostic.severity,
+                escape(&diagnostic.message),
+                escape(&diagnostic.code),
+            )
+            .unwrap();
+        }
+
+        writeln!(output, "  </file>").unwrap();
+    }
+
+    writeln!(output, "</checkstyle>").unwrap();
+
+    output
+}
+
+// Every file is a test suite. A file that was linted cleanly is a single passing test case,
+// otherwise every diagnostic is its own failing test case.
+// Parse errors are reported as errors rather than failures, since linting couldn't happen at all.
+pub fn junit() -> String {
+    let files = take_files();
+
+    let count = |predicate: &dyn Fn(&XmlDiagnostic) -> bool| -> usize {
+        files
+            .values()
+            .map(|diagnostics| diagnostics.iter().filter(|d| predicate(d)).count())
+            .sum()
+    };
+
+    let total_tests: usize = files
+        .values()
+        .map(|diagnostics| diagnostics.len().max(1))
+        .sum();
+
+    let mut output = String::new();
+
+    writeln!(output, r#"<?xml version="1.0" encoding="UTF-8"?>"#).unwrap();
+    writeln!(
+        output,
+        r#"<testsuites name="selene" tests="{}" failures="{}" errors="{}">"#,
+        total_tests,
+        count(&|diagnostic| !diagnostic.is_parse_error()),
+        count(&XmlDiagnostic::is_parse_error),
+    )
+    .unwrap();
+
+    for (filename, diagnostics) in &files {
+        let filename = escape(filename);
+        let errors = diagnostics
+            .iter()
+            .filter(|diagnostic| diagnostic.is_parse_error())
+            .count();
+
+        writeln!(
+            output,
+            r#"  <testsuite name="{filename}" tests="{}" failures="{}" errors="{errors}">"#,
+            diagnostics.len().max(1),
+            diagnostics.len() - errors,
+        )
+        .unwrap();
+
+        if diagnostics.is_empty() {
+            writeln!(
+                output,
+                r#"    <testcase name="selene" classname="{filename}"/>"#
+            )
+            .unwrap();
+        }
+
+        for diagnostic in diagnostics {
+            let element = if diagnostic.is_parse_error() {
+                "error"
+            } else {
+                "failure"
+            };
+
+            writeln!(
+                output,
+                r#"    <testcase name="{}:{}:{}" classname="{filename}">"#,
+                escape(&diagnostic.code),
+                diagnostic.line,
+                diagnostic.column,
+            )
+            .unwrap();
+
+            writeln!(
+                output,
+                r#"      <{element} type="{}" message="{}">{}: {}:{}:{}: {}</{element}>"#,
+                escape(&diagnostic.code),
+                escape(diagnostic.message.lines().next().unwrap_or_default()),
+                diagnostic.severity,
+                filename,
+                diagnostic.line,
+                diagnostic.column,
+                escape(&diagnostic.message),
+            )
+            .unwrap();
+
+            writeln!(output, "    </testcase>").unwrap();
+        }
+
+        writeln!(output, "  </testsuite>").unwrap();
+    }
+
+    writeln!(output, "</testsuites>").unwrap();
+
+    output
+}
+
+pub fn print_checkstyle() {
+    print!("{}", checkstyle());
+}
+
+pub fn print_junit() {
+    print!("{}", junit());
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use codespan_reporting::diagnostic::Label as CodespanLabel;
+
+    // The output is built from global state, so both formats are tested in one test
+    #[test]
+    fn test_xml_output() {
+        let push = || {
+            let mut files = codespan::Files::new();
+            let file_id = files.add("code.lua", "print(1 / 0)\nlocal = 1\n");
+
+            record_file("clean.lua");
+
+            push_diagnostic(
+                &CodespanDiagnostic::warning()
+                    .with_code("divide_by_zero")
+                    .with_message("dividing by zero is not allowed, use math.huge instead")
+                    .with_labels(vec![CodespanLabel::primary(file_id, 6..11)])
+                    .with_notes(vec!["<see docs>".to_owned()]),
+                &files,
+            );
+
+            push_diagnostic(
+                &CodespanDiagnostic::error()
+                    .with_code("parse_error")
+                    .with_message("unexpected token `=`")
+                    .with_labels(vec![CodespanLabel::primary(file_id, 19..20)]),
+                &files,
+            );
+        };
+
+        push();
+        pretty_assertions::assert_eq!(
+            checkstyle(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<checkstyle version="4.3">
+  <file name="clean.lua">
+  </file>
+  <file name="code.lua">
+    <error line="1" column="7" severity="warning" message="dividing by zero is not allowed, use math.huge instead&#10;&lt;see docs&gt;" source="selene.divide_by_zero"/>
+    <error line="2" column="7" severity="error" message="unexpected token `=`" source="selene.parse_error"/>
+  </file>
+</checkstyle>
+"#
+        );
+
+        push();
+        pretty_assertions::assert_eq!(
+            junit(),
+            r#"<?xml version="1.0" encoding="UTF-8"?>
+<testsuites name="selene" tests="3" failures="1" errors="1">
+  <testsuite name="clean.lua" tests="1" failures="0" errors="0">
+    <testcase name="selene" classname="clean.lua"/>
+  </testsuite>
+  <testsuite name="code.lua" tests="2" failures="1" errors="1">
+    <testcase name="divide_by_zero:1:7" classname="code.lua">
+      <failure type="divide_by_zero" message="dividing by zero is not allowed, use math.huge instead">warning: code.lua:1:7: dividing by zero is not allowed, use math.huge instead&#10;&lt;see docs&gt;</failure>
+    </testcase>
+    <testcase name="parse_error:2:7" classname="code.lua">
+      <error type="parse_error" message="unexpected token `=`">error: code.lua:2:7: unexpected token `=`</error>
+    </testcase>
+  </testsuite>
+</testsuites>
+"#
+        );
+    }
+}